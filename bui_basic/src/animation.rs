@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use crate::{construct::CapsuleFill, style::{Color, Stroke}};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseInQuad,
+    EaseOutQuad,
+    EaseInOutQuad,
+    EaseOutCubic,
+}
+
+impl Easing {
+    // t and the result go from 0 to 1
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInQuad => t*t,
+            Easing::EaseOutQuad => t*(2.0-t),
+            Easing::EaseInOutQuad => if t < 0.5 {
+                2.0*t*t
+            } else {
+                -1.0+(4.0-2.0*t)*t
+            },
+            Easing::EaseOutCubic => {
+                let u = 1.0-t;
+                1.0-u*u*u
+            }
+        }
+    }
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::EaseOutQuad
+    }
+}
+
+pub trait Lerp {
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self+(other-self)*t
+    }
+}
+
+impl Lerp for [f32; 2] {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        [self[0].lerp(&other[0], t), self[1].lerp(&other[1], t)]
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        [self[0].lerp(&other[0], t), self[1].lerp(&other[1], t), self[2].lerp(&other[2], t), self[3].lerp(&other[3], t)]
+    }
+}
+
+impl Lerp for Stroke {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Stroke::new(self.color.lerp(&other.color, t), self.width.lerp(&other.width, t))
+    }
+}
+
+impl Lerp for CapsuleFill {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            radius: self.radius.lerp(&other.radius, t),
+            spacing: self.spacing.lerp(&other.spacing, t),
+        }
+    }
+}
+
+// A value that eases towards a target as ticks pass.
+#[derive(Debug, Clone, Copy)]
+pub struct Animated<T> {
+    from: T,
+    to: T,
+    elapsed: Duration,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl<T: Lerp+Clone> Animated<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            from: value.clone(),
+            to: value,
+            elapsed: Duration::ZERO,
+            duration: Duration::ZERO,
+            easing: Easing::Linear,
+        }
+    }
+
+    pub fn get(&self) -> T {
+        if self.is_animating() {
+            let t = self.elapsed.as_secs_f32()/self.duration.as_secs_f32();
+            self.from.lerp(&self.to, self.easing.apply(t))
+        } else {
+            self.to.clone()
+        }
+    }
+
+    pub fn get_target(&self) -> &T {
+        &self.to
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.elapsed < self.duration
+    }
+
+    // starts from the current value, so retargeting mid animation does not jump
+    pub fn animate_to(&mut self, to: T, duration: Duration, easing: Easing) {
+        self.from = self.get();
+        self.to = to;
+        self.elapsed = Duration::ZERO;
+        self.duration = duration;
+        self.easing = easing;
+    }
+
+    // jumps to the value without animating
+    pub fn set(&mut self, value: T) {
+        self.from = value.clone();
+        self.to = value;
+        self.elapsed = Duration::ZERO;
+        self.duration = Duration::ZERO;
+    }
+
+    // returns whether the value changed, so the frame has to be drawn again
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        if self.is_animating() {
+            self.elapsed = (self.elapsed+dt).min(self.duration);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<T: Lerp+Clone+Default> Default for Animated<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 5] = [Easing::Linear, Easing::EaseInQuad, Easing::EaseOutQuad, Easing::EaseInOutQuad, Easing::EaseOutCubic];
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for easing in ALL {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn easings_clamp_outside_the_range() {
+        for easing in ALL {
+            assert_eq!(easing.apply(-1.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(2.0), 1.0, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn easings_meet_halfway_where_symmetric() {
+        assert_eq!(Easing::Linear.apply(0.5), 0.5);
+        assert_eq!(Easing::EaseInOutQuad.apply(0.5), 0.5);
+        assert!(Easing::EaseInQuad.apply(0.5) < 0.5);
+        assert!(Easing::EaseOutQuad.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn tick_reports_the_last_step_then_stops() {
+        let mut value = Animated::new(0.0);
+        value.animate_to(1.0, Duration::from_millis(100), Easing::Linear);
+
+        assert!(value.tick(Duration::from_millis(50)));
+        assert_eq!(value.get(), 0.5);
+        // overshooting the end still changes the value once
+        assert!(value.tick(Duration::from_millis(80)));
+        assert_eq!(value.get(), 1.0);
+        assert!(!value.is_animating());
+        assert!(!value.tick(Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn zero_duration_does_not_animate() {
+        let mut value = Animated::new(0.0);
+        value.animate_to(1.0, Duration::ZERO, Easing::Linear);
+        assert_eq!(value.get(), 1.0);
+        assert!(!value.tick(Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn retargeting_starts_from_the_current_value() {
+        let mut value = Animated::new(0.0);
+        value.animate_to(1.0, Duration::from_millis(100), Easing::Linear);
+        value.tick(Duration::from_millis(50));
+
+        value.animate_to(0.0, Duration::from_millis(100), Easing::Linear);
+        assert_eq!(value.get(), 0.5);
+        assert_eq!(*value.get_target(), 0.0);
+        value.tick(Duration::from_millis(50));
+        assert_eq!(value.get(), 0.25);
+    }
+
+    #[test]
+    fn set_jumps_without_animating() {
+        let mut value = Animated::new(0.0);
+        value.animate_to(1.0, Duration::from_millis(100), Easing::Linear);
+        value.set(0.25);
+        assert_eq!(value.get(), 0.25);
+        assert!(!value.tick(Duration::from_millis(16)));
+    }
+}
\ No newline at end of file
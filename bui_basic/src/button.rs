@@ -1,6 +1,8 @@
-use bui::rect::{SizeAndCenter, Points};
+use std::time::Duration;
 
-use crate::{construct::Construct, containers::{Fill, Init, GetHeight, TranslateY, TranslateX}, signal::{SignalReciever, ResizedSignal, CursorMovedSignal, MouseLeftDownSignal, MouseLeftUpSignal, CharacterInputSignal}};
+use bui::{rect::{SizeAndCenter, Points}, line::LineRaw, freeform_2dcapsule::Freeform2DCapsule};
+
+use crate::{construct::{Construct, StandardConstructTarget, LineTarget, Freeform2DCapsuleTarget, ShapeTarget, CapsuleFill, fill_capsules}, containers::{Fill, Init, GetHeight, TranslateY, TranslateX, GetPointBounds}, signal::{SignalReciever, ignore_signals, ShortCircuitingCallback, Handled, Propagating, Phase, ResizedSignal, ScaleFactorSignal, CursorMovedSignal, MouseLeftDownSignal, MouseLeftUpSignal, MouseButtonSignal, MouseButton, ButtonState, TickSignal, RedrawCallback, ReconstructCallback}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, RetainedId, IntoPrimitives}, style::{ConstructStyled, Theme, Stroke, StyledLineTarget}, draw_list::DrawList, animation::{Animated, Easing, Lerp}};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PressState {
@@ -9,31 +11,392 @@ pub enum PressState {
     Pressed,
 }
 
+// lengths are in normalized units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStyle {
+    // how far the frame sits inside the button bounds
+    pub inset: f32,
+    pub border: bool,
+    pub background: Option<CapsuleFill>,
+}
+
+impl FrameStyle {
+    pub const NONE: Self = Self {
+        inset: 0.0,
+        border: false,
+        background: None,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonFrame {
+    pub none: FrameStyle,
+    pub hovered: FrameStyle,
+    pub pressed: FrameStyle,
+    pub disabled: FrameStyle,
+}
+
+impl ButtonFrame {
+    // only the child is drawn
+    pub const NONE: Self = Self {
+        none: FrameStyle::NONE,
+        hovered: FrameStyle::NONE,
+        pressed: FrameStyle::NONE,
+        disabled: FrameStyle::NONE,
+    };
+
+    pub const DEFAULT: Self = Self {
+        none: FrameStyle {
+            inset: 0.0,
+            border: true,
+            background: None,
+        },
+        hovered: FrameStyle {
+            inset: 0.0,
+            border: true,
+            background: Some(CapsuleFill {
+                radius: 0.002,
+                spacing: 0.012,
+            }),
+        },
+        pressed: FrameStyle {
+            inset: 0.006,
+            border: true,
+            background: Some(CapsuleFill::solid(0.004)),
+        },
+        disabled: FrameStyle {
+            inset: 0.0,
+            border: false,
+            background: Some(CapsuleFill {
+                radius: 0.001,
+                spacing: 0.024,
+            }),
+        },
+    };
+
+    pub fn for_state(&self, press_state: PressState, disabled: bool) -> FrameStyle {
+        if disabled {
+            self.disabled
+        } else {
+            match press_state {
+                PressState::None => self.none,
+                PressState::Hovered => self.hovered,
+                PressState::Pressed => self.pressed,
+            }
+        }
+    }
+}
+
+impl Default for ButtonFrame {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// a background that appears or disappears grows from or shrinks to nothing
+impl Lerp for FrameStyle {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            inset: self.inset.lerp(&other.inset, t),
+            border: if t < 0.5 {
+                self.border
+            } else {
+                other.border
+            },
+            background: match (self.background, other.background) {
+                (Some(from), Some(to)) => Some(from.lerp(&to, t)),
+                (Some(from), None) => Some(CapsuleFill {
+                    radius: from.radius*(1.0-t),
+                    spacing: from.spacing,
+                }),
+                (None, Some(to)) => Some(CapsuleFill {
+                    radius: to.radius*t,
+                    spacing: to.spacing,
+                }),
+                (None, None) => None
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Button<T> {
     child: T,
     press_state: PressState,
+    disabled: bool,
+    frame: ButtonFrame,
+    // eases between the styles of the press states
+    frame_style: Animated<FrameStyle>,
+    transition: Duration,
+    // the press state or the frame changed since the last construct
+    frame_dirty: bool,
+    retained_id: RetainedId,
+    // the pass along the hit path in which the button reacts to propagating signals
+    phase: Phase,
     fill_area: SizeAndCenter,
     points: Points,
 }
 
 impl<T> Button<T> {
+    // styles switch at once unless set_transition opts in to animating them
+    pub const DEFAULT_TRANSITION: Duration = Duration::ZERO;
+
     pub fn new(child: T, fill_area: SizeAndCenter) -> Self {
+        Self::new_with_frame(child, fill_area, ButtonFrame::DEFAULT)
+    }
+
+    pub fn new_with_frame(child: T, fill_area: SizeAndCenter, frame: ButtonFrame) -> Self {
         Self {
             child,
             press_state: PressState::None,
+            disabled: false,
+            frame,
+            frame_style: Animated::new(frame.none),
+            transition: Self::DEFAULT_TRANSITION,
+            frame_dirty: true,
+            retained_id: RetainedId::new(),
+            phase: Phase::Bubble,
             fill_area,
             points: fill_area.into(),
         }
     }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    pub fn get_press_state(&self) -> PressState {
+        self.press_state
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    // a disabled button drops its press state and ignores input until enabled again
+    pub fn set_disabled(&mut self, disabled: bool) {
+        if self.disabled != disabled {
+            self.disabled = disabled;
+            self.press_state = PressState::None;
+            self.animate_frame_style();
+        }
+    }
+
+    pub fn get_frame(&self) -> &ButtonFrame {
+        &self.frame
+    }
+
+    pub fn set_frame(&mut self, frame: ButtonFrame) {
+        self.frame = frame;
+        self.frame_style.set(frame.for_state(self.press_state, self.disabled));
+        self.frame_dirty = true;
+    }
+
+    pub fn get_phase(&self) -> Phase {
+        self.phase
+    }
+
+    // in the capture phase the button can take a press before the widgets inside it see it
+    pub fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    // a zero duration switches styles without animating
+    pub fn set_transition(&mut self, transition: Duration) {
+        self.transition = transition;
+    }
+
+    fn set_press_state(&mut self, press_state: PressState) -> PressStateCallback {
+        self.press_state = press_state;
+        self.animate_frame_style();
+        PressStateCallback::Update(press_state)
+    }
+
+    fn animate_frame_style(&mut self) {
+        self.frame_style.animate_to(self.frame.for_state(self.press_state, self.disabled), self.transition, Easing::EaseOutQuad);
+        self.frame_dirty = true;
+    }
+
+    fn get_stroke(&self, theme: &Theme) -> Stroke {
+        let stroke = theme.button.for_state(self.press_state);
+        if self.disabled {
+            stroke.with_alpha(theme.disabled_alpha)
+        } else {
+            stroke
+        }
+    }
+
+    fn get_frame_style(&self) -> FrameStyle {
+        self.frame_style.get()
+    }
+
+    fn get_frame_points(&self, style: &FrameStyle) -> Points {
+        Points {
+            p1x: self.points.p1x+style.inset,
+            p1y: self.points.p1y-style.inset,
+            p2x: self.points.p2x-style.inset,
+            p2y: self.points.p2y+style.inset,
+        }
+    }
+
+    fn frame_lines(&self) -> Vec<LineRaw> {
+        let style = self.get_frame_style();
+        if !style.border {
+            return Vec::with_capacity(0);
+        }
+        let points = self.get_frame_points(&style);
+        vec![
+            LineRaw {
+                p1: [points.p1x, points.p2y],
+                p2: [points.p1x, points.p1y],
+            },
+            LineRaw {
+                p1: [points.p1x, points.p1y],
+                p2: [points.p2x, points.p1y],
+            },
+            LineRaw {
+                p1: [points.p2x, points.p1y],
+                p2: [points.p2x, points.p2y],
+            },
+            LineRaw {
+                p1: [points.p2x, points.p2y],
+                p2: [points.p1x, points.p2y],
+            }
+        ]
+    }
+
+    fn frame_capsules(&self) -> Vec<Freeform2DCapsule> {
+        let style = self.get_frame_style();
+        match style.background {
+            Some(background) => fill_capsules(self.get_frame_points(&style), background),
+            None => Vec::with_capacity(0)
+        }
+    }
+
+    fn take_own_propagating_signal<S, R>(&mut self, signal: &mut Propagating<S>, consume: bool) -> R
+    where
+        Self: SignalReciever<S, R>
+    {
+        let press_state = self.press_state;
+        let callback = <Self as SignalReciever<S, R>>::take_signal(self, &mut signal.signal);
+        if consume && self.press_state != press_state {
+            signal.set_handled();
+        }
+        callback
+    }
+
+    fn take_propagating_signal<S, R: ShortCircuitingCallback>(&mut self, signal: &mut Propagating<S>, consume: bool) -> R
+    where
+        T: SignalReciever<Propagating<S>, R>,
+        Self: SignalReciever<S, R>
+    {
+        let reacts = signal.get_phase() == self.phase;
+        match signal.get_phase() {
+            Phase::Capture => {
+                if !reacts {
+                    return self.child.take_signal(signal);
+                }
+                let callback = self.take_own_propagating_signal(signal, consume);
+                if signal.is_handled() {
+                    callback
+                } else {
+                    callback.or_into(self.child.take_signal(signal))
+                }
+            },
+            Phase::Bubble => {
+                let callback = self.child.take_signal(signal);
+                if signal.is_handled() || !reacts {
+                    callback
+                } else {
+                    callback.or_into(self.take_own_propagating_signal(signal, consume))
+                }
+            }
+        }
+    }
 }
 
 impl<C, T: Construct<C>> Construct<C> for Button<T> {
-    fn construct(&self) -> C {
+    default fn construct(&self) -> C {
         self.child.construct()
     }
 }
 
+impl<T: Construct<LineTarget>> Construct<LineTarget> for Button<T> {
+    fn construct(&self) -> LineTarget {
+        LineTarget(self.frame_lines()).append_into(self.child.construct())
+    }
+}
+
+// the background goes first so the child draws over it
+impl<T: Construct<Freeform2DCapsuleTarget>> Construct<Freeform2DCapsuleTarget> for Button<T> {
+    fn construct(&self) -> Freeform2DCapsuleTarget {
+        Freeform2DCapsuleTarget(self.frame_capsules()).append_into(self.child.construct())
+    }
+}
+
+impl<T: Construct<ShapeTarget>> Construct<ShapeTarget> for Button<T> {
+    fn construct(&self) -> ShapeTarget {
+        ShapeTarget::new(LineTarget(self.frame_lines()), Freeform2DCapsuleTarget(self.frame_capsules())).append_into(self.child.construct())
+    }
+}
+
+impl<T: Construct<DrawList>> Construct<DrawList> for Button<T> {
+    fn construct(&self) -> DrawList {
+        let mut target = DrawList::EMPTY;
+        target.push_capsules(self.frame_capsules());
+        target.push_lines(self.frame_lines());
+        target.append_into(self.child.construct())
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for Button<T> {
+    default fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(&theme.with_text(self.get_stroke(theme)))
+    }
+}
+
+impl<T: ConstructStyled<StyledLineTarget>> ConstructStyled<StyledLineTarget> for Button<T> {
+    fn construct_styled(&self, theme: &Theme) -> StyledLineTarget {
+        let stroke = self.get_stroke(theme);
+        StyledLineTarget::from_lines(&self.frame_lines(), stroke).append_into(self.child.construct_styled(&theme.with_text(stroke)))
+    }
+}
+
+impl<T: ConstructStyled<DrawList>> ConstructStyled<DrawList> for Button<T> {
+    fn construct_styled(&self, theme: &Theme) -> DrawList {
+        let stroke = self.get_stroke(theme);
+        let mut target = DrawList::EMPTY;
+        target.set_stroke(stroke);
+        target.push_capsules(self.frame_capsules());
+        target.push_lines(self.frame_lines());
+        target.append_into(self.child.construct_styled(&theme.with_text(stroke)))
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for Button<T> {
+    default fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<T: RetainedConstruct<LineTarget>> RetainedConstruct<LineTarget> for Button<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<LineTarget>) {
+        target.write_slot(&self.retained_id, self.frame_dirty, || LineTarget(self.frame_lines()));
+        self.child.construct_retained(target);
+    }
+}
+
+impl<T: RetainedConstruct<Freeform2DCapsuleTarget>> RetainedConstruct<Freeform2DCapsuleTarget> for Button<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<Freeform2DCapsuleTarget>) {
+        target.write_slot(&self.retained_id, self.frame_dirty, || Freeform2DCapsuleTarget(self.frame_capsules()));
+        self.child.construct_retained(target);
+    }
+}
+
 impl<T: Fill> Init for Button<T> {
     fn init(&mut self) {
         self.child.fill(self.fill_area);
@@ -44,15 +407,40 @@ impl<T: Fill> Fill for Button<T> {
     fn fill(&mut self, fill_target: SizeAndCenter) {
         self.fill_area = fill_target;
         self.points = self.fill_area.into();
+        self.frame_dirty = true;
         self.child.fill(fill_target);
     }
 }
 
+impl<T: Measure> Measure for Button<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.child.measure(constraints)
+    }
+}
+
+// the child fills the button, so its height cannot change
+impl<T: Dirty> Dirty for Button<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty().or(DirtyFlags::new(false, self.frame_dirty))
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.child.update_layout();
+        0.0
+    }
+
+    fn clear_dirty(&mut self) {
+        self.frame_dirty = false;
+        self.child.clear_dirty();
+    }
+}
+
 impl <T: TranslateX> TranslateX for Button<T> {
     fn translate_x(&mut self, dx: f32) {
         self.fill_area.cx += dx;
-        self.points.p1y += dx;
-        self.points.p2y += dx;
+        self.points.p1x += dx;
+        self.points.p2x += dx;
+        self.frame_dirty = true;
         self.child.translate_x(dx);
     }
 }
@@ -62,6 +450,7 @@ impl <T: TranslateY> TranslateY for Button<T> {
         self.fill_area.cy += dy;
         self.points.p1y += dy;
         self.points.p2y += dy;
+        self.frame_dirty = true;
         self.child.translate_y(dy);
     }
 }
@@ -72,28 +461,57 @@ impl<T> GetHeight for Button<T> {
     }
 }
 
+impl<T> GetPointBounds for Button<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.points
+    }
+}
+
+impl<T: HitTest> HitTest for Button<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        if self.points.contains(x, y) {
+            self.child.hit_test(x, y, path);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<S, R, T> RouteSignal<S, R> for Button<T>
+where
+    Button<T>: SignalReciever<S, R>
+{
+    fn route_signal(&mut self, _path: &[usize], signal: &mut S) -> R {
+        self.take_signal(signal)
+    }
+}
+
 impl<T: SignalReciever<ResizedSignal, R>, R> SignalReciever<ResizedSignal, R> for Button<T> {
     fn take_signal(&mut self, signal: &mut ResizedSignal) -> R {
         self.child.take_signal(signal)
     }
 }
 
+impl<T: SignalReciever<ScaleFactorSignal, R>, R> SignalReciever<ScaleFactorSignal, R> for Button<T> {
+    fn take_signal(&mut self, signal: &mut ScaleFactorSignal) -> R {
+        self.child.take_signal(signal)
+    }
+}
+
 impl<T> SignalReciever<CursorMovedSignal, PressStateCallback> for Button<T> {
     fn take_signal(&mut self, signal: &mut CursorMovedSignal) -> PressStateCallback {
+        if self.disabled {
+            return PressStateCallback::NoChange;
+        }
         if self.points.contains(signal.norm_posx, signal.norm_posy) {
             match self.press_state {
                 PressState::Hovered | PressState::Pressed => PressStateCallback::NoChange,
-                PressState::None => {
-                    self.press_state = PressState::Hovered;
-                    PressStateCallback::Update(self.press_state)
-                }
+                PressState::None => self.set_press_state(PressState::Hovered)
             }
         } else {
             match self.press_state {
-                PressState::Hovered | PressState::Pressed => {
-                    self.press_state = PressState::None;
-                    PressStateCallback::Update(self.press_state)
-                },
+                PressState::Hovered | PressState::Pressed => self.set_press_state(PressState::None),
                 PressState::None => PressStateCallback::NoChange
             }
         }
@@ -111,10 +529,7 @@ impl<T> SignalReciever<MouseLeftDownSignal, PressStateCallback> for Button<T> {
     fn take_signal(&mut self, _signal: &mut MouseLeftDownSignal) -> PressStateCallback {
         match self.press_state {
             PressState::None | PressState::Pressed => PressStateCallback::NoChange,
-            PressState::Hovered => {
-                self.press_state = PressState::Pressed;
-                PressStateCallback::Update(self.press_state)
-            }
+            PressState::Hovered => self.set_press_state(PressState::Pressed)
         }
     }
 }
@@ -129,7 +544,7 @@ impl<T, R: Default> SignalReciever<MouseLeftDownSignal, R> for Button<T> {
 impl<T> SignalReciever<MouseLeftUpSignal, ClickedCallback> for Button<T> {
     fn take_signal(&mut self, _signal: &mut MouseLeftUpSignal) -> ClickedCallback {
         if self.press_state == PressState::Pressed {
-            self.press_state = PressState::Hovered; // callback reciever should know this changed based on the ClickedCallback
+            self.set_press_state(PressState::Hovered); // callback reciever should know this changed based on the ClickedCallback
             ClickedCallback::Clicked
         } else {
             ClickedCallback::NoClick
@@ -144,9 +559,62 @@ impl<T, R: Default> SignalReciever<MouseLeftUpSignal, R> for Button<T> {
     }
 }
 
-impl<T, R: Default> SignalReciever<CharacterInputSignal, R> for Button<T> {
-    fn take_signal(&mut self, _signal: &mut CharacterInputSignal) -> R {
-        R::default()
+impl<T, R: Default> SignalReciever<MouseButtonSignal, R> for Button<T>
+where
+    Button<T>: SignalReciever<MouseLeftDownSignal, R>+SignalReciever<MouseLeftUpSignal, R>
+{
+    fn take_signal(&mut self, signal: &mut MouseButtonSignal) -> R {
+        match (signal.button, signal.state) {
+            (MouseButton::Left, ButtonState::Pressed) => <Self as SignalReciever<MouseLeftDownSignal, R>>::take_signal(self, &mut MouseLeftDownSignal()),
+            (MouseButton::Left, ButtonState::Released) => <Self as SignalReciever<MouseLeftUpSignal, R>>::take_signal(self, &mut MouseLeftUpSignal()),
+            _ => R::default()
+        }
+    }
+}
+
+impl<R: ShortCircuitingCallback, T: SignalReciever<Propagating<CursorMovedSignal>, R>> SignalReciever<Propagating<CursorMovedSignal>, R> for Button<T>
+where
+    Button<T>: SignalReciever<CursorMovedSignal, R>
+{
+    fn take_signal(&mut self, signal: &mut Propagating<CursorMovedSignal>) -> R {
+        self.take_propagating_signal(signal, false)
+    }
+}
+
+impl<R: ShortCircuitingCallback, T: SignalReciever<Propagating<MouseLeftDownSignal>, R>> SignalReciever<Propagating<MouseLeftDownSignal>, R> for Button<T>
+where
+    Button<T>: SignalReciever<MouseLeftDownSignal, R>
+{
+    fn take_signal(&mut self, signal: &mut Propagating<MouseLeftDownSignal>) -> R {
+        self.take_propagating_signal(signal, true)
+    }
+}
+
+impl<R: ShortCircuitingCallback, T: SignalReciever<Propagating<MouseLeftUpSignal>, R>> SignalReciever<Propagating<MouseLeftUpSignal>, R> for Button<T>
+where
+    Button<T>: SignalReciever<MouseLeftUpSignal, R>
+{
+    fn take_signal(&mut self, signal: &mut Propagating<MouseLeftUpSignal>) -> R {
+        self.take_propagating_signal(signal, true)
+    }
+}
+
+ignore_signals!([T] Button<T>: ReleasedElsewhereSignal, ClickSignal, ScrollSignal, FlingSignal, PinchSignal, CharacterInputSignal, ImeSignal, KeySignal);
+
+impl<T: SignalReciever<TickSignal, RedrawCallback>> SignalReciever<TickSignal, RedrawCallback> for Button<T> {
+    fn take_signal(&mut self, signal: &mut TickSignal) -> RedrawCallback {
+        let animating = self.frame_style.tick(signal.dt);
+        if animating {
+            self.frame_dirty = true;
+        }
+        RedrawCallback::new(animating).or_into(self.child.take_signal(signal))
+    }
+}
+
+impl<C, T: SignalReciever<TickSignal, RedrawCallback>> SignalReciever<TickSignal, ReconstructCallback<C>> for Button<T> {
+    fn take_signal(&mut self, signal: &mut TickSignal) -> ReconstructCallback<C> {
+        let redraw_callback: RedrawCallback = self.take_signal(signal);
+        redraw_callback.into()
     }
 }
 
@@ -159,4 +627,58 @@ pub enum PressStateCallback {
 pub enum ClickedCallback {
     NoClick,
     Clicked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Block;
+
+    fn tick(button: &mut Button<Block>, ms: u64) -> bool {
+        let redraw: RedrawCallback = button.take_signal(&mut TickSignal {
+            dt: Duration::from_millis(ms),
+            now: Duration::ZERO,
+        });
+        redraw.get_redraw()
+    }
+
+    #[test]
+    fn frame_stays_dirty_until_the_transition_ends() {
+        let mut button = Button::new(Block::new(0.0, 0.0), SizeAndCenter {
+            sx: 0.5,
+            sy: 0.5,
+            cx: 0.0,
+            cy: 0.0,
+        });
+        button.set_transition(Duration::from_millis(120));
+        button.clear_dirty();
+        button.set_disabled(true);
+        assert!(button.get_dirty().construct);
+
+        button.clear_dirty();
+        assert!(tick(&mut button, 60));
+        assert!(button.get_dirty().construct);
+
+        button.clear_dirty();
+        assert!(tick(&mut button, 100));
+        assert!(button.get_dirty().construct);
+
+        button.clear_dirty();
+        assert!(!tick(&mut button, 16));
+        assert!(!button.get_dirty().construct);
+    }
+
+    #[test]
+    fn styles_switch_at_once_by_default() {
+        let mut button = Button::new(Block::new(0.0, 0.0), SizeAndCenter {
+            sx: 0.5,
+            sy: 0.5,
+            cx: 0.0,
+            cy: 0.0,
+        });
+        button.set_disabled(true);
+        assert_eq!(button.get_frame_style(), ButtonFrame::DEFAULT.disabled);
+        button.clear_dirty();
+        assert!(!tick(&mut button, 16));
+    }
 }
\ No newline at end of file
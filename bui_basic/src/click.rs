@@ -0,0 +1,317 @@
+use std::time::Duration;
+
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::Construct, containers::{Fill, FillWidth, Init, TranslateX, TranslateY, GetPointBounds}, signal::{SignalReciever, forward_signals, ShortCircuitingCallback, CursorMovedSignal, MouseLeftDownSignal, MouseButtonSignal, MouseButton, ButtonState, ClickSignal, ClickEvent}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}};
+
+#[derive(Debug, Clone, Copy)]
+struct LastClick {
+    button: MouseButton,
+    timestamp: Duration,
+    pixel_posx: f32,
+    pixel_posy: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClickCounter {
+    max_interval: Duration,
+    max_distance: f32,
+    last_click: Option<LastClick>,
+    count: u32,
+}
+
+impl ClickCounter {
+    pub const DEFAULT_MAX_INTERVAL: Duration = Duration::from_millis(500);
+    pub const DEFAULT_MAX_DISTANCE: f32 = 4.0;
+
+    pub fn new() -> Self {
+        Self::new_with_thresholds(Self::DEFAULT_MAX_INTERVAL, Self::DEFAULT_MAX_DISTANCE)
+    }
+
+    // max_distance is in pixels
+    pub fn new_with_thresholds(max_interval: Duration, max_distance: f32) -> Self {
+        Self {
+            max_interval,
+            max_distance,
+            last_click: None,
+            count: 0,
+        }
+    }
+
+    pub fn set_max_interval(&mut self, max_interval: Duration) {
+        self.max_interval = max_interval;
+    }
+
+    pub fn set_max_distance(&mut self, max_distance: f32) {
+        self.max_distance = max_distance;
+    }
+
+    pub fn reset(&mut self) {
+        self.last_click = None;
+        self.count = 0;
+    }
+
+    // returns how many clicks in a row this press makes
+    pub fn register(&mut self, button: MouseButton, timestamp: Duration, pixel_posx: f32, pixel_posy: f32) -> u32 {
+        let continues = match self.last_click {
+            Some(last_click) => {
+                let dx = pixel_posx-last_click.pixel_posx;
+                let dy = pixel_posy-last_click.pixel_posy;
+                // without a known cursor position only the timing can tell clicks apart
+                let near = dx.is_nan() || dy.is_nan() || (dx*dx+dy*dy).sqrt() <= self.max_distance;
+                last_click.button == button
+                    && timestamp >= last_click.timestamp
+                    && timestamp-last_click.timestamp <= self.max_interval
+                    && near
+            },
+            None => false
+        };
+
+        self.count = if continues && self.count < 3 {
+            self.count+1
+        } else {
+            1
+        };
+        self.last_click = Some(LastClick {
+            button,
+            timestamp,
+            pixel_posx,
+            pixel_posy,
+        });
+        self.count
+    }
+}
+
+impl Default for ClickCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn click_count_to_event(count: u32) -> Option<ClickEvent> {
+    match count {
+        2 => Some(ClickEvent::DoubleClick),
+        3 => Some(ClickEvent::TripleClick),
+        _ => None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClickDetector<T> {
+    child: T,
+    click_counter: ClickCounter,
+    cursor: CursorMovedSignal,
+}
+
+impl<T> ClickDetector<T> {
+    pub fn new(child: T) -> Self {
+        Self::new_with_counter(child, ClickCounter::new())
+    }
+
+    pub fn new_with_counter(child: T, click_counter: ClickCounter) -> Self {
+        Self {
+            child,
+            click_counter,
+            cursor: CursorMovedSignal::OCCLUDED,
+        }
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    pub fn get_click_counter_mut(&mut self) -> &mut ClickCounter {
+        &mut self.click_counter
+    }
+}
+
+impl<T: Init> Init for ClickDetector<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
+}
+
+impl<T: Fill> Fill for ClickDetector<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.child.fill(fill_target);
+    }
+}
+
+impl<T: FillWidth> FillWidth for ClickDetector<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        self.child.fill_width(sx, cx, ty)
+    }
+}
+
+impl<T: Dirty> Dirty for ClickDetector<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.child.update_layout()
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<T: Measure> Measure for ClickDetector<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.child.measure(constraints)
+    }
+}
+
+impl<T: TranslateX> TranslateX for ClickDetector<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.child.translate_x(dx);
+    }
+}
+
+impl<T: TranslateY> TranslateY for ClickDetector<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.child.translate_y(dy);
+    }
+}
+
+impl<T: GetPointBounds> GetPointBounds for ClickDetector<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.child.get_point_bounds()
+    }
+}
+
+impl<T: HitTest> HitTest for ClickDetector<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for ClickDetector<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for ClickDetector<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for ClickDetector<T> {
+    fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for ClickDetector<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
+impl<R, T: SignalReciever<CursorMovedSignal, R>> SignalReciever<CursorMovedSignal, R> for ClickDetector<T> {
+    fn take_signal(&mut self, signal: &mut CursorMovedSignal) -> R {
+        self.cursor = *signal;
+        self.child.take_signal(signal)
+    }
+}
+
+impl<R: ShortCircuitingCallback, T: SignalReciever<MouseButtonSignal, R>+SignalReciever<ClickSignal, R>> SignalReciever<MouseButtonSignal, R> for ClickDetector<T> {
+    fn take_signal(&mut self, signal: &mut MouseButtonSignal) -> R {
+        let click_event = match (signal.state, signal.timestamp) {
+            (ButtonState::Pressed, Some(timestamp)) => click_count_to_event(self.click_counter.register(signal.button, timestamp, self.cursor.pixel_posx, self.cursor.pixel_posy)),
+            _ => None
+        };
+
+        let callback = <T as SignalReciever<MouseButtonSignal, R>>::take_signal(&mut self.child, signal);
+        match click_event {
+            Some(event) => callback.or_into(self.child.take_signal(&mut ClickSignal {
+                event,
+                button: signal.button,
+                norm_posx: self.cursor.norm_posx,
+                norm_posy: self.cursor.norm_posy,
+            })),
+            None => callback
+        }
+    }
+}
+
+impl<R, T: SignalReciever<MouseLeftDownSignal, R>> SignalReciever<MouseLeftDownSignal, R> for ClickDetector<T> {
+    fn take_signal(&mut self, signal: &mut MouseLeftDownSignal) -> R {
+        self.click_counter.reset();
+        self.child.take_signal(signal)
+    }
+}
+
+forward_signals!(ClickDetector.child: MouseLeftUpSignal, ClickSignal, ResizedSignal, ScaleFactorSignal, ScrollSignal, FlingSignal, PinchSignal, CharacterInputSignal, ImeSignal, TickSignal, KeySignal);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{construct::LineTarget, signal::ReconstructCallback, test_util::Block};
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn quick_clicks_in_place_count_up() {
+        let mut counter = ClickCounter::new();
+        assert_eq!(counter.register(MouseButton::Left, ms(0), 10.0, 10.0), 1);
+        assert_eq!(counter.register(MouseButton::Left, ms(200), 10.0, 10.0), 2);
+        assert_eq!(counter.register(MouseButton::Left, ms(400), 10.0, 10.0), 3);
+    }
+
+    #[test]
+    fn fourth_click_starts_over() {
+        let mut counter = ClickCounter::new();
+        for (i, expected) in [1, 2, 3, 1, 2].into_iter().enumerate() {
+            assert_eq!(counter.register(MouseButton::Left, ms(100*i as u64), 0.0, 0.0), expected);
+        }
+    }
+
+    #[test]
+    fn slow_click_starts_over() {
+        let mut counter = ClickCounter::new_with_thresholds(ms(300), 4.0);
+        counter.register(MouseButton::Left, ms(0), 0.0, 0.0);
+        assert_eq!(counter.register(MouseButton::Left, ms(300), 0.0, 0.0), 2);
+        assert_eq!(counter.register(MouseButton::Left, ms(601), 0.0, 0.0), 1);
+    }
+
+    #[test]
+    fn distant_click_starts_over() {
+        let mut counter = ClickCounter::new_with_thresholds(ms(500), 4.0);
+        counter.register(MouseButton::Left, ms(0), 0.0, 0.0);
+        assert_eq!(counter.register(MouseButton::Left, ms(100), 2.0, 2.0), 2);
+        assert_eq!(counter.register(MouseButton::Left, ms(200), 2.0, 7.0), 1);
+    }
+
+    #[test]
+    fn other_button_starts_over() {
+        let mut counter = ClickCounter::new();
+        counter.register(MouseButton::Left, ms(0), 0.0, 0.0);
+        assert_eq!(counter.register(MouseButton::Right, ms(100), 0.0, 0.0), 1);
+    }
+
+    #[test]
+    fn unknown_cursor_counts_by_time_only() {
+        let mut counter = ClickCounter::new();
+        counter.register(MouseButton::Left, ms(0), f32::NAN, f32::NAN);
+        assert_eq!(counter.register(MouseButton::Left, ms(100), f32::NAN, f32::NAN), 2);
+    }
+
+    #[test]
+    fn detector_reports_a_double_click_before_any_cursor_move() {
+        let mut detector = ClickDetector::new(Block::new(0.0, 0.0));
+        for timestamp in [ms(0), ms(100)] {
+            let _: ReconstructCallback<LineTarget> = detector.take_signal(&mut MouseButtonSignal::new_with_timestamp(MouseButton::Left, ButtonState::Pressed, timestamp));
+        }
+        assert_eq!(detector.get_child().received, vec!["MouseButtonSignal", "MouseButtonSignal", "ClickSignal"]);
+    }
+}
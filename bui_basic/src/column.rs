@@ -1,4 +1,4 @@
-use crate::{construct::{ Construct, StandardConstructTarget }, containers::{Init, GetHeight, SetCenterTopPosition, Fill, FillWidth, TranslateX, TranslateY}, signal::{SignalReciever, ReconstructCallback}};
+use crate::{construct::{ Construct, StandardConstructTarget }, containers::{Init, GetHeight, SetCenterTopPosition, Fill, FillWidth, TranslateX, TranslateY}, signal::{SignalReciever, ReconstructCallback, Handled, RedrawCallback, ShortCircuitingCallback}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, Size, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}};
 
 #[derive(Debug, Clone)]
 pub struct VecColumn<T> {
@@ -69,6 +69,28 @@ impl<C: StandardConstructTarget, T: Construct<C>> Construct<C> for VecColumn<T>
     }
 }
 
+impl<C: StandardConstructTarget, T: ConstructStyled<C>> ConstructStyled<C> for VecColumn<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        if self.children.len() == 0 {
+            C::EMPTY
+        } else {
+            let mut construct_target = self.children[0].construct_styled(theme);
+            for i in 1..self.children.len() {
+                construct_target.append(self.children[i].construct_styled(theme));
+            }
+            construct_target
+        }
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for VecColumn<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        for child in &self.children {
+            child.construct_retained(target);
+        }
+    }
+}
+
 // impl<T: GetHeight+SetCenterTopPosition+Init> Init for VecColumn<T> {
 impl<T: Init> Init for VecColumn<T> {
     fn init(&mut self) {
@@ -91,6 +113,52 @@ impl<T: FillWidth> FillWidth for VecColumn<T> {
     }
 }
 
+impl<T: Measure> Measure for VecColumn<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let mut size_hint = SizeHint::ZERO;
+        let mut remaining = constraints;
+        for child in &self.children {
+            let child_hint = child.measure(remaining);
+            remaining = remaining.shrink(0.0, child_hint.preferred.height);
+            size_hint = SizeHint {
+                min: stack(size_hint.min, child_hint.min),
+                preferred: stack(size_hint.preferred, child_hint.preferred),
+                max: stack(size_hint.max, child_hint.max),
+            };
+        }
+        size_hint.preferred = constraints.constrain(size_hint.preferred);
+        size_hint.normalized()
+    }
+}
+
+fn stack(top: Size, bottom: Size) -> Size {
+    Size::new(top.width.max(bottom.width), top.height+bottom.height)
+}
+
+impl<T: Dirty+TranslateY> Dirty for VecColumn<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.children.iter().fold(DirtyFlags::CLEAN, |dirty, child| dirty.or(child.get_dirty()))
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        // everything below a child that changed height moves with it
+        let mut height_change = 0.0;
+        for child in &mut self.children {
+            if height_change != 0.0 {
+                child.translate_y(-height_change);
+            }
+            height_change += child.update_layout();
+        }
+        height_change
+    }
+
+    fn clear_dirty(&mut self) {
+        for child in &mut self.children {
+            child.clear_dirty();
+        }
+    }
+}
+
 impl<T: TranslateX> TranslateX for VecColumn<T> {
     fn translate_x(&mut self, dx: f32) {
         self.cx += dx;
@@ -109,11 +177,16 @@ impl<T: TranslateY> TranslateY for VecColumn<T> {
     }
 }
 
-impl<T: SignalReciever<S, R>, S, R> SignalReciever<S, Vec<R>> for VecColumn<T> {
+impl<T: SignalReciever<S, R>, S, R: Default> SignalReciever<S, Vec<R>> for VecColumn<T> {
     fn take_signal(&mut self, signal: &mut S) -> Vec<R> {
         let mut responses = Vec::with_capacity(self.children.len());
         for child in &mut self.children {
-            responses.push(child.take_signal(signal));
+            // children after the one that handled the signal still get a slot
+            if signal.is_handled() {
+                responses.push(R::default());
+            } else {
+                responses.push(child.take_signal(signal));
+            }
         }
         responses
     }
@@ -129,4 +202,55 @@ impl<T: SignalReciever<S, ReconstructCallback<C>>, S, C> SignalReciever<S, Recon
         }
         ReconstructCallback::new(false)
     }
+}
+
+// every child gets the signal, animations keep running even when another one already asked for a redraw
+impl<T: SignalReciever<S, RedrawCallback>, S> SignalReciever<S, RedrawCallback> for VecColumn<T> {
+    fn take_signal(&mut self, signal: &mut S) -> RedrawCallback {
+        let redraw_callbacks: Vec<RedrawCallback> = self.take_signal(signal);
+        redraw_callbacks.into_iter().fold(RedrawCallback::new(false), |redraw_callback, other| redraw_callback.or_into(other))
+    }
+}
+
+impl<T: HitTest> HitTest for VecColumn<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        for (i, child) in self.children.iter().enumerate().rev() {
+            path.push(i);
+            if child.hit_test(x, y, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+}
+
+impl<S, R: Default, T: RouteSignal<S, R>> RouteSignal<S, R> for VecColumn<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        match path.first().and_then(|i| self.children.get_mut(*i)) {
+            Some(child) => child.route_signal(&path[1..], signal),
+            None => R::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{signal::{Propagating, Phase, MouseLeftDownSignal}, test_util::Block};
+
+    #[test]
+    fn handled_signal_still_yields_a_callback_per_child() {
+        let mut column = VecColumn::new();
+        let mut first = Block::new(1.0, 0.2);
+        first.handles = Some(Phase::Bubble);
+        column.push(first);
+        column.push(Block::new(1.0, 0.2));
+        column.push(Block::new(1.0, 0.2));
+
+        let redraw_callbacks: Vec<RedrawCallback> = column.take_signal(&mut Propagating::new(MouseLeftDownSignal()));
+        assert_eq!(redraw_callbacks.len(), 3);
+        let signals: Vec<usize> = column.get_children_mut().iter().map(|child| child.signals).collect();
+        assert_eq!(signals, vec![1, 0, 0]);
+    }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use bui::{line::LineRaw, freeform_2dcapsule::Freeform2DCapsule};
+use bui::{line::LineRaw, freeform_2dcapsule::Freeform2DCapsule, rect::Points};
 
 pub trait Construct<C> {
     fn construct(&self) -> C;
@@ -46,4 +46,107 @@ impl StandardConstructTarget for Freeform2DCapsuleTarget {
         self.0.append(&mut value.0);
         self
     }
+}
+
+impl Default for Freeform2DCapsuleTarget {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+// a capsule of even thickness between two points, in normalized coordinates
+pub fn capsule(p1: [f32; 2], p2: [f32; 2], radius: f32) -> Freeform2DCapsule {
+    Freeform2DCapsule {
+        p1,
+        p2,
+        r1: radius,
+        r2: radius,
+    }
+}
+
+// Lines and capsules constructed in one pass, for renderers that draw both.
+#[derive(Debug, Clone)]
+pub struct ShapeTarget {
+    pub lines: LineTarget,
+    pub capsules: Freeform2DCapsuleTarget,
+}
+
+impl ShapeTarget {
+    pub fn new(lines: LineTarget, capsules: Freeform2DCapsuleTarget) -> Self {
+        Self {
+            lines,
+            capsules,
+        }
+    }
+}
+
+impl StandardConstructTarget for ShapeTarget {
+    const EMPTY: Self = ShapeTarget {
+        lines: LineTarget::EMPTY,
+        capsules: Freeform2DCapsuleTarget::EMPTY,
+    };
+
+    fn append(&mut self, value: Self) {
+        self.lines.append(value.lines);
+        self.capsules.append(value.capsules);
+    }
+
+    fn append_into(mut self, value: Self) -> Self {
+        self.append(value);
+        self
+    }
+}
+
+impl Default for ShapeTarget {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+// Rows of capsules covering a rectangle. Rows further apart than twice the radius leave gaps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CapsuleFill {
+    pub radius: f32,
+    pub spacing: f32,
+}
+
+impl CapsuleFill {
+    pub const fn solid(radius: f32) -> Self {
+        Self {
+            radius,
+            spacing: radius*2.0,
+        }
+    }
+}
+
+// the radius is shrunk to fit rectangles thinner than the capsules
+pub fn fill_capsules(points: Points, fill: CapsuleFill) -> Vec<Freeform2DCapsule> {
+    let radius = fill.radius
+        .min((points.p2x-points.p1x)/2.0)
+        .min((points.p1y-points.p2y)/2.0);
+    if radius <= 0.0 || fill.spacing <= 0.0 {
+        return Vec::with_capacity(0);
+    }
+
+    let (left, right) = (points.p1x+radius, points.p2x-radius);
+    let mut capsules = Vec::new();
+    let mut y = points.p1y-radius;
+    while y >= points.p2y+radius {
+        capsules.push(capsule([left, y], [right, y], radius));
+        y -= fill.spacing;
+    }
+    capsules
+}
+
+// one capsule centered on each edge
+pub fn outline_capsules(points: Points, radius: f32) -> Vec<Freeform2DCapsule> {
+    if radius <= 0.0 {
+        return Vec::with_capacity(0);
+    }
+    vec![
+        capsule([points.p1x, points.p2y], [points.p1x, points.p1y], radius),
+        capsule([points.p1x, points.p1y], [points.p2x, points.p1y], radius),
+        capsule([points.p2x, points.p1y], [points.p2x, points.p2y], radius),
+        capsule([points.p2x, points.p2y], [points.p1x, points.p2y], radius),
+    ]
 }
\ No newline at end of file
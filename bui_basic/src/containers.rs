@@ -1,8 +1,8 @@
 use std::marker::PhantomData;
 
-use bui::rect::{SizeAndCenter, Points};
+use bui::{rect::{SizeAndCenter, Points}, freeform_2dcapsule::Freeform2DCapsule};
 
-use crate::{construct::{Construct, StandardConstructTarget}, signal::{SignalReciever, ShortCircuitingCallback}};
+use crate::{construct::{Construct, StandardConstructTarget, LineTarget, Freeform2DCapsuleTarget, ShapeTarget, CapsuleFill, fill_capsules, outline_capsules}, signal::{SignalReciever, ShortCircuitingCallback, Handled, ResizedSignal, ScaleFactorSignal}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, Size, SizeHint}, units::{Length, UnitContext, WINDOW_NORM}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, RetainedId, IntoPrimitives}, style::{ConstructStyled, Theme}, draw_list::DrawList};
 
 pub struct FillContainer<T> {
     child: T,
@@ -18,12 +18,24 @@ impl<T> FillContainer<T> {
     }
 }
 
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for FillContainer<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
 impl<C, T: Construct<C>> Construct<C> for FillContainer<T> {
     fn construct(&self) -> C {
         self.child.construct()
     }
 }
 
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for FillContainer<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
 impl<T> GetHeight for FillContainer<T> {
     fn get_height(&self) -> f32 {
         self.fill_target.sy*2.0
@@ -37,6 +49,27 @@ impl<T> SetCenterTopPosition for FillContainer<T> {
     }
 }
 
+impl<T> Measure for FillContainer<T> {
+    fn measure(&self, _constraints: Constraints) -> SizeHint {
+        SizeHint::fixed(Size::new(self.fill_target.sx*2.0, self.fill_target.sy*2.0))
+    }
+}
+
+impl<T: Dirty> Dirty for FillContainer<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.child.update_layout();
+        0.0
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
 impl<T: Fill> Init for FillContainer<T> {
     fn init(&mut self) {
         self.child.fill(self.fill_target);
@@ -49,18 +82,49 @@ impl<R, T: SignalReciever<S, R>, S> SignalReciever<S, R> for FillContainer<T> {
     }
 }
 
+impl<T: HitTest> HitTest for FillContainer<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for FillContainer<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
 pub struct HeightContainer<T> {
     child: T,
-    sy: f32,
+    height: Length,
+    units: UnitContext,
 }
 
 impl<T> HeightContainer<T> {
     pub fn new(child: T, sy: f32) -> Self {
+        Self::new_with_length(child, Length::Norm(sy*2.0))
+    }
+
+    // a Fraction height is a share of the window height
+    pub fn new_with_length(child: T, height: Length) -> Self {
         Self {
             child,
-            sy,
+            height,
+            units: UnitContext::DEFAULT,
         }
     }
+
+    pub fn set_height(&mut self, height: Length) {
+        self.height = height;
+    }
+
+    pub fn set_units(&mut self, units: UnitContext) {
+        self.units = units;
+    }
+
+    fn norm_sy(&self) -> f32 {
+        self.height.to_norm_y(&self.units, WINDOW_NORM)/2.0
+    }
 }
 
 impl<T: Init> Init for HeightContainer<T> {
@@ -71,13 +135,43 @@ impl<T: Init> Init for HeightContainer<T> {
 
 impl<T: Fill> FillWidth for HeightContainer<T> {
     fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        let sy = self.norm_sy();
         self.child.fill(SizeAndCenter {
             sx,
-            sy: self.sy,
+            sy,
             cx,
-            cy: ty-self.sy,
+            cy: ty-sy,
         });
-        ty-self.sy*2.0
+        ty-sy*2.0
+    }
+}
+
+impl<T: Measure> Measure for HeightContainer<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let height = self.norm_sy()*2.0;
+        self.child.measure(constraints.with_max_height(height)).map(|size| Size::new(size.width, height))
+    }
+}
+
+// the height is fixed no matter what the child does
+impl<T: Dirty> Dirty for HeightContainer<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.child.update_layout();
+        0.0
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for HeightContainer<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
     }
 }
 
@@ -87,6 +181,12 @@ impl<C, T: Construct<C>> Construct<C> for HeightContainer<T> {
     }
 }
 
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for HeightContainer<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
 impl<T: TranslateX> TranslateX for HeightContainer<T> {
     fn translate_x(&mut self, dx: f32) {
         self.child.translate_x(dx);
@@ -100,11 +200,37 @@ impl<T: TranslateY> TranslateY for HeightContainer<T> {
 }
 
 impl<R, T: SignalReciever<S, R>, S> SignalReciever<S, R> for HeightContainer<T> {
-    fn take_signal(&mut self, signal: &mut S) -> R {
+    default fn take_signal(&mut self, signal: &mut S) -> R {
+        self.child.take_signal(signal)
+    }
+}
+
+impl<R, T: SignalReciever<ResizedSignal, R>> SignalReciever<ResizedSignal, R> for HeightContainer<T> {
+    fn take_signal(&mut self, signal: &mut ResizedSignal) -> R {
+        self.units.resize(signal);
+        self.child.take_signal(signal)
+    }
+}
+
+impl<R, T: SignalReciever<ScaleFactorSignal, R>> SignalReciever<ScaleFactorSignal, R> for HeightContainer<T> {
+    fn take_signal(&mut self, signal: &mut ScaleFactorSignal) -> R {
+        self.units.rescale(signal);
         self.child.take_signal(signal)
     }
 }
 
+impl<T: HitTest> HitTest for HeightContainer<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for HeightContainer<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
 pub struct HSplitContainer<L, R> {
     left: L,
     split: f32,
@@ -136,6 +262,40 @@ impl<L: Fill, R: Fill> Fill for HSplitContainer<L, R> {
     }
 }
 
+// split is the share of the width given to the left child
+impl<L: Measure, R: Measure> Measure for HSplitContainer<L, R> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let left = self.left.measure(constraints.with_max_width(constraints.max_width*self.split));
+        let right = self.right.measure(constraints.with_max_width(constraints.max_width*(1.0-self.split)));
+        let combine = |left: Size, right: Size| Size::new(
+            f32::max(left.width/self.split, right.width/(1.0-self.split)),
+            left.height.max(right.height),
+        );
+        SizeHint {
+            min: combine(left.min, right.min),
+            preferred: constraints.constrain(combine(left.preferred, right.preferred)),
+            max: combine(left.max, right.max),
+        }.normalized()
+    }
+}
+
+impl<L: Dirty, R: Dirty> Dirty for HSplitContainer<L, R> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.left.get_dirty().or(self.right.get_dirty())
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.left.update_layout();
+        self.right.update_layout();
+        0.0
+    }
+
+    fn clear_dirty(&mut self) {
+        self.left.clear_dirty();
+        self.right.clear_dirty();
+    }
+}
+
 impl<L: TranslateX, R: TranslateX> TranslateX for HSplitContainer<L, R> {
     fn translate_x(&mut self, dx: f32) {
         self.left.translate_x(dx);
@@ -150,12 +310,25 @@ impl<L: TranslateY, R: TranslateY> TranslateY for HSplitContainer<L, R> {
     }
 }
 
+impl<C: IntoPrimitives, L: RetainedConstruct<C>, R: RetainedConstruct<C>> RetainedConstruct<C> for HSplitContainer<L, R> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.left.construct_retained(target);
+        self.right.construct_retained(target);
+    }
+}
+
 impl<C: StandardConstructTarget, L: Construct<C>, R: Construct<C>> Construct<C> for HSplitContainer<L, R> {
     fn construct(&self) -> C {
         self.left.construct().append_into(self.right.construct())
     }
 }
 
+impl<C: StandardConstructTarget, L: ConstructStyled<C>, R: ConstructStyled<C>> ConstructStyled<C> for HSplitContainer<L, R> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.left.construct_styled(theme).append_into(self.right.construct_styled(theme))
+    }
+}
+
 impl<O, P, L: SignalReciever<S, O>, R: SignalReciever<S, P>, S> SignalReciever<S, (O, P)> for HSplitContainer<L, R> {
     fn take_signal(&mut self, signal: &mut S) -> (O, P) {
         (self.left.take_signal(signal), self.right.take_signal(signal))
@@ -164,7 +337,27 @@ impl<O, P, L: SignalReciever<S, O>, R: SignalReciever<S, P>, S> SignalReciever<S
 
 impl<O: ShortCircuitingCallback, L: SignalReciever<S, O>, R: SignalReciever<S, O>, S> SignalReciever<S, O> for HSplitContainer<L, R> {
     fn take_signal(&mut self, signal: &mut S) -> O {
-        self.left.take_signal(signal).or_into(self.right.take_signal(signal))
+        let callback = self.left.take_signal(signal);
+        if signal.is_handled() {
+            return callback;
+        }
+        callback.or_into(self.right.take_signal(signal))
+    }
+}
+
+impl<L: HitTest, R: HitTest> HitTest for HSplitContainer<L, R> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        hit_test_pair(&self.left, &self.right, x, y, path)
+    }
+}
+
+impl<S, O: Default, L: RouteSignal<S, O>, R: RouteSignal<S, O>> RouteSignal<S, O> for HSplitContainer<L, R> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> O {
+        match path.first() {
+            Some(0) => self.left.route_signal(&path[1..], signal),
+            Some(1) => self.right.route_signal(&path[1..], signal),
+            _ => O::default()
+        }
     }
 }
 
@@ -179,8 +372,14 @@ impl SetCenterTopPosition for NoContainer {fn set_center_top_position(&mut self,
 impl TranslateX for NoContainer {fn translate_x(&mut self, _dx: f32) {}}
 impl TranslateY for NoContainer {fn translate_y(&mut self, _dy: f32) {}}
 impl GetPointBounds for NoContainer {fn get_point_bounds(&self) -> Points {Points::ZERO}}
+impl Measure for NoContainer {fn measure(&self, _constraints: Constraints) -> SizeHint {SizeHint::ZERO}}
+impl Dirty for NoContainer {fn get_dirty(&self) -> DirtyFlags {DirtyFlags::CLEAN} fn update_layout(&mut self) -> f32 {0.0} fn clear_dirty(&mut self) {}}
 impl<T, R: Default> SignalReciever<T, R> for NoContainer {fn take_signal(&mut self, _signal: &mut T) -> R {R::default()}}
 impl<C: Default> Construct<C> for NoContainer {fn construct(&self) -> C {C::default()}}
+impl<C: Default> ConstructStyled<C> for NoContainer {fn construct_styled(&self, _theme: &Theme) -> C {C::default()}}
+impl<C: IntoPrimitives> RetainedConstruct<C> for NoContainer {fn construct_retained(&self, _target: &mut RetainedTarget<C>) {}}
+impl HitTest for NoContainer {fn hit_test(&self, _x: f32, _y: f32, _path: &mut Vec<usize>) -> bool {false}}
+impl<S, R: Default> RouteSignal<S, R> for NoContainer {fn route_signal(&mut self, _path: &[usize], _signal: &mut S) -> R {R::default()}}
 
 pub struct VStackContainer<T, B> {
     top: T,
@@ -209,6 +408,38 @@ impl<T: FillWidth, B: FillWidth> FillWidth for VStackContainer<T, B> {
     }
 }
 
+impl<T: Measure, B: Measure> Measure for VStackContainer<T, B> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let top = self.top.measure(constraints);
+        let bottom = self.bottom.measure(constraints.shrink(0.0, top.preferred.height));
+        let combine = |top: Size, bottom: Size| Size::new(top.width.max(bottom.width), top.height+bottom.height);
+        SizeHint {
+            min: combine(top.min, bottom.min),
+            preferred: constraints.constrain(combine(top.preferred, bottom.preferred)),
+            max: combine(top.max, bottom.max),
+        }.normalized()
+    }
+}
+
+impl<T: Dirty, B: Dirty+TranslateY> Dirty for VStackContainer<T, B> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.top.get_dirty().or(self.bottom.get_dirty())
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let top_change = self.top.update_layout();
+        if top_change != 0.0 {
+            self.bottom.translate_y(-top_change);
+        }
+        top_change+self.bottom.update_layout()
+    }
+
+    fn clear_dirty(&mut self) {
+        self.top.clear_dirty();
+        self.bottom.clear_dirty();
+    }
+}
+
 impl<T: TranslateX, B: TranslateX> TranslateX for VStackContainer<T, B> {
     fn translate_x(&mut self, dx: f32) {
         self.top.translate_x(dx);
@@ -223,12 +454,25 @@ impl<T: TranslateY, B: TranslateY> TranslateY for VStackContainer<T, B> {
     }
 }
 
+impl<C: IntoPrimitives, T: RetainedConstruct<C>, B: RetainedConstruct<C>> RetainedConstruct<C> for VStackContainer<T, B> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.top.construct_retained(target);
+        self.bottom.construct_retained(target);
+    }
+}
+
 impl<C: StandardConstructTarget, T: Construct<C>, B: Construct<C>> Construct<C> for VStackContainer<T, B> {
     fn construct(&self) -> C {
         self.top.construct().append_into(self.bottom.construct())
     }
 }
 
+impl<C: StandardConstructTarget, T: ConstructStyled<C>, B: ConstructStyled<C>> ConstructStyled<C> for VStackContainer<T, B> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.top.construct_styled(theme).append_into(self.bottom.construct_styled(theme))
+    }
+}
+
 impl<S, O, P, T: SignalReciever<S, O>, B: SignalReciever<S, P>> SignalReciever<S, (O, P)> for VStackContainer<T, B> {
     fn take_signal(&mut self, signal: &mut S) -> (O, P) {
         (self.top.take_signal(signal), self.bottom.take_signal(signal))
@@ -237,42 +481,1157 @@ impl<S, O, P, T: SignalReciever<S, O>, B: SignalReciever<S, P>> SignalReciever<S
 
 impl<S, O: ShortCircuitingCallback, T: SignalReciever<S, O>, B: SignalReciever<S, O>> SignalReciever<S, O> for VStackContainer<T, B> {
     fn take_signal(&mut self, signal: &mut S) -> O {
-        self.top.take_signal(signal).or_into(self.bottom.take_signal(signal))
+        let callback = self.top.take_signal(signal);
+        if signal.is_handled() {
+            return callback;
+        }
+        callback.or_into(self.bottom.take_signal(signal))
     }
 }
 
-pub trait Fill {
-    fn fill(&mut self, fill_target: SizeAndCenter);
+impl<T: HitTest, B: HitTest> HitTest for VStackContainer<T, B> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        hit_test_pair(&self.top, &self.bottom, x, y, path)
+    }
 }
 
-pub trait FillWidth {
-    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32;
+impl<S, O: Default, T: RouteSignal<S, O>, B: RouteSignal<S, O>> RouteSignal<S, O> for VStackContainer<T, B> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> O {
+        match path.first() {
+            Some(0) => self.top.route_signal(&path[1..], signal),
+            Some(1) => self.bottom.route_signal(&path[1..], signal),
+            _ => O::default()
+        }
+    }
 }
 
-pub trait Init {
-    fn init(&mut self);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Insets {
+    pub left: Length,
+    pub right: Length,
+    pub top: Length,
+    pub bottom: Length,
 }
 
-pub trait GetHeight {
-    fn get_height(&self) -> f32;
+impl Insets {
+    pub const ZERO: Self = Self::all(Length::ZERO);
+
+    pub const fn all(inset: Length) -> Self {
+        Self {
+            left: inset,
+            right: inset,
+            top: inset,
+            bottom: inset,
+        }
+    }
+
+    pub const fn symmetric(horizontal: Length, vertical: Length) -> Self {
+        Self {
+            left: horizontal,
+            right: horizontal,
+            top: vertical,
+            bottom: vertical,
+        }
+    }
 }
 
-pub trait GetCenterPosition {
-    fn get_center_position(&self) -> (f32, f32);
+// Nothing is drawn around the child, so padding and margin are the same thing here.
+pub struct Padding<T> {
+    child: T,
+    insets: Insets,
+    points: Points,
+    units: UnitContext,
 }
 
-pub trait SetCenterTopPosition {
-    fn set_center_top_position(&mut self, cx: f32, ty: f32);
+pub type Margin<T> = Padding<T>;
+
+impl<T> Padding<T> {
+    pub fn new(child: T, insets: Insets) -> Self {
+        Self::new_with_units(child, insets, UnitContext::DEFAULT)
+    }
+
+    pub fn new_with_units(child: T, insets: Insets, units: UnitContext) -> Self {
+        Self {
+            child,
+            insets,
+            points: Points::ZERO,
+            units,
+        }
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    pub fn set_insets(&mut self, insets: Insets) {
+        self.insets = insets;
+    }
+
+    // left, right, top, bottom in normalized coordinates, fractions are of the outer width and height
+    fn norm_insets(&self, width: f32, height: f32) -> (f32, f32, f32, f32) {
+        (
+            self.insets.left.to_norm_x(&self.units, width),
+            self.insets.right.to_norm_x(&self.units, width),
+            self.insets.top.to_norm_y(&self.units, height),
+            self.insets.bottom.to_norm_y(&self.units, height),
+        )
+    }
 }
 
-pub trait TranslateX {
-    fn translate_x(&mut self, dx: f32);
+impl<T: Init> Init for Padding<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
 }
 
-pub trait TranslateY {
-    fn translate_y(&mut self, dy: f32);
+impl<T: Fill> Fill for Padding<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        let (left, right, top, bottom) = self.norm_insets(fill_target.sx*2.0, fill_target.sy*2.0);
+        let sx = (fill_target.sx-(left+right)/2.0).max(0.0);
+        let sy = (fill_target.sy-(top+bottom)/2.0).max(0.0);
+        self.child.fill(SizeAndCenter {
+            sx,
+            sy,
+            cx: fill_target.cx-fill_target.sx+left+sx,
+            cy: fill_target.cy+fill_target.sy-top-sy,
+        });
+        self.points = fill_target.into();
+    }
 }
 
-pub trait GetPointBounds {
-    fn get_point_bounds(&self) -> Points;
+impl<T: FillWidth> FillWidth for Padding<T> {
+    // vertical fractions are of the window height since the outer height is not known yet
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        let (left, right, top, bottom) = self.norm_insets(sx*2.0, WINDOW_NORM);
+        let inner_sx = (sx-(left+right)/2.0).max(0.0);
+        let by = self.child.fill_width(inner_sx, cx-sx+left+inner_sx, ty-top)-bottom;
+        self.points = Points {
+            p1x: cx-sx,
+            p1y: ty,
+            p2x: cx+sx,
+            p2y: by,
+        };
+        by
+    }
+}
+
+impl<T: Measure> Measure for Padding<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let available = |max: f32| if max.is_finite() { max } else { WINDOW_NORM };
+        let (left, right, top, bottom) = self.norm_insets(available(constraints.max_width), available(constraints.max_height));
+        let (width, height) = (left+right, top+bottom);
+        self.child.measure(constraints.shrink(width, height)).map(|size| Size::new(size.width+width, size.height+height))
+    }
+}
+
+impl<T: Dirty> Dirty for Padding<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let height_change = self.child.update_layout();
+        self.points.p2y -= height_change;
+        height_change
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<T: TranslateX> TranslateX for Padding<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.points.p1x += dx;
+        self.points.p2x += dx;
+        self.child.translate_x(dx);
+    }
+}
+
+impl<T: TranslateY> TranslateY for Padding<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.points.p1y += dy;
+        self.points.p2y += dy;
+        self.child.translate_y(dy);
+    }
+}
+
+impl<T> GetPointBounds for Padding<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.points
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for Padding<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for Padding<T> {
+    fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for Padding<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
+impl<S, R, T: SignalReciever<S, R>> SignalReciever<S, R> for Padding<T> {
+    default fn take_signal(&mut self, signal: &mut S) -> R {
+        self.child.take_signal(signal)
+    }
+}
+
+impl<R, T: SignalReciever<ResizedSignal, R>> SignalReciever<ResizedSignal, R> for Padding<T> {
+    fn take_signal(&mut self, signal: &mut ResizedSignal) -> R {
+        self.units.resize(signal);
+        self.child.take_signal(signal)
+    }
+}
+
+impl<R, T: SignalReciever<ScaleFactorSignal, R>> SignalReciever<ScaleFactorSignal, R> for Padding<T> {
+    fn take_signal(&mut self, signal: &mut ScaleFactorSignal) -> R {
+        self.units.rescale(signal);
+        self.child.take_signal(signal)
+    }
+}
+
+impl<T: HitTest> HitTest for Padding<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for Padding<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HAlign {
+    Left,
+    Center,
+    Right,
+    Fill,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VAlign {
+    Top,
+    Center,
+    Bottom,
+    Fill,
+}
+
+// Places the child at its preferred size inside the target, sizes are full lengths
+// in normalized coordinates and are shrunk to fit when the target is smaller. Without
+// an explicit size the child is measured.
+pub struct Align<T> {
+    child: T,
+    horizontal: HAlign,
+    vertical: VAlign,
+    width: Option<f32>,
+    height: Option<f32>,
+    points: Points,
+}
+
+impl<T> Align<T> {
+    pub fn new(child: T, horizontal: HAlign, vertical: VAlign) -> Self {
+        Self {
+            child,
+            horizontal,
+            vertical,
+            width: None,
+            height: None,
+            points: Points::ZERO,
+        }
+    }
+
+    pub fn center(child: T) -> Self {
+        Self::new(child, HAlign::Center, VAlign::Center)
+    }
+
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.width = Some(width);
+        self.height = Some(height);
+        self
+    }
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    // the explicit size where there is one, the child's preferred size otherwise
+    fn child_size(&self, constraints: Constraints) -> Size
+    where
+        T: Measure
+    {
+        match (self.width, self.height) {
+            (Some(width), Some(height)) => Size::new(width, height),
+            (width, height) => {
+                let preferred = self.child.measure(constraints).preferred;
+                Size::new(width.unwrap_or(preferred.width), height.unwrap_or(preferred.height))
+            }
+        }
+    }
+
+    // returns the half size and center of the child along x
+    fn place_x(&self, sx: f32, cx: f32, width: f32) -> (f32, f32) {
+        if self.horizontal == HAlign::Fill {
+            return (sx, cx);
+        }
+        let child_sx = (width/2.0).min(sx);
+        match self.horizontal {
+            HAlign::Left => (child_sx, cx-sx+child_sx),
+            HAlign::Right => (child_sx, cx+sx-child_sx),
+            _ => (child_sx, cx)
+        }
+    }
+
+    fn place_y(&self, sy: f32, cy: f32, height: f32) -> (f32, f32) {
+        if self.vertical == VAlign::Fill {
+            return (sy, cy);
+        }
+        let child_sy = (height/2.0).min(sy);
+        match self.vertical {
+            VAlign::Top => (child_sy, cy+sy-child_sy),
+            VAlign::Bottom => (child_sy, cy-sy+child_sy),
+            _ => (child_sy, cy)
+        }
+    }
+}
+
+impl<T: Init> Init for Align<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
+}
+
+impl<T: Fill+Measure> Fill for Align<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        let size = match (self.horizontal, self.vertical) {
+            (HAlign::Fill, VAlign::Fill) => Size::ZERO,
+            _ => self.child_size(Constraints::new(fill_target.sx*2.0, fill_target.sy*2.0))
+        };
+        let (sx, cx) = self.place_x(fill_target.sx, fill_target.cx, size.width);
+        let (sy, cy) = self.place_y(fill_target.sy, fill_target.cy, size.height);
+        self.child.fill(SizeAndCenter {
+            sx,
+            sy,
+            cx,
+            cy,
+        });
+        self.points = fill_target.into();
+    }
+}
+
+// only the horizontal alignment applies, the child decides its own height
+impl<T: FillWidth+Measure> FillWidth for Align<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        let width = match self.horizontal {
+            HAlign::Fill => 0.0,
+            _ => self.child_size(Constraints::width(sx*2.0)).width
+        };
+        let (child_sx, child_cx) = self.place_x(sx, cx, width);
+        let by = self.child.fill_width(child_sx, child_cx, ty);
+        self.points = Points {
+            p1x: cx-sx,
+            p1y: ty,
+            p2x: cx+sx,
+            p2y: by,
+        };
+        by
+    }
+}
+
+// Align itself takes all the space it is given, only its preferred size comes from the child.
+impl<T: Measure> Measure for Align<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let child = self.child.measure(constraints);
+        let preferred = Size::new(
+            self.width.unwrap_or(child.preferred.width),
+            self.height.unwrap_or(child.preferred.height),
+        );
+        SizeHint {
+            min: child.min,
+            preferred: constraints.constrain(preferred),
+            max: Size::INFINITY,
+        }.normalized()
+    }
+}
+
+impl<T: Dirty> Dirty for Align<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let height_change = self.child.update_layout();
+        self.points.p2y -= height_change;
+        height_change
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<T: TranslateX> TranslateX for Align<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.points.p1x += dx;
+        self.points.p2x += dx;
+        self.child.translate_x(dx);
+    }
+}
+
+impl<T: TranslateY> TranslateY for Align<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.points.p1y += dy;
+        self.points.p2y += dy;
+        self.child.translate_y(dy);
+    }
+}
+
+impl<T> GetPointBounds for Align<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.points
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for Align<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for Align<T> {
+    fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for Align<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
+impl<R, T: SignalReciever<S, R>, S> SignalReciever<S, R> for Align<T> {
+    fn take_signal(&mut self, signal: &mut S) -> R {
+        self.child.take_signal(signal)
+    }
+}
+
+impl<T: HitTest> HitTest for Align<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for Align<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
+// Keeps the child at a width to height ratio in pixels, centered in the target.
+pub struct AspectRatio<T> {
+    child: T,
+    ratio: f32,
+    points: Points,
+    units: UnitContext,
+}
+
+impl<T> AspectRatio<T> {
+    pub fn new(child: T, ratio: f32) -> Self {
+        Self::new_with_units(child, ratio, UnitContext::DEFAULT)
+    }
+
+    pub fn new_with_units(child: T, ratio: f32, units: UnitContext) -> Self {
+        assert_positive_ratio(ratio);
+        Self {
+            child,
+            ratio,
+            points: Points::ZERO,
+            units,
+        }
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    pub fn set_ratio(&mut self, ratio: f32) {
+        assert_positive_ratio(ratio);
+        self.ratio = ratio;
+    }
+
+    // normalized half height for a normalized half width
+    fn sy_for_sx(&self, sx: f32) -> f32 {
+        sx*self.units.resxp as f32/(self.ratio*self.units.resyp.max(1) as f32)
+    }
+}
+
+fn assert_positive_ratio(ratio: f32) {
+    assert!(ratio > 0.0, "aspect ratio must be positive, got {}", ratio);
+}
+
+impl<T: Init> Init for AspectRatio<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
+}
+
+impl<T: Fill> Fill for AspectRatio<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        let sy = self.sy_for_sx(fill_target.sx);
+        let (sx, sy) = if sy <= fill_target.sy {
+            (fill_target.sx, sy)
+        } else {
+            (fill_target.sx*fill_target.sy/sy, fill_target.sy)
+        };
+        self.child.fill(SizeAndCenter {
+            sx,
+            sy,
+            cx: fill_target.cx,
+            cy: fill_target.cy,
+        });
+        self.points = fill_target.into();
+    }
+}
+
+impl<T: Fill> FillWidth for AspectRatio<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        let sy = self.sy_for_sx(sx);
+        self.child.fill(SizeAndCenter {
+            sx,
+            sy,
+            cx,
+            cy: ty-sy,
+        });
+        self.points = Points {
+            p1x: cx-sx,
+            p1y: ty,
+            p2x: cx+sx,
+            p2y: ty-sy*2.0,
+        };
+        ty-sy*2.0
+    }
+}
+
+// prefers the largest size with the ratio inside the constraints, the full window width when unbounded
+impl<T> Measure for AspectRatio<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let aspect = self.sy_for_sx(1.0);
+        let width = constraints.max_width.min(constraints.max_height/aspect);
+        let width = if width.is_finite() { width } else { 2.0 };
+        SizeHint::flexible(Size::new(width, width*aspect))
+    }
+}
+
+impl<T: Dirty> Dirty for AspectRatio<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.child.update_layout();
+        0.0
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<T: TranslateX> TranslateX for AspectRatio<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.points.p1x += dx;
+        self.points.p2x += dx;
+        self.child.translate_x(dx);
+    }
+}
+
+impl<T: TranslateY> TranslateY for AspectRatio<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.points.p1y += dy;
+        self.points.p2y += dy;
+        self.child.translate_y(dy);
+    }
+}
+
+impl<T> GetPointBounds for AspectRatio<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.points
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for AspectRatio<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for AspectRatio<T> {
+    fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for AspectRatio<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
+impl<S, R, T: SignalReciever<S, R>> SignalReciever<S, R> for AspectRatio<T> {
+    default fn take_signal(&mut self, signal: &mut S) -> R {
+        self.child.take_signal(signal)
+    }
+}
+
+impl<R, T: SignalReciever<ResizedSignal, R>> SignalReciever<ResizedSignal, R> for AspectRatio<T> {
+    fn take_signal(&mut self, signal: &mut ResizedSignal) -> R {
+        self.units.resize(signal);
+        self.child.take_signal(signal)
+    }
+}
+
+impl<T: HitTest> HitTest for AspectRatio<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for AspectRatio<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
+// Draws capsules behind the child's bounds and optionally a thick border around them.
+pub struct Background<T> {
+    child: T,
+    fill: Option<CapsuleFill>,
+    border: Option<f32>,
+    // the bounds or the look changed since the last construct
+    background_dirty: bool,
+    retained_id: RetainedId,
+}
+
+impl<T> Background<T> {
+    pub fn new(child: T, fill: CapsuleFill) -> Self {
+        Self {
+            child,
+            fill: Some(fill),
+            border: None,
+            background_dirty: true,
+            retained_id: RetainedId::new(),
+        }
+    }
+
+    // only a border of capsules with the given radius
+    pub fn new_border(child: T, border: f32) -> Self {
+        Self {
+            child,
+            fill: None,
+            border: Some(border),
+            background_dirty: true,
+            retained_id: RetainedId::new(),
+        }
+    }
+
+    pub fn with_border(mut self, border: f32) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    pub fn set_fill(&mut self, fill: Option<CapsuleFill>) {
+        self.fill = fill;
+        self.background_dirty = true;
+    }
+
+    pub fn set_border(&mut self, border: Option<f32>) {
+        self.border = border;
+        self.background_dirty = true;
+    }
+}
+
+impl<T: GetPointBounds> Background<T> {
+    fn background_capsules(&self) -> Vec<Freeform2DCapsule> {
+        let points = self.child.get_point_bounds();
+        let mut capsules = match self.fill {
+            Some(fill) => fill_capsules(points, fill),
+            None => Vec::new()
+        };
+        if let Some(border) = self.border {
+            capsules.append(&mut outline_capsules(points, border));
+        }
+        capsules
+    }
+}
+
+impl<T: Init> Init for Background<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
+}
+
+impl<T: Fill> Fill for Background<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.background_dirty = true;
+        self.child.fill(fill_target);
+    }
+}
+
+impl<T: FillWidth> FillWidth for Background<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        self.background_dirty = true;
+        self.child.fill_width(sx, cx, ty)
+    }
+}
+
+impl<T: Measure> Measure for Background<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.child.measure(constraints)
+    }
+}
+
+impl<T: Dirty> Dirty for Background<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty().or(DirtyFlags::new(false, self.background_dirty))
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let height_change = self.child.update_layout();
+        if height_change != 0.0 {
+            self.background_dirty = true;
+        }
+        height_change
+    }
+
+    fn clear_dirty(&mut self) {
+        self.background_dirty = false;
+        self.child.clear_dirty();
+    }
+}
+
+impl<T: TranslateX> TranslateX for Background<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.background_dirty = true;
+        self.child.translate_x(dx);
+    }
+}
+
+impl<T: TranslateY> TranslateY for Background<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.background_dirty = true;
+        self.child.translate_y(dy);
+    }
+}
+
+impl<T: GetPointBounds> GetPointBounds for Background<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.child.get_point_bounds()
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for Background<T> {
+    default fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<T: GetPointBounds+RetainedConstruct<Freeform2DCapsuleTarget>> RetainedConstruct<Freeform2DCapsuleTarget> for Background<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<Freeform2DCapsuleTarget>) {
+        target.write_slot(&self.retained_id, self.background_dirty, || Freeform2DCapsuleTarget(self.background_capsules()));
+        self.child.construct_retained(target);
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for Background<T> {
+    default fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+// the background goes first so the child draws over it
+impl<T: GetPointBounds+Construct<Freeform2DCapsuleTarget>> Construct<Freeform2DCapsuleTarget> for Background<T> {
+    fn construct(&self) -> Freeform2DCapsuleTarget {
+        Freeform2DCapsuleTarget(self.background_capsules()).append_into(self.child.construct())
+    }
+}
+
+impl<T: GetPointBounds+Construct<ShapeTarget>> Construct<ShapeTarget> for Background<T> {
+    fn construct(&self) -> ShapeTarget {
+        ShapeTarget::new(LineTarget::EMPTY, Freeform2DCapsuleTarget(self.background_capsules())).append_into(self.child.construct())
+    }
+}
+
+impl<T: GetPointBounds+Construct<DrawList>> Construct<DrawList> for Background<T> {
+    fn construct(&self) -> DrawList {
+        DrawList::from(Freeform2DCapsuleTarget(self.background_capsules())).append_into(self.child.construct())
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for Background<T> {
+    default fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
+impl<T: GetPointBounds+ConstructStyled<ShapeTarget>> ConstructStyled<ShapeTarget> for Background<T> {
+    fn construct_styled(&self, theme: &Theme) -> ShapeTarget {
+        ShapeTarget::new(LineTarget::EMPTY, Freeform2DCapsuleTarget(self.background_capsules())).append_into(self.child.construct_styled(theme))
+    }
+}
+
+impl<T: GetPointBounds+ConstructStyled<DrawList>> ConstructStyled<DrawList> for Background<T> {
+    fn construct_styled(&self, theme: &Theme) -> DrawList {
+        DrawList::from(Freeform2DCapsuleTarget(self.background_capsules())).append_into(self.child.construct_styled(theme))
+    }
+}
+
+impl<S, R, T: SignalReciever<S, R>> SignalReciever<S, R> for Background<T> {
+    fn take_signal(&mut self, signal: &mut S) -> R {
+        self.child.take_signal(signal)
+    }
+}
+
+impl<T: HitTest> HitTest for Background<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for Background<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
+// children constructed later are drawn on top, so they are tested first
+fn hit_test_pair<A: HitTest, B: HitTest>(a: &A, b: &B, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+    path.push(1);
+    if b.hit_test(x, y, path) {
+        return true;
+    }
+    path.pop();
+
+    path.push(0);
+    if a.hit_test(x, y, path) {
+        return true;
+    }
+    path.pop();
+
+    false
+}
+
+pub trait Fill {
+    fn fill(&mut self, fill_target: SizeAndCenter);
+}
+
+pub trait FillWidth {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32;
+}
+
+pub trait Init {
+    fn init(&mut self);
+}
+
+pub trait GetHeight {
+    fn get_height(&self) -> f32;
+}
+
+pub trait GetCenterPosition {
+    fn get_center_position(&self) -> (f32, f32);
+}
+
+pub trait SetCenterTopPosition {
+    fn set_center_top_position(&mut self, cx: f32, ty: f32);
+}
+
+pub trait TranslateX {
+    fn translate_x(&mut self, dx: f32);
+}
+
+pub trait TranslateY {
+    fn translate_y(&mut self, dy: f32);
+}
+
+pub trait GetPointBounds {
+    fn get_point_bounds(&self) -> Points;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::{Block, Growing}, draw_list::DrawCommand};
+
+    fn fill_target() -> SizeAndCenter {
+        SizeAndCenter {
+            sx: 1.0,
+            sy: 1.0,
+            cx: 0.0,
+            cy: 0.0,
+        }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a-b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn align_centers_the_measured_child() {
+        let mut align = Align::center(Block::new(0.5, 0.4));
+        align.fill(fill_target());
+        assert_close(align.get_child().get_size().width, 0.5);
+        assert_close(align.get_child().get_size().height, 0.4);
+        assert_eq!(align.get_child().get_center(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn align_places_the_measured_child_at_the_edges() {
+        let mut align = Align::new(Block::new(0.5, 0.4), HAlign::Right, VAlign::Top);
+        align.fill(fill_target());
+        let points = align.get_child().points;
+        assert_close(points.p1x, 0.5);
+        assert_close(points.p2x, 1.0);
+        assert_close(points.p1y, 1.0);
+        assert_close(points.p2y, 0.6);
+    }
+
+    #[test]
+    fn align_explicit_size_wins_over_the_measured_one() {
+        let mut align = Align::new(Block::new(0.5, 0.4), HAlign::Left, VAlign::Center).with_width(1.0);
+        align.fill(fill_target());
+        assert_close(align.get_child().get_size().width, 1.0);
+        assert_close(align.get_child().get_size().height, 0.4);
+        assert_close(align.get_child().points.p1x, -1.0);
+    }
+
+    #[test]
+    fn align_fill_ignores_the_measured_size() {
+        let mut align = Align::new(Block::new(0.5, 0.4), HAlign::Fill, VAlign::Bottom);
+        align.fill(fill_target());
+        assert_close(align.get_child().get_size().width, 2.0);
+        assert_close(align.get_child().points.p2y, -1.0);
+    }
+
+    #[test]
+    fn align_shrinks_a_child_larger_than_the_target() {
+        let mut align = Align::center(Block::new(3.0, 3.0));
+        align.fill(fill_target());
+        assert_close(align.get_child().get_size().width, 2.0);
+        assert_close(align.get_child().get_size().height, 2.0);
+    }
+
+    #[test]
+    fn align_fill_width_places_the_measured_width() {
+        let mut align = Align::new(Block::new(0.5, 0.4), HAlign::Center, VAlign::Top);
+        let by = align.fill_width(1.0, 0.0, 1.0);
+        assert_close(by, 0.6);
+        assert_close(align.get_child().points.p1x, -0.25);
+        assert_close(align.get_child().points.p2x, 0.25);
+    }
+
+    #[test]
+    fn padding_insets_the_filled_child() {
+        let insets = Insets {
+            left: Length::Norm(0.2),
+            right: Length::Norm(0.4),
+            top: Length::Norm(0.1),
+            bottom: Length::Fraction(0.25),
+        };
+        let mut padding = Padding::new(Block::new(0.0, 0.0), insets);
+        padding.fill(fill_target());
+        let points = padding.get_child().points;
+        assert_close(points.p1x, -0.8);
+        assert_close(points.p2x, 0.6);
+        assert_close(points.p1y, 0.9);
+        assert_close(points.p2y, -0.5);
+        assert_close(padding.get_point_bounds().p2y, -1.0);
+    }
+
+    #[test]
+    fn padding_fill_width_adds_the_vertical_insets() {
+        let mut padding = Padding::new(Block::new(0.0, 0.5), Insets::symmetric(Length::Norm(0.1), Length::Norm(0.2)));
+        assert_close(padding.fill_width(1.0, 0.0, 1.0), 0.1);
+        let points = padding.get_child().points;
+        assert_close(points.p1x, -0.9);
+        assert_close(points.p2x, 0.9);
+        assert_close(points.p1y, 0.8);
+        assert_close(points.p2y, 0.3);
+    }
+
+    #[test]
+    fn padding_pixel_insets_depend_on_the_axis() {
+        let mut padding = Padding::new_with_units(Block::new(0.0, 0.0), Insets::all(Length::Px(64.0)), UnitContext::new(1280, 720, 1.0));
+        padding.fill(fill_target());
+        let points = padding.get_child().points;
+        assert_close(points.p1x, -0.9);
+        assert_close(points.p1y, 1.0-128.0/720.0);
+    }
+
+    #[test]
+    fn padding_measures_the_child_with_its_insets() {
+        let padding = Padding::new(Block::new(0.5, 0.4), Insets::all(Length::Norm(0.1)));
+        let preferred = padding.measure(Constraints::UNBOUNDED).preferred;
+        assert_close(preferred.width, 0.7);
+        assert_close(preferred.height, 0.6);
+    }
+
+    // twice as wide as high on a square window
+    fn aspect_ratio() -> AspectRatio<Block> {
+        AspectRatio::new_with_units(Block::new(0.0, 0.0), 2.0, UnitContext::new(1000, 1000, 1.0))
+    }
+
+    #[test]
+    fn aspect_ratio_fits_the_wider_side() {
+        let mut aspect_ratio = aspect_ratio();
+        aspect_ratio.fill(fill_target());
+        assert_close(aspect_ratio.get_child().get_size().width, 2.0);
+        assert_close(aspect_ratio.get_child().get_size().height, 1.0);
+
+        aspect_ratio.fill(SizeAndCenter {
+            sx: 1.0,
+            sy: 0.25,
+            cx: 0.5,
+            cy: 0.0,
+        });
+        assert_close(aspect_ratio.get_child().get_size().width, 1.0);
+        assert_close(aspect_ratio.get_child().get_size().height, 0.5);
+        assert_eq!(aspect_ratio.get_child().get_center(), (0.5, 0.0));
+    }
+
+    #[test]
+    fn aspect_ratio_fill_width_takes_the_height_for_the_width() {
+        let mut aspect_ratio = aspect_ratio();
+        assert_close(aspect_ratio.fill_width(1.0, 0.0, 1.0), 0.0);
+        assert_close(aspect_ratio.get_child().points.p2y, 0.0);
+    }
+
+    #[test]
+    fn aspect_ratio_measures_the_largest_fitting_size() {
+        let aspect_ratio = aspect_ratio();
+        let preferred = aspect_ratio.measure(Constraints::UNBOUNDED).preferred;
+        assert_close(preferred.width, 2.0);
+        assert_close(preferred.height, 1.0);
+
+        let preferred = aspect_ratio.measure(Constraints::new(1.0, 0.2)).preferred;
+        assert_close(preferred.width, 0.4);
+        assert_close(preferred.height, 0.2);
+    }
+
+    #[test]
+    #[should_panic(expected = "aspect ratio must be positive, got 0")]
+    fn zero_aspect_ratio_panics() {
+        AspectRatio::new(Block::new(0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "aspect ratio must be positive, got -1")]
+    fn setting_a_negative_aspect_ratio_panics() {
+        aspect_ratio().set_ratio(-1.0);
+    }
+
+    #[test]
+    fn vstack_moves_the_bottom_by_the_growth_of_the_top() {
+        let mut stack = VStackContainer::new(Growing::new(2.0, 0.3), Growing::new(2.0, 0.2));
+        assert_close(stack.fill_width(1.0, 0.0, 1.0), 0.5);
+
+        stack.top.grow(0.25);
+        assert_eq!(stack.get_dirty(), DirtyFlags::new(true, true));
+        assert_close(stack.update_layout(), 0.25);
+        stack.clear_dirty();
+        assert!(stack.get_dirty().is_clean());
+
+        assert_close(stack.top.block.points.p1y, 1.0);
+        assert_close(stack.top.block.points.p2y, 0.45);
+        assert_close(stack.bottom.block.points.p1y, 0.45);
+        assert_close(stack.bottom.block.points.p2y, 0.25);
+    }
+
+    #[test]
+    fn vstack_adds_the_growth_of_both_parts() {
+        let mut stack = VStackContainer::new(Growing::new(2.0, 0.3), Growing::new(2.0, 0.2));
+        stack.fill_width(1.0, 0.0, 1.0);
+
+        stack.top.grow(-0.1);
+        stack.bottom.grow(0.3);
+        assert_close(stack.update_layout(), 0.2);
+
+        assert_close(stack.top.block.points.p2y, 0.8);
+        assert_close(stack.bottom.block.points.p1y, 0.8);
+        assert_close(stack.bottom.block.points.p2y, 0.3);
+    }
+
+    fn capsule_count(draw_list: &DrawList) -> usize {
+        draw_list.0.iter().filter(|command| matches!(command, DrawCommand::Capsule(_))).count()
+    }
+
+    #[test]
+    fn styled_background_draws_its_capsules_before_the_child() {
+        let points = Points {
+            p1x: -0.5,
+            p1y: 0.5,
+            p2x: 0.5,
+            p2y: -0.5,
+        };
+        let fill = CapsuleFill::solid(0.1);
+        let background = Background::new(Block::at(-0.5, 0.5, 0.5, -0.5), fill).with_border(0.02);
+        let expected = fill_capsules(points, fill).len()+outline_capsules(points, 0.02).len();
+        assert!(expected > 0);
+
+        let draw_list: DrawList = background.construct_styled(&Theme::DARK);
+        assert_eq!(draw_list.0.len(), expected);
+        assert_eq!(capsule_count(&draw_list), expected);
+
+        let shapes: ShapeTarget = background.construct_styled(&Theme::DARK);
+        assert!(shapes.lines.0.is_empty());
+        assert_eq!(shapes.capsules.0.len(), expected);
+    }
+
+    #[test]
+    fn styled_background_without_fill_or_border_adds_nothing() {
+        let mut background = Background::new(Block::at(-0.5, 0.5, 0.5, -0.5), CapsuleFill::solid(0.1));
+        background.set_fill(None);
+        let draw_list: DrawList = background.construct_styled(&Theme::DARK);
+        assert!(draw_list.0.is_empty());
+    }
 }
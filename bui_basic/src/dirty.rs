@@ -0,0 +1,129 @@
+use bui::rect::SizeAndCenter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DirtyFlags {
+    // the size changed and the parent has to lay out what depends on it
+    pub layout: bool,
+    // the geometry changed and has to be constructed again
+    pub construct: bool,
+}
+
+impl DirtyFlags {
+    pub const CLEAN: Self = Self::new(false, false);
+
+    pub const fn new(layout: bool, construct: bool) -> Self {
+        Self {
+            layout,
+            construct,
+        }
+    }
+
+    pub fn or(self, other: Self) -> Self {
+        Self::new(self.layout || other.layout, self.construct || other.construct)
+    }
+
+    pub fn is_clean(&self) -> bool {
+        !self.layout && !self.construct
+    }
+}
+
+// The last call that placed a widget, kept by containers that have to redo it.
+#[derive(Debug, Clone, Copy)]
+pub enum LayoutTarget {
+    Fill(SizeAndCenter),
+    FillWidth(f32, f32, f32),
+}
+
+impl LayoutTarget {
+    pub fn translate_x(&mut self, dx: f32) {
+        match self {
+            LayoutTarget::Fill(fill_target) => fill_target.cx += dx,
+            LayoutTarget::FillWidth(_, cx, _) => *cx += dx
+        }
+    }
+
+    pub fn translate_y(&mut self, dy: f32) {
+        match self {
+            LayoutTarget::Fill(fill_target) => fill_target.cy += dy,
+            LayoutTarget::FillWidth(_, _, ty) => *ty += dy
+        }
+    }
+}
+
+// After signals were handled call update_layout on the root, then construct if the root
+// reports construct, then clear_dirty.
+pub trait Dirty {
+    // flags of this widget and everything below it
+    fn get_dirty(&self) -> DirtyFlags;
+    // lays out again only what changed, in the place it already had, and returns by how much
+    // the height grew so the parent can move whatever comes after it
+    fn update_layout(&mut self) -> f32;
+    fn clear_dirty(&mut self);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{column::VecColumn, containers::FillWidth, test_util::Growing};
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a-b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn flags_combine_per_kind() {
+        let layout = DirtyFlags::new(true, false);
+        let construct = DirtyFlags::new(false, true);
+        assert_eq!(DirtyFlags::CLEAN.or(layout), layout);
+        assert_eq!(construct.or(DirtyFlags::CLEAN), construct);
+        assert_eq!(layout.or(construct), DirtyFlags::new(true, true));
+        assert_eq!(DirtyFlags::CLEAN.or(DirtyFlags::CLEAN), DirtyFlags::CLEAN);
+    }
+
+    #[test]
+    fn only_no_flags_is_clean() {
+        assert!(DirtyFlags::CLEAN.is_clean());
+        assert!(DirtyFlags::default().is_clean());
+        assert!(!DirtyFlags::new(true, false).is_clean());
+        assert!(!DirtyFlags::new(false, true).is_clean());
+        assert!(!DirtyFlags::new(true, true).is_clean());
+    }
+
+    #[test]
+    fn column_moves_the_children_after_a_grown_one() {
+        let mut column = VecColumn::from(vec![Growing::new(2.0, 0.2), Growing::new(2.0, 0.2), Growing::new(2.0, 0.2)]);
+        assert_close(column.fill_width(1.0, 0.0, 1.0), 0.4);
+        assert!(column.get_dirty().is_clean());
+
+        column.get_children_mut()[1].grow(0.1);
+        assert_eq!(column.get_dirty(), DirtyFlags::new(true, true));
+        assert_close(column.update_layout(), 0.1);
+        column.clear_dirty();
+        assert!(column.get_dirty().is_clean());
+
+        let children = column.get_children_mut();
+        // the one above stays, the grown one keeps its top, the one below moves down
+        assert_close(children[0].block.points.p1y, 1.0);
+        assert_close(children[0].block.points.p2y, 0.8);
+        assert_close(children[1].block.points.p1y, 0.8);
+        assert_close(children[1].block.points.p2y, 0.5);
+        assert_close(children[2].block.points.p1y, 0.5);
+        assert_close(children[2].block.points.p2y, 0.3);
+    }
+
+    #[test]
+    fn column_adds_up_the_changes_of_several_children() {
+        let mut column = VecColumn::from(vec![Growing::new(2.0, 0.2), Growing::new(2.0, 0.2), Growing::new(2.0, 0.2)]);
+        column.fill_width(1.0, 0.0, 1.0);
+
+        column.get_children_mut()[0].grow(0.1);
+        column.get_children_mut()[1].grow(-0.05);
+        assert_close(column.update_layout(), 0.05);
+
+        let children = column.get_children_mut();
+        assert_close(children[1].block.points.p1y, 0.7);
+        assert_close(children[1].block.points.p2y, 0.55);
+        assert_close(children[2].block.points.p1y, 0.55);
+        assert_close(children[2].block.points.p2y, 0.35);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,358 @@
+use bui::{line::LineRaw, freeform_2dcapsule::Freeform2DCapsule, rect::Points};
+
+use crate::{construct::{StandardConstructTarget, LineTarget, Freeform2DCapsuleTarget, ShapeTarget}, style::{Stroke, FadeTarget}};
+
+#[derive(Debug, Clone)]
+pub enum DrawCommand {
+    Line(LineRaw),
+    Capsule(Freeform2DCapsule),
+    // nested clips are intersected with the ones around them
+    PushClip(Points),
+    PopClip,
+    // lasts until the next style change, also across appended lists
+    SetStroke(Stroke),
+}
+
+// Primitives in the order they are drawn, so lines and capsules can be constructed in one pass.
+#[derive(Debug, Clone)]
+pub struct DrawList (pub Vec<DrawCommand>);
+
+impl DrawList {
+    pub fn push_line(&mut self, line: LineRaw) {
+        self.0.push(DrawCommand::Line(line));
+    }
+
+    pub fn push_lines(&mut self, lines: Vec<LineRaw>) {
+        self.0.extend(lines.into_iter().map(DrawCommand::Line));
+    }
+
+    pub fn push_capsule(&mut self, capsule: Freeform2DCapsule) {
+        self.0.push(DrawCommand::Capsule(capsule));
+    }
+
+    pub fn push_capsules(&mut self, capsules: Vec<Freeform2DCapsule>) {
+        self.0.extend(capsules.into_iter().map(DrawCommand::Capsule));
+    }
+
+    pub fn push_clip(&mut self, clip: Points) {
+        self.0.push(DrawCommand::PushClip(clip));
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.0.push(DrawCommand::PopClip);
+    }
+
+    pub fn set_stroke(&mut self, stroke: Stroke) {
+        self.0.push(DrawCommand::SetStroke(stroke));
+    }
+
+    // all lines and all capsules, dropping order, clips and styles
+    pub fn split(self) -> (LineTarget, Freeform2DCapsuleTarget) {
+        let mut lines = LineTarget::EMPTY;
+        let mut capsules = Freeform2DCapsuleTarget::EMPTY;
+        for command in self.0 {
+            match command {
+                DrawCommand::Line(line) => lines.0.push(line),
+                DrawCommand::Capsule(capsule) => capsules.0.push(capsule),
+                _ => {}
+            }
+        }
+        (lines, capsules)
+    }
+
+    // Runs of the same primitive with no clip or style change in between, in draw order.
+    // Unbalanced pops are ignored.
+    pub fn into_batches(self) -> Vec<DrawBatch> {
+        let mut batches: Vec<DrawBatch> = Vec::new();
+        let mut clips: Vec<Points> = Vec::new();
+        let mut stroke = None;
+        // a new batch has to be started for the next primitive
+        let mut state_changed = true;
+
+        for command in self.0 {
+            match command {
+                DrawCommand::Line(line) => {
+                    match batches.last_mut() {
+                        Some(DrawBatch { primitives: BatchPrimitives::Lines(lines), .. }) if !state_changed => lines.0.push(line),
+                        _ => batches.push(DrawBatch {
+                            primitives: BatchPrimitives::Lines(LineTarget(vec![line])),
+                            clip: clips.last().copied(),
+                            stroke,
+                        })
+                    }
+                    state_changed = false;
+                },
+                DrawCommand::Capsule(capsule) => {
+                    match batches.last_mut() {
+                        Some(DrawBatch { primitives: BatchPrimitives::Capsules(capsules), .. }) if !state_changed => capsules.0.push(capsule),
+                        _ => batches.push(DrawBatch {
+                            primitives: BatchPrimitives::Capsules(Freeform2DCapsuleTarget(vec![capsule])),
+                            clip: clips.last().copied(),
+                            stroke,
+                        })
+                    }
+                    state_changed = false;
+                },
+                DrawCommand::PushClip(clip) => {
+                    let clip = match clips.last() {
+                        Some(outer) => intersect_points(*outer, clip),
+                        None => clip
+                    };
+                    clips.push(clip);
+                    state_changed = true;
+                },
+                DrawCommand::PopClip => {
+                    clips.pop();
+                    state_changed = true;
+                },
+                DrawCommand::SetStroke(new_stroke) => {
+                    stroke = Some(new_stroke);
+                    state_changed = true;
+                }
+            }
+        }
+        batches
+    }
+}
+
+// Primitives before the first stroke change use the renderer's default and cannot be faded,
+// so lists that do not start with one are not fadeable.
+impl FadeTarget for DrawList {
+    fn can_fade(&self) -> bool {
+        matches!(self.0.first(), Some(DrawCommand::SetStroke(_)) | None)
+    }
+
+    fn fade(&mut self, alpha: f32) {
+        for command in &mut self.0 {
+            if let DrawCommand::SetStroke(stroke) = command {
+                *stroke = stroke.with_alpha(alpha);
+            }
+        }
+    }
+}
+
+impl StandardConstructTarget for DrawList {
+    const EMPTY: Self = DrawList(vec![]);
+
+    fn append(&mut self, mut value: Self) {
+        self.0.append(&mut value.0);
+    }
+
+    fn append_into(mut self, mut value: Self) -> Self {
+        self.0.append(&mut value.0);
+        self
+    }
+}
+
+impl Default for DrawList {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl From<LineTarget> for DrawList {
+    fn from(target: LineTarget) -> Self {
+        Self(target.0.into_iter().map(DrawCommand::Line).collect())
+    }
+}
+
+impl From<Freeform2DCapsuleTarget> for DrawList {
+    fn from(target: Freeform2DCapsuleTarget) -> Self {
+        Self(target.0.into_iter().map(DrawCommand::Capsule).collect())
+    }
+}
+
+// capsules first, they are usually backgrounds
+impl From<ShapeTarget> for DrawList {
+    fn from(target: ShapeTarget) -> Self {
+        Self::from(target.capsules).append_into(Self::from(target.lines))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BatchPrimitives {
+    Lines(LineTarget),
+    Capsules(Freeform2DCapsuleTarget),
+}
+
+#[derive(Debug, Clone)]
+pub struct DrawBatch {
+    pub primitives: BatchPrimitives,
+    pub clip: Option<Points>,
+    pub stroke: Option<Stroke>,
+}
+
+// an empty intersection collapses to a zero sized rectangle
+fn intersect_points(a: Points, b: Points) -> Points {
+    let p1x = a.p1x.max(b.p1x);
+    let p2x = a.p2x.min(b.p2x).max(p1x);
+    let p1y = a.p1y.min(b.p1y);
+    let p2y = a.p2y.max(b.p2y).min(p1y);
+    Points {
+        p1x,
+        p1y,
+        p2x,
+        p2y,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::construct::capsule;
+
+    const LINE: LineRaw = LineRaw {
+        p1: [0.0, 0.0],
+        p2: [1.0, 0.0],
+    };
+    const RED: Stroke = Stroke::new([1.0, 0.0, 0.0, 1.0], 1.0);
+    const BLUE: Stroke = Stroke::new([0.0, 0.0, 1.0, 1.0], 2.0);
+
+    fn points(p1x: f32, p1y: f32, p2x: f32, p2y: f32) -> Points {
+        Points {
+            p1x,
+            p1y,
+            p2x,
+            p2y,
+        }
+    }
+
+    fn assert_points(actual: Option<Points>, expected: Option<Points>) {
+        match (actual, expected) {
+            (Some(a), Some(b)) => assert!(
+                a.p1x == b.p1x && a.p1y == b.p1y && a.p2x == b.p2x && a.p2y == b.p2y,
+                "{:?} != {:?}", a, b
+            ),
+            (None, None) => {},
+            _ => panic!("{:?} != {:?}", actual, expected)
+        }
+    }
+
+    fn line_count(batch: &DrawBatch) -> usize {
+        match &batch.primitives {
+            BatchPrimitives::Lines(lines) => lines.0.len(),
+            BatchPrimitives::Capsules(_) => panic!("expected lines")
+        }
+    }
+
+    fn capsule_count(batch: &DrawBatch) -> usize {
+        match &batch.primitives {
+            BatchPrimitives::Capsules(capsules) => capsules.0.len(),
+            BatchPrimitives::Lines(_) => panic!("expected capsules")
+        }
+    }
+
+    #[test]
+    fn runs_of_one_primitive_share_a_batch() {
+        let mut draw_list = DrawList::EMPTY;
+        draw_list.push_lines(vec![LINE, LINE]);
+        draw_list.push_capsules(vec![capsule([0.0, 0.0], [1.0, 0.0], 0.1); 3]);
+        draw_list.push_line(LINE);
+
+        let batches = draw_list.into_batches();
+        assert_eq!(batches.len(), 3);
+        assert_eq!(line_count(&batches[0]), 2);
+        assert_eq!(capsule_count(&batches[1]), 3);
+        assert_eq!(line_count(&batches[2]), 1);
+        assert!(batches.iter().all(|batch| batch.clip.is_none() && batch.stroke.is_none()));
+    }
+
+    #[test]
+    fn stroke_changes_start_a_new_batch() {
+        let mut draw_list = DrawList::EMPTY;
+        draw_list.push_line(LINE);
+        draw_list.set_stroke(RED);
+        draw_list.push_lines(vec![LINE, LINE]);
+        draw_list.set_stroke(BLUE);
+        draw_list.push_line(LINE);
+
+        let batches = draw_list.into_batches();
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].stroke, None);
+        assert_eq!(batches[1].stroke, Some(RED));
+        assert_eq!(line_count(&batches[1]), 2);
+        assert_eq!(batches[2].stroke, Some(BLUE));
+    }
+
+    #[test]
+    fn clip_changes_start_a_new_batch() {
+        let clip = points(-0.5, 0.5, 0.5, -0.5);
+        let mut draw_list = DrawList::EMPTY;
+        draw_list.push_line(LINE);
+        draw_list.push_clip(clip);
+        draw_list.push_line(LINE);
+        draw_list.pop_clip();
+        draw_list.push_line(LINE);
+
+        let batches = draw_list.into_batches();
+        assert_eq!(batches.len(), 3);
+        assert_points(batches[0].clip, None);
+        assert_points(batches[1].clip, Some(clip));
+        assert_points(batches[2].clip, None);
+    }
+
+    #[test]
+    fn nested_clips_are_intersected() {
+        let mut draw_list = DrawList::EMPTY;
+        draw_list.push_clip(points(-1.0, 1.0, 0.5, -0.5));
+        draw_list.push_clip(points(-0.5, 0.5, 1.0, -1.0));
+        draw_list.push_line(LINE);
+        // does not overlap the outer clip at all
+        draw_list.push_clip(points(0.8, 1.0, 1.0, 0.8));
+        draw_list.push_line(LINE);
+        draw_list.pop_clip();
+        draw_list.pop_clip();
+        draw_list.push_line(LINE);
+
+        let batches = draw_list.into_batches();
+        assert_eq!(batches.len(), 3);
+        assert_points(batches[0].clip, Some(points(-0.5, 0.5, 0.5, -0.5)));
+        assert_points(batches[1].clip, Some(points(0.8, 0.5, 0.8, 0.5)));
+        assert_points(batches[2].clip, Some(points(-1.0, 1.0, 0.5, -0.5)));
+    }
+
+    #[test]
+    fn unbalanced_pops_are_ignored() {
+        let clip = points(-0.5, 0.5, 0.5, -0.5);
+        let mut draw_list = DrawList::EMPTY;
+        draw_list.pop_clip();
+        draw_list.push_line(LINE);
+        draw_list.push_clip(clip);
+        draw_list.pop_clip();
+        draw_list.pop_clip();
+        draw_list.push_capsule(capsule([0.0, 0.0], [1.0, 0.0], 0.1));
+
+        let batches = draw_list.into_batches();
+        assert_eq!(batches.len(), 2);
+        assert_points(batches[0].clip, None);
+        assert_points(batches[1].clip, None);
+    }
+
+    #[test]
+    fn the_stroke_lasts_across_appended_lists() {
+        let mut first = DrawList::EMPTY;
+        first.set_stroke(RED);
+        first.push_line(LINE);
+        let second = DrawList::from(LineTarget(vec![LINE]));
+
+        let batches = first.append_into(second).into_batches();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].stroke, Some(RED));
+        assert_eq!(line_count(&batches[0]), 2);
+    }
+
+    #[test]
+    fn split_keeps_only_primitives() {
+        let mut draw_list = DrawList::EMPTY;
+        draw_list.set_stroke(RED);
+        draw_list.push_clip(points(-0.5, 0.5, 0.5, -0.5));
+        draw_list.push_line(LINE);
+        draw_list.push_capsule(capsule([0.0, 0.0], [1.0, 0.0], 0.1));
+        draw_list.pop_clip();
+        draw_list.push_line(LINE);
+
+        let (lines, capsules) = draw_list.split();
+        assert_eq!(lines.0.len(), 2);
+        assert_eq!(capsules.0.len(), 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,605 @@
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::{Construct, StandardConstructTarget}, containers::{Init, Fill, FillWidth, TranslateX, TranslateY, GetPointBounds}, signal::{SignalReciever, ReconstructCallback, Handled, RedrawCallback, ShortCircuitingCallback}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, Size, SizeHint}, dirty::{Dirty, DirtyFlags, LayoutTarget}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlexDirection {
+    Row,
+    Column,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Justify {
+    Start,
+    Center,
+    End,
+    SpaceBetween,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrossAlign {
+    Start,
+    Center,
+    End,
+    Stretch,
+}
+
+// All sizes are full lengths in normalized coordinates along the container's main axis,
+// except cross_size which is along the other axis.
+#[derive(Debug, Clone)]
+pub struct FlexItem<T> {
+    child: T,
+    basis: f32,
+    grow: f32,
+    shrink: f32,
+    min: f32,
+    max: f32,
+    cross_size: Option<f32>,
+}
+
+impl<T> FlexItem<T> {
+    pub fn new(child: T) -> Self {
+        Self {
+            child,
+            basis: 0.0,
+            grow: 1.0,
+            shrink: 1.0,
+            min: 0.0,
+            max: f32::INFINITY,
+            cross_size: None,
+        }
+    }
+
+    pub fn with_basis(mut self, basis: f32) -> Self {
+        self.basis = basis;
+        self
+    }
+
+    pub fn with_grow(mut self, grow: f32) -> Self {
+        self.grow = grow;
+        self
+    }
+
+    pub fn with_shrink(mut self, shrink: f32) -> Self {
+        self.shrink = shrink;
+        self
+    }
+
+    pub fn with_min(mut self, min: f32) -> Self {
+        self.min = min;
+        self
+    }
+
+    pub fn with_max(mut self, max: f32) -> Self {
+        self.max = max;
+        self
+    }
+
+    // without a cross size the child's preferred cross size is aligned, Stretch ignores both
+    pub fn with_cross_size(mut self, cross_size: f32) -> Self {
+        self.cross_size = Some(cross_size);
+        self
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    fn clamp(&self, size: f32) -> f32 {
+        size.max(self.min).min(self.max.max(self.min))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FlexContainer<T> {
+    items: Vec<FlexItem<T>>,
+    direction: FlexDirection,
+    justify: Justify,
+    cross_align: CrossAlign,
+    gap: f32,
+    points: Points,
+    layout_target: Option<LayoutTarget>,
+}
+
+impl<T> FlexContainer<T> {
+    pub fn new(direction: FlexDirection) -> Self {
+        Self {
+            items: Vec::new(),
+            direction,
+            justify: Justify::Start,
+            cross_align: CrossAlign::Stretch,
+            gap: 0.0,
+            points: Points::ZERO,
+            layout_target: None,
+        }
+    }
+
+    pub fn row() -> Self {
+        Self::new(FlexDirection::Row)
+    }
+
+    pub fn column() -> Self {
+        Self::new(FlexDirection::Column)
+    }
+
+    pub fn push(&mut self, item: FlexItem<T>) {
+        self.items.push(item);
+    }
+
+    pub fn into_push(mut self, item: FlexItem<T>) -> Self {
+        self.push(item);
+        self
+    }
+
+    pub fn set_justify(&mut self, justify: Justify) {
+        self.justify = justify;
+    }
+
+    pub fn with_justify(mut self, justify: Justify) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    pub fn set_cross_align(&mut self, cross_align: CrossAlign) {
+        self.cross_align = cross_align;
+    }
+
+    pub fn with_cross_align(mut self, cross_align: CrossAlign) -> Self {
+        self.cross_align = cross_align;
+        self
+    }
+
+    pub fn set_gap(&mut self, gap: f32) {
+        self.gap = gap;
+    }
+
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn get_items_mut(&mut self) -> &mut Vec<FlexItem<T>> {
+        &mut self.items
+    }
+
+    fn total_gap(&self) -> f32 {
+        self.gap*self.items.len().saturating_sub(1) as f32
+    }
+
+    // grows or shrinks the clamped bases until they fill main_size or every item hits its limit
+    fn compute_main_sizes(&self, main_size: f32) -> Vec<f32> {
+        let mut sizes: Vec<f32> = self.items.iter().map(|item| item.clamp(item.basis)).collect();
+        let mut frozen = vec![false; self.items.len()];
+
+        for _ in 0..self.items.len() {
+            let free = main_size-self.total_gap()-sizes.iter().sum::<f32>();
+            if free.abs() <= f32::EPSILON {
+                break;
+            }
+
+            let weights: Vec<f32> = self.items.iter().zip(&frozen).map(|(item, frozen)| match (frozen, free > 0.0) {
+                (true, _) => 0.0,
+                (false, true) => item.grow,
+                (false, false) => item.shrink*item.basis.max(f32::EPSILON)
+            }).collect();
+            let weight_total: f32 = weights.iter().sum();
+            if weight_total <= 0.0 {
+                break;
+            }
+
+            let mut clamped = false;
+            for (i, item) in self.items.iter().enumerate() {
+                if frozen[i] || weights[i] == 0.0 {
+                    continue;
+                }
+                let size = sizes[i]+free*weights[i]/weight_total;
+                let clamped_size = item.clamp(size);
+                if clamped_size != size {
+                    frozen[i] = true;
+                    clamped = true;
+                }
+                sizes[i] = clamped_size;
+            }
+            if !clamped {
+                break;
+            }
+        }
+        sizes
+    }
+
+    // offset of the first item and the spacing between items along the main axis
+    fn justify_offsets(&self, main_size: f32, sizes: &[f32]) -> (f32, f32) {
+        let leftover = (main_size-self.total_gap()-sizes.iter().sum::<f32>()).max(0.0);
+        match self.justify {
+            Justify::Start => (0.0, self.gap),
+            Justify::Center => (leftover/2.0, self.gap),
+            Justify::End => (leftover, self.gap),
+            Justify::SpaceBetween if sizes.len() > 1 => (0.0, self.gap+leftover/(sizes.len()-1) as f32),
+            Justify::SpaceBetween => (0.0, self.gap)
+        }
+    }
+
+    // offset and size of an item along the cross axis
+    fn cross_placement(&self, item: &FlexItem<T>, main_size: f32, cross_size: f32) -> (f32, f32)
+    where
+        T: Measure
+    {
+        if self.cross_align == CrossAlign::Stretch {
+            return (0.0, cross_size);
+        }
+
+        let size = item.cross_size.unwrap_or_else(|| {
+            let constraints = match self.direction {
+                FlexDirection::Row => Constraints::new(main_size, cross_size),
+                FlexDirection::Column => Constraints::new(cross_size, main_size)
+            };
+            let preferred = item.child.measure(constraints).preferred;
+            match self.direction {
+                FlexDirection::Row => preferred.height,
+                FlexDirection::Column => preferred.width
+            }
+        }).min(cross_size);
+        match self.cross_align {
+            CrossAlign::Start => (0.0, size),
+            CrossAlign::Center => ((cross_size-size)/2.0, size),
+            _ => (cross_size-size, size)
+        }
+    }
+
+    // positions are measured from the left and from the top
+    fn fill_items(&mut self, sizes: &[f32], main_size: f32, cross_size: f32, left: f32, ty: f32)
+    where
+        T: Fill+Measure
+    {
+        let (mut main_offset, spacing) = self.justify_offsets(main_size, sizes);
+        for i in 0..self.items.len() {
+            let (cross_offset, item_cross_size) = self.cross_placement(&self.items[i], sizes[i], cross_size);
+            let fill_target = match self.direction {
+                FlexDirection::Row => SizeAndCenter {
+                    sx: sizes[i]/2.0,
+                    sy: item_cross_size/2.0,
+                    cx: left+main_offset+sizes[i]/2.0,
+                    cy: ty-cross_offset-item_cross_size/2.0,
+                },
+                FlexDirection::Column => SizeAndCenter {
+                    sx: item_cross_size/2.0,
+                    sy: sizes[i]/2.0,
+                    cx: left+cross_offset+item_cross_size/2.0,
+                    cy: ty-main_offset-sizes[i]/2.0,
+                }
+            };
+            self.items[i].child.fill(fill_target);
+            main_offset += sizes[i]+spacing;
+        }
+    }
+}
+
+impl<T: Init> Init for FlexContainer<T> {
+    fn init(&mut self) {
+        for item in &mut self.items {
+            item.child.init();
+        }
+    }
+}
+
+impl<T: Fill+Measure> Fill for FlexContainer<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        let (main_size, cross_size) = match self.direction {
+            FlexDirection::Row => (fill_target.sx*2.0, fill_target.sy*2.0),
+            FlexDirection::Column => (fill_target.sy*2.0, fill_target.sx*2.0)
+        };
+
+        let sizes = self.compute_main_sizes(main_size);
+        self.fill_items(&sizes, main_size, cross_size, fill_target.cx-fill_target.sx, fill_target.cy+fill_target.sy);
+        self.points = fill_target.into();
+        self.layout_target = Some(LayoutTarget::Fill(fill_target));
+    }
+}
+
+// A row takes the height of its tallest child, a column grows to fit its children
+// since there is no main size to grow into or shrink from.
+impl<T: Fill+FillWidth+Measure> FillWidth for FlexContainer<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        let left = cx-sx;
+        self.layout_target = Some(LayoutTarget::FillWidth(sx, cx, ty));
+        let height = match self.direction {
+            FlexDirection::Row => {
+                let sizes = self.compute_main_sizes(sx*2.0);
+                let (mut main_offset, spacing) = self.justify_offsets(sx*2.0, &sizes);
+                let mut height: f32 = 0.0;
+                for (item, size) in self.items.iter_mut().zip(&sizes) {
+                    height = height.max(ty-item.child.fill_width(size/2.0, left+main_offset+size/2.0, ty));
+                    main_offset += size+spacing;
+                }
+                self.fill_items(&sizes, sx*2.0, height, left, ty);
+                height
+            },
+            FlexDirection::Column => {
+                let mut sizes = Vec::with_capacity(self.items.len());
+                for item in &mut self.items {
+                    let content_height = ty-item.child.fill_width(sx, cx, ty);
+                    sizes.push(item.clamp(content_height.max(item.basis)));
+                }
+                let height = sizes.iter().sum::<f32>()+self.total_gap();
+                self.fill_items(&sizes, height, sx*2.0, left, ty);
+                height
+            }
+        };
+
+        self.points = Points {
+            p1x: left,
+            p1y: ty,
+            p2x: cx+sx,
+            p2y: ty-height,
+        };
+        ty-height
+    }
+}
+
+impl<T: Measure> Measure for FlexContainer<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let to_main_cross = |size: Size| match self.direction {
+            FlexDirection::Row => (size.width, size.height),
+            FlexDirection::Column => (size.height, size.width)
+        };
+        let from_main_cross = |main: f32, cross: f32| match self.direction {
+            FlexDirection::Row => Size::new(main, cross),
+            FlexDirection::Column => Size::new(cross, main)
+        };
+
+        let (mut min_main, mut preferred_main, mut max_main) = (self.total_gap(), self.total_gap(), self.total_gap());
+        let (mut min_cross, mut preferred_cross): (f32, f32) = (0.0, 0.0);
+        for item in &self.items {
+            let child = item.child.measure(constraints);
+            let (child_main, child_cross) = to_main_cross(child.preferred);
+            min_main += item.min;
+            preferred_main += item.clamp(item.basis.max(child_main));
+            max_main += if item.grow > 0.0 { f32::INFINITY } else { item.clamp(item.basis.max(child_main)) };
+
+            let cross = item.cross_size.unwrap_or(child_cross);
+            min_cross = min_cross.max(to_main_cross(child.min).1.min(cross));
+            preferred_cross = preferred_cross.max(cross);
+        }
+
+        SizeHint {
+            min: from_main_cross(min_main, min_cross),
+            preferred: constraints.constrain(from_main_cross(preferred_main, preferred_cross)),
+            max: from_main_cross(max_main, f32::INFINITY),
+        }.normalized()
+    }
+}
+
+// a child that changed height moves the tracks or items after it, so the last layout is redone
+impl<T: Dirty+Fill+FillWidth+Measure> Dirty for FlexContainer<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.items.iter().fold(DirtyFlags::CLEAN, |dirty, item| dirty.or(item.child.get_dirty()))
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let mut changed = false;
+        for item in &mut self.items {
+            changed |= item.child.update_layout() != 0.0;
+        }
+        if !changed {
+            return 0.0;
+        }
+
+        match self.layout_target {
+            Some(LayoutTarget::Fill(fill_target)) => {
+                self.fill(fill_target);
+                0.0
+            },
+            Some(LayoutTarget::FillWidth(sx, cx, ty)) => {
+                let by = self.points.p2y;
+                by-self.fill_width(sx, cx, ty)
+            },
+            None => 0.0
+        }
+    }
+
+    fn clear_dirty(&mut self) {
+        for item in &mut self.items {
+            item.child.clear_dirty();
+        }
+    }
+}
+
+impl<T: TranslateX> TranslateX for FlexContainer<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.points.p1x += dx;
+        self.points.p2x += dx;
+        if let Some(layout_target) = self.layout_target.as_mut() {
+            layout_target.translate_x(dx);
+        }
+        for item in &mut self.items {
+            item.child.translate_x(dx);
+        }
+    }
+}
+
+impl<T: TranslateY> TranslateY for FlexContainer<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.points.p1y += dy;
+        self.points.p2y += dy;
+        if let Some(layout_target) = self.layout_target.as_mut() {
+            layout_target.translate_y(dy);
+        }
+        for item in &mut self.items {
+            item.child.translate_y(dy);
+        }
+    }
+}
+
+impl<T> GetPointBounds for FlexContainer<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.points
+    }
+}
+
+impl<C: StandardConstructTarget, T: Construct<C>> Construct<C> for FlexContainer<T> {
+    fn construct(&self) -> C {
+        let mut construct_target = C::EMPTY;
+        for item in &self.items {
+            construct_target.append(item.child.construct());
+        }
+        construct_target
+    }
+}
+
+impl<C: StandardConstructTarget, T: ConstructStyled<C>> ConstructStyled<C> for FlexContainer<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        let mut construct_target = C::EMPTY;
+        for item in &self.items {
+            construct_target.append(item.child.construct_styled(theme));
+        }
+        construct_target
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for FlexContainer<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        for item in &self.items {
+            item.child.construct_retained(target);
+        }
+    }
+}
+
+impl<T: SignalReciever<S, R>, S, R: Default> SignalReciever<S, Vec<R>> for FlexContainer<T> {
+    fn take_signal(&mut self, signal: &mut S) -> Vec<R> {
+        let mut responses = Vec::with_capacity(self.items.len());
+        for item in &mut self.items {
+            if signal.is_handled() {
+                responses.push(R::default());
+            } else {
+                responses.push(item.child.take_signal(signal));
+            }
+        }
+        responses
+    }
+}
+
+impl<T: SignalReciever<S, ReconstructCallback<C>>, S, C> SignalReciever<S, ReconstructCallback<C>> for FlexContainer<T> {
+    fn take_signal(&mut self, signal: &mut S) -> ReconstructCallback<C> {
+        let reconstruct_callbacks: Vec<ReconstructCallback<C>> = self.take_signal(signal);
+        for reconstruct_callback in reconstruct_callbacks {
+            if reconstruct_callback.get_reconstruct() {
+                return reconstruct_callback
+            }
+        }
+        ReconstructCallback::new(false)
+    }
+}
+
+// every child gets the signal, animations keep running even when another one already asked for a redraw
+impl<T: SignalReciever<S, RedrawCallback>, S> SignalReciever<S, RedrawCallback> for FlexContainer<T> {
+    fn take_signal(&mut self, signal: &mut S) -> RedrawCallback {
+        let redraw_callbacks: Vec<RedrawCallback> = self.take_signal(signal);
+        redraw_callbacks.into_iter().fold(RedrawCallback::new(false), |redraw_callback, other| redraw_callback.or_into(other))
+    }
+}
+
+impl<T: HitTest> HitTest for FlexContainer<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        for (i, item) in self.items.iter().enumerate().rev() {
+            path.push(i);
+            if item.child.hit_test(x, y, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+}
+
+impl<S, R: Default, T: RouteSignal<S, R>> RouteSignal<S, R> for FlexContainer<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        match path.first().and_then(|i| self.items.get_mut(*i)) {
+            Some(item) => item.child.route_signal(&path[1..], signal),
+            None => R::default()
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Block;
+
+    fn fill_target() -> SizeAndCenter {
+        SizeAndCenter {
+            sx: 1.0,
+            sy: 1.0,
+            cx: 0.0,
+            cy: 0.0,
+        }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a-b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    fn row(cross_align: CrossAlign) -> FlexContainer<Block> {
+        FlexContainer::row()
+            .with_cross_align(cross_align)
+            .into_push(FlexItem::new(Block::new(0.5, 0.4)))
+            .into_push(FlexItem::new(Block::new(0.5, 0.4)).with_cross_size(1.0))
+    }
+
+    #[test]
+    fn stretch_fills_the_cross_axis() {
+        let mut flex = row(CrossAlign::Stretch);
+        flex.fill(fill_target());
+        for item in flex.get_items_mut() {
+            assert_close(item.get_child().get_size().height, 2.0);
+        }
+    }
+
+    #[test]
+    fn cross_align_uses_the_measured_size() {
+        let mut flex = row(CrossAlign::Center);
+        flex.fill(fill_target());
+        let items = flex.get_items_mut();
+        assert_close(items[0].get_child().get_size().height, 0.4);
+        assert_close(items[0].get_child().get_center().1, 0.0);
+        // an explicit cross size wins over the measured one
+        assert_close(items[1].get_child().get_size().height, 1.0);
+    }
+
+    #[test]
+    fn cross_align_start_and_end() {
+        let mut flex = row(CrossAlign::Start);
+        flex.fill(fill_target());
+        assert_close(flex.get_items_mut()[0].get_child().points.p1y, 1.0);
+        assert_close(flex.get_items_mut()[0].get_child().points.p2y, 0.6);
+
+        let mut flex = row(CrossAlign::End);
+        flex.fill(fill_target());
+        assert_close(flex.get_items_mut()[0].get_child().points.p1y, -0.6);
+        assert_close(flex.get_items_mut()[0].get_child().points.p2y, -1.0);
+    }
+
+    #[test]
+    fn column_aligns_the_measured_width() {
+        let mut flex = FlexContainer::column()
+            .with_cross_align(CrossAlign::End)
+            .into_push(FlexItem::new(Block::new(0.5, 0.4)));
+        flex.fill(fill_target());
+        let child = flex.get_items_mut()[0].get_child();
+        assert_close(child.points.p1x, 0.5);
+        assert_close(child.points.p2x, 1.0);
+    }
+
+    #[test]
+    fn measured_cross_size_is_clamped_to_the_container() {
+        let mut flex = FlexContainer::row()
+            .with_cross_align(CrossAlign::Center)
+            .into_push(FlexItem::new(Block::new(0.5, 3.0)));
+        flex.fill(fill_target());
+        assert_close(flex.get_items_mut()[0].get_child().get_size().height, 2.0);
+    }
+}
\ No newline at end of file
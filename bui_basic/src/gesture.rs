@@ -0,0 +1,602 @@
+use std::time::Duration;
+
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::Construct, containers::{Fill, FillWidth, Init, TranslateX, TranslateY, GetPointBounds}, signal::{SignalReciever, forward_signals, ShortCircuitingCallback, CursorMovedSignal, MouseLeftDownSignal, MouseLeftUpSignal, ScrollSignal, FlingSignal, PinchSignal, TouchSignal, TouchPhase, TickSignal}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    Tap(CursorMovedSignal),
+    LongPress(CursorMovedSignal),
+    LongPressMoved(CursorMovedSignal),
+    LongPressEnded(CursorMovedSignal),
+    Pan {
+        position: CursorMovedSignal,
+        dx: f32,
+        dy: f32,
+    },
+    PanEnded(CursorMovedSignal),
+    Pinch {
+        scale: f32,
+        norm_centerx: f32,
+        norm_centery: f32,
+    },
+    Fling {
+        vx: f32,
+        vy: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrackedTouch {
+    id: u64,
+    start: TouchSignal,
+    last: TouchSignal,
+    vx: f32,
+    vy: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GestureState {
+    Idle,
+    Pending,
+    LongPressed,
+    Panning,
+    Pinching {
+        start_distance: f32,
+    },
+    // a multi-touch gesture ended but fingers are still down
+    Finished,
+}
+
+fn pixel_distance(a: &TouchSignal, b: &TouchSignal) -> f32 {
+    let dx = b.pixel_posx-a.pixel_posx;
+    let dy = b.pixel_posy-a.pixel_posy;
+    (dx*dx+dy*dy).sqrt()
+}
+
+#[derive(Debug, Clone)]
+pub struct GestureRecognizer {
+    long_press_duration: Duration,
+    tap_slop: f32,
+    fling_velocity: f32,
+    touches: Vec<TrackedTouch>,
+    state: GestureState,
+}
+
+impl GestureRecognizer {
+    pub const DEFAULT_LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+    pub const DEFAULT_TAP_SLOP: f32 = 10.0;
+    pub const DEFAULT_FLING_VELOCITY: f32 = 500.0;
+
+    pub fn new() -> Self {
+        Self::new_with_thresholds(Self::DEFAULT_LONG_PRESS_DURATION, Self::DEFAULT_TAP_SLOP, Self::DEFAULT_FLING_VELOCITY)
+    }
+
+    // tap_slop is in pixels and fling_velocity in pixels per second
+    pub fn new_with_thresholds(long_press_duration: Duration, tap_slop: f32, fling_velocity: f32) -> Self {
+        Self {
+            long_press_duration,
+            tap_slop,
+            fling_velocity,
+            touches: Vec::new(),
+            state: GestureState::Idle,
+        }
+    }
+
+    pub fn get_touch_count(&self) -> usize {
+        self.touches.len()
+    }
+
+    pub fn reset(&mut self) {
+        self.touches.clear();
+        self.state = GestureState::Idle;
+    }
+
+    fn touch_distance(&self) -> f32 {
+        if self.touches.len() < 2 {
+            0.0
+        } else {
+            pixel_distance(&self.touches[0].last, &self.touches[1].last)
+        }
+    }
+
+    // ends a single touch gesture that can't continue once a second finger lands
+    fn interrupt(&mut self, gestures: &mut Vec<Gesture>) {
+        let last = match self.touches.first() {
+            Some(touch) => touch.last.to_cursor_moved(),
+            None => return
+        };
+        match self.state {
+            GestureState::LongPressed => gestures.push(Gesture::LongPressEnded(last)),
+            GestureState::Panning => gestures.push(Gesture::PanEnded(last)),
+            _ => {}
+        }
+    }
+
+    pub fn take_touch(&mut self, signal: &TouchSignal) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+
+        match signal.phase {
+            TouchPhase::Started => {
+                self.touches.retain(|touch| touch.id != signal.id);
+                if self.touches.len() == 1 {
+                    self.interrupt(&mut gestures);
+                }
+                self.touches.push(TrackedTouch {
+                    id: signal.id,
+                    start: *signal,
+                    last: *signal,
+                    vx: 0.0,
+                    vy: 0.0,
+                });
+
+                self.state = match self.touches.len() {
+                    1 => GestureState::Pending,
+                    2 => GestureState::Pinching {
+                        start_distance: self.touch_distance(),
+                    },
+                    _ => GestureState::Finished,
+                };
+            },
+            TouchPhase::Moved => {
+                let (start, dx, dy) = match self.touches.iter_mut().find(|touch| touch.id == signal.id) {
+                    Some(touch) => {
+                        let dt = signal.timestamp.saturating_sub(touch.last.timestamp).as_secs_f32();
+                        let dx = signal.pixel_posx-touch.last.pixel_posx;
+                        let dy = signal.pixel_posy-touch.last.pixel_posy;
+                        if dt > 0.0 {
+                            touch.vx = dx/dt;
+                            touch.vy = dy/dt;
+                        }
+                        touch.last = *signal;
+                        (touch.start, dx, dy)
+                    },
+                    None => return gestures
+                };
+
+                match self.state {
+                    GestureState::Pending => {
+                        if pixel_distance(&start, signal) > self.tap_slop {
+                            self.state = GestureState::Panning;
+                            gestures.push(Gesture::Pan {
+                                position: signal.to_cursor_moved(),
+                                dx: signal.pixel_posx-start.pixel_posx,
+                                dy: signal.pixel_posy-start.pixel_posy,
+                            });
+                        } else if signal.timestamp.saturating_sub(start.timestamp) >= self.long_press_duration {
+                            self.state = GestureState::LongPressed;
+                            gestures.push(Gesture::LongPress(start.to_cursor_moved()));
+                        }
+                    },
+                    GestureState::Panning => gestures.push(Gesture::Pan {
+                        position: signal.to_cursor_moved(),
+                        dx,
+                        dy,
+                    }),
+                    GestureState::LongPressed => gestures.push(Gesture::LongPressMoved(signal.to_cursor_moved())),
+                    GestureState::Pinching { start_distance } => {
+                        if start_distance > 0.0 {
+                            let a = self.touches[0].last;
+                            let b = self.touches[1].last;
+                            gestures.push(Gesture::Pinch {
+                                scale: self.touch_distance()/start_distance,
+                                norm_centerx: (a.norm_posx+b.norm_posx)/2.0,
+                                norm_centery: (a.norm_posy+b.norm_posy)/2.0,
+                            });
+                        }
+                    },
+                    GestureState::Idle | GestureState::Finished => {}
+                }
+            },
+            TouchPhase::Ended => {
+                let touch = match self.touches.iter().position(|touch| touch.id == signal.id) {
+                    Some(i) => self.touches.remove(i),
+                    None => return gestures
+                };
+
+                match self.state {
+                    GestureState::Pending => {
+                        if signal.timestamp.saturating_sub(touch.start.timestamp) >= self.long_press_duration {
+                            gestures.push(Gesture::LongPress(touch.start.to_cursor_moved()));
+                            gestures.push(Gesture::LongPressEnded(signal.to_cursor_moved()));
+                        } else {
+                            gestures.push(Gesture::Tap(touch.start.to_cursor_moved()));
+                        }
+                    },
+                    GestureState::Panning => {
+                        // the fling comes first so it still reaches what is under the finger
+                        if (touch.vx*touch.vx+touch.vy*touch.vy).sqrt() >= self.fling_velocity {
+                            gestures.push(Gesture::Fling {
+                                vx: touch.vx,
+                                vy: touch.vy,
+                            });
+                        }
+                        gestures.push(Gesture::PanEnded(signal.to_cursor_moved()));
+                    },
+                    GestureState::LongPressed => gestures.push(Gesture::LongPressEnded(signal.to_cursor_moved())),
+                    GestureState::Pinching { .. } | GestureState::Idle | GestureState::Finished => {}
+                }
+
+                self.state = if self.touches.len() == 0 {
+                    GestureState::Idle
+                } else {
+                    GestureState::Finished
+                };
+            },
+            TouchPhase::Cancelled => {
+                self.interrupt(&mut gestures);
+                self.touches.retain(|touch| touch.id != signal.id);
+                self.state = if self.touches.len() == 0 {
+                    GestureState::Idle
+                } else {
+                    GestureState::Finished
+                };
+            }
+        }
+
+        gestures
+    }
+
+    // long presses are only noticed on touch events unless this is called periodically
+    pub fn poll(&mut self, now: Duration) -> Vec<Gesture> {
+        if self.state == GestureState::Pending && self.touches.len() == 1 {
+            let start = self.touches[0].start;
+            if now.saturating_sub(start.timestamp) >= self.long_press_duration {
+                self.state = GestureState::LongPressed;
+                return vec![Gesture::LongPress(start.to_cursor_moved())];
+            }
+        }
+        Vec::new()
+    }
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// everything the gestures of a TouchAdapter are delivered as
+pub trait GestureReciever<R>: SignalReciever<CursorMovedSignal, R>+SignalReciever<MouseLeftDownSignal, R>+SignalReciever<MouseLeftUpSignal, R>+SignalReciever<ScrollSignal, R>+SignalReciever<FlingSignal, R>+SignalReciever<PinchSignal, R> {}
+
+impl<R, T> GestureReciever<R> for T
+where
+    T: SignalReciever<CursorMovedSignal, R>+SignalReciever<MouseLeftDownSignal, R>+SignalReciever<MouseLeftUpSignal, R>+SignalReciever<ScrollSignal, R>+SignalReciever<FlingSignal, R>+SignalReciever<PinchSignal, R>
+{}
+
+// Turns touches into the mouse and scroll signals the widgets already understand:
+// a tap clicks, a long press starts a drag (text selection), a pan scrolls and a fling
+// keeps a ScrollContainer coasting. Pinches are passed on as PinchSignal.
+#[derive(Debug, Clone)]
+pub struct TouchAdapter<T> {
+    child: T,
+    recognizer: GestureRecognizer,
+    last_gestures: Vec<Gesture>,
+}
+
+impl<T> TouchAdapter<T> {
+    pub fn new(child: T) -> Self {
+        Self::new_with_recognizer(child, GestureRecognizer::new())
+    }
+
+    pub fn new_with_recognizer(child: T, recognizer: GestureRecognizer) -> Self {
+        Self {
+            child,
+            recognizer,
+            last_gestures: Vec::new(),
+        }
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    // gestures recognized by the last touch signal or poll, including ones without a widget mapping
+    pub fn get_last_gestures(&self) -> &Vec<Gesture> {
+        &self.last_gestures
+    }
+
+    pub fn poll<R: ShortCircuitingCallback+Default>(&mut self, now: Duration) -> R
+    where
+        T: GestureReciever<R>
+    {
+        self.last_gestures = self.recognizer.poll(now);
+        self.deliver_gestures()
+    }
+
+    fn deliver_gestures<R: ShortCircuitingCallback+Default>(&mut self) -> R
+    where
+        T: GestureReciever<R>
+    {
+        let mut callback = R::default();
+        for gesture in self.last_gestures.clone() {
+            callback = callback.or_into(self.deliver_gesture(gesture));
+        }
+        callback
+    }
+
+    fn deliver_gesture<R: ShortCircuitingCallback+Default>(&mut self, gesture: Gesture) -> R
+    where
+        T: GestureReciever<R>
+    {
+        // touches don't hover, so the cursor is moved away once a gesture lets go
+        let mut released = CursorMovedSignal::OCCLUDED;
+        match gesture {
+            Gesture::Tap(mut position) => self.child.take_signal(&mut position)
+                .or_into(self.child.take_signal(&mut MouseLeftDownSignal()))
+                .or_into(self.child.take_signal(&mut MouseLeftUpSignal()))
+                .or_into(self.child.take_signal(&mut released)),
+            Gesture::LongPress(mut position) => self.child.take_signal(&mut position)
+                .or_into(self.child.take_signal(&mut MouseLeftDownSignal())),
+            Gesture::LongPressMoved(mut position) => self.child.take_signal(&mut position),
+            Gesture::LongPressEnded(mut position) => self.child.take_signal(&mut position)
+                .or_into(self.child.take_signal(&mut MouseLeftUpSignal()))
+                .or_into(self.child.take_signal(&mut released)),
+            Gesture::Pan { mut position, dx, dy } => self.child.take_signal(&mut position)
+                .or_into(self.child.take_signal(&mut ScrollSignal {
+                    px: dx,
+                    py: dy,
+                })),
+            Gesture::PanEnded(_) => self.child.take_signal(&mut released),
+            Gesture::Pinch { scale, norm_centerx, norm_centery } => self.child.take_signal(&mut PinchSignal {
+                scale,
+                norm_centerx,
+                norm_centery,
+            }),
+            Gesture::Fling { vx, vy } => self.child.take_signal(&mut FlingSignal {
+                vx,
+                vy,
+            })
+        }
+    }
+}
+
+impl<T: Init> Init for TouchAdapter<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
+}
+
+impl<T: Fill> Fill for TouchAdapter<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.child.fill(fill_target);
+    }
+}
+
+impl<T: FillWidth> FillWidth for TouchAdapter<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        self.child.fill_width(sx, cx, ty)
+    }
+}
+
+impl<T: Dirty> Dirty for TouchAdapter<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.child.update_layout()
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<T: Measure> Measure for TouchAdapter<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.child.measure(constraints)
+    }
+}
+
+impl<T: TranslateX> TranslateX for TouchAdapter<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.child.translate_x(dx);
+    }
+}
+
+impl<T: TranslateY> TranslateY for TouchAdapter<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.child.translate_y(dy);
+    }
+}
+
+impl<T: GetPointBounds> GetPointBounds for TouchAdapter<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.child.get_point_bounds()
+    }
+}
+
+impl<T: HitTest> HitTest for TouchAdapter<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for TouchAdapter<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for TouchAdapter<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for TouchAdapter<T> {
+    fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for TouchAdapter<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
+impl<R: ShortCircuitingCallback+Default, T: GestureReciever<R>> SignalReciever<TouchSignal, R> for TouchAdapter<T> {
+    fn take_signal(&mut self, signal: &mut TouchSignal) -> R {
+        self.last_gestures = self.recognizer.take_touch(signal);
+        self.deliver_gestures()
+    }
+}
+
+// a finger held still never sends another touch, so long presses are noticed on ticks
+impl<R: ShortCircuitingCallback+Default, T: GestureReciever<R>+SignalReciever<TickSignal, R>> SignalReciever<TickSignal, R> for TouchAdapter<T> {
+    fn take_signal(&mut self, signal: &mut TickSignal) -> R {
+        let callback: R = self.poll(signal.now);
+        callback.or_into(self.child.take_signal(signal))
+    }
+}
+
+forward_signals!(TouchAdapter.child: CursorMovedSignal, MouseLeftDownSignal, MouseLeftUpSignal, MouseButtonSignal, ClickSignal, ResizedSignal, ScaleFactorSignal, ScrollSignal, FlingSignal, PinchSignal, CharacterInputSignal, ImeSignal, KeySignal);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{construct::LineTarget, signal::ReconstructCallback, scroll::ScrollContainer, test_util::Block};
+
+    type Reconstruct = ReconstructCallback<LineTarget>;
+
+    // a 720 by 720 pixel window
+    fn touch(id: u64, phase: TouchPhase, pixel_posx: f32, pixel_posy: f32, millis: u64) -> TouchSignal {
+        TouchSignal {
+            id,
+            phase,
+            pixel_posx,
+            pixel_posy,
+            norm_posx: pixel_posx/360.0-1.0,
+            norm_posy: 1.0-pixel_posy/360.0,
+            timestamp: Duration::from_millis(millis),
+        }
+    }
+
+    fn tick(millis: u64) -> TickSignal {
+        TickSignal {
+            dt: Duration::from_millis(16),
+            now: Duration::from_millis(millis),
+        }
+    }
+
+    fn adapter() -> TouchAdapter<Block> {
+        TouchAdapter::new(Block::at(-1.0, 1.0, 1.0, -1.0))
+    }
+
+    #[test]
+    fn tap_presses_and_releases() {
+        let mut adapter = adapter();
+        let _: Reconstruct = adapter.take_signal(&mut touch(0, TouchPhase::Started, 360.0, 360.0, 0));
+        let _: Reconstruct = adapter.take_signal(&mut touch(0, TouchPhase::Ended, 362.0, 360.0, 80));
+
+        assert!(matches!(adapter.get_last_gestures()[..], [Gesture::Tap(_)]));
+        assert_eq!(adapter.get_child().received, vec!["CursorMovedSignal", "MouseLeftDownSignal", "MouseLeftUpSignal", "CursorMovedSignal"]);
+    }
+
+    #[test]
+    fn long_press_fires_on_tick_without_further_touches() {
+        let mut adapter = adapter();
+        let _: Reconstruct = adapter.take_signal(&mut touch(0, TouchPhase::Started, 360.0, 360.0, 0));
+        let _: Reconstruct = adapter.take_signal(&mut tick(200));
+        assert!(adapter.get_child().received.iter().all(|name| *name == "TickSignal"));
+
+        let _: Reconstruct = adapter.take_signal(&mut tick(600));
+        assert!(matches!(adapter.get_last_gestures()[..], [Gesture::LongPress(_)]));
+        assert_eq!(adapter.get_child().received, vec!["TickSignal", "CursorMovedSignal", "MouseLeftDownSignal", "TickSignal"]);
+    }
+
+    #[test]
+    fn pan_reports_pixel_deltas() {
+        let mut recognizer = GestureRecognizer::new();
+        assert!(recognizer.take_touch(&touch(0, TouchPhase::Started, 100.0, 400.0, 0)).is_empty());
+        // within the tap slop
+        assert!(recognizer.take_touch(&touch(0, TouchPhase::Moved, 100.0, 395.0, 10)).is_empty());
+
+        let gestures = recognizer.take_touch(&touch(0, TouchPhase::Moved, 100.0, 380.0, 20));
+        assert!(matches!(gestures[..], [Gesture::Pan { dx, dy, .. }] if dx == 0.0 && dy == -20.0));
+        let gestures = recognizer.take_touch(&touch(0, TouchPhase::Moved, 110.0, 370.0, 30));
+        assert!(matches!(gestures[..], [Gesture::Pan { dx, dy, .. }] if dx == 10.0 && dy == -10.0));
+    }
+
+    #[test]
+    fn pan_is_delivered_as_scroll() {
+        let mut adapter = adapter();
+        let _: Reconstruct = adapter.take_signal(&mut touch(0, TouchPhase::Started, 100.0, 400.0, 0));
+        let _: Reconstruct = adapter.take_signal(&mut touch(0, TouchPhase::Moved, 100.0, 380.0, 500));
+        assert_eq!(adapter.get_child().received, vec!["CursorMovedSignal", "ScrollSignal"]);
+    }
+
+    #[test]
+    fn pinch_scales_by_touch_distance() {
+        let mut adapter = adapter();
+        let _: Reconstruct = adapter.take_signal(&mut touch(0, TouchPhase::Started, 300.0, 360.0, 0));
+        let _: Reconstruct = adapter.take_signal(&mut touch(1, TouchPhase::Started, 400.0, 360.0, 10));
+        let _: Reconstruct = adapter.take_signal(&mut touch(1, TouchPhase::Moved, 500.0, 360.0, 20));
+
+        match adapter.get_last_gestures()[..] {
+            [Gesture::Pinch { scale, norm_centerx, norm_centery }] => {
+                assert!((scale-2.0).abs() < 1e-5);
+                assert!((norm_centerx-(400.0/360.0-1.0)).abs() < 1e-5);
+                assert!(norm_centery.abs() < 1e-5);
+            },
+            ref gestures => panic!("expected a pinch, got {:?}", gestures)
+        }
+        assert_eq!(adapter.get_child().received, vec!["PinchSignal"]);
+    }
+
+    #[test]
+    fn fast_release_flings_before_the_pan_ends() {
+        let mut recognizer = GestureRecognizer::new();
+        recognizer.take_touch(&touch(0, TouchPhase::Started, 100.0, 400.0, 0));
+        recognizer.take_touch(&touch(0, TouchPhase::Moved, 100.0, 300.0, 50));
+
+        let gestures = recognizer.take_touch(&touch(0, TouchPhase::Ended, 100.0, 300.0, 60));
+        match gestures[..] {
+            [Gesture::Fling { vx, vy }, Gesture::PanEnded(_)] => {
+                assert_eq!(vx, 0.0);
+                assert!((vy+2000.0).abs() < 1.0);
+            },
+            ref gestures => panic!("expected a fling, got {:?}", gestures)
+        }
+    }
+
+    #[test]
+    fn slow_release_does_not_fling() {
+        let mut recognizer = GestureRecognizer::new();
+        recognizer.take_touch(&touch(0, TouchPhase::Started, 100.0, 400.0, 0));
+        recognizer.take_touch(&touch(0, TouchPhase::Moved, 100.0, 380.0, 1000));
+
+        let gestures = recognizer.take_touch(&touch(0, TouchPhase::Ended, 100.0, 380.0, 1010));
+        assert!(matches!(gestures[..], [Gesture::PanEnded(_)]));
+    }
+
+    #[test]
+    fn fling_keeps_a_scroll_container_coasting() {
+        let scroll = ScrollContainer::new(Block::new(2.0, 4.0)).with_momentum(ScrollContainer::<Block>::DEFAULT_FRICTION);
+        let mut adapter = TouchAdapter::new(scroll);
+        adapter.fill(SizeAndCenter {
+            sx: 1.0,
+            sy: 1.0,
+            cx: 0.0,
+            cy: 0.0,
+        });
+
+        let _: Reconstruct = adapter.take_signal(&mut touch(0, TouchPhase::Started, 360.0, 400.0, 0));
+        let _: Reconstruct = adapter.take_signal(&mut touch(0, TouchPhase::Moved, 360.0, 300.0, 50));
+        let _: Reconstruct = adapter.take_signal(&mut touch(0, TouchPhase::Ended, 360.0, 300.0, 60));
+        let offset = adapter.get_child().get_offset();
+        assert!(offset > 0.0);
+
+        let reconstruct_callback: Reconstruct = adapter.take_signal(&mut tick(76));
+        assert!(reconstruct_callback.get_reconstruct());
+        assert!(adapter.get_child().get_offset() > offset);
+    }
+}
@@ -0,0 +1,544 @@
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::{Construct, StandardConstructTarget}, containers::{Init, Fill, FillWidth, TranslateX, TranslateY, GetPointBounds}, signal::{SignalReciever, ReconstructCallback, Handled, RedrawCallback, ShortCircuitingCallback}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, Size, SizeHint}, dirty::{Dirty, DirtyFlags, LayoutTarget}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}};
+
+// Fixed sizes are full lengths in normalized coordinates, not half sizes like SizeAndCenter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    Fixed(f32),
+    Fraction(f32),
+    // content sized tracks take the preferred size of their largest child that does not span
+    Content,
+}
+
+#[derive(Debug, Clone)]
+struct GridCell<T> {
+    child: T,
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct GridContainer<T> {
+    cells: Vec<GridCell<T>>,
+    columns: Vec<Track>,
+    rows: Vec<Track>,
+    points: Points,
+    layout_target: Option<LayoutTarget>,
+}
+
+impl<T> GridContainer<T> {
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Self {
+            cells: Vec::new(),
+            columns,
+            rows,
+            points: Points::ZERO,
+            layout_target: None,
+        }
+    }
+
+    pub fn push(&mut self, child: T, row: usize, column: usize) {
+        self.push_spanning(child, row, column, 1, 1);
+    }
+
+    // panics if the cell does not fit inside the tracks, so a grid without tracks takes no children
+    pub fn push_spanning(&mut self, child: T, row: usize, column: usize, row_span: usize, column_span: usize) {
+        assert!(row_span > 0 && column_span > 0, "grid cell spans must be at least 1, got {}x{}", row_span, column_span);
+        assert!(row+row_span <= self.rows.len(), "grid rows {}..{} out of range for {} rows", row, row+row_span, self.rows.len());
+        assert!(column+column_span <= self.columns.len(), "grid columns {}..{} out of range for {} columns", column, column+column_span, self.columns.len());
+        self.cells.push(GridCell {
+            child,
+            row,
+            column,
+            row_span,
+            column_span,
+        });
+    }
+
+    pub fn into_push(mut self, child: T, row: usize, column: usize) -> Self {
+        self.push(child, row, column);
+        self
+    }
+
+    pub fn into_push_spanning(mut self, child: T, row: usize, column: usize, row_span: usize, column_span: usize) -> Self {
+        self.push_spanning(child, row, column, row_span, column_span);
+        self
+    }
+
+    pub fn get_columns(&self) -> &Vec<Track> {
+        &self.columns
+    }
+
+    pub fn get_rows(&self) -> &Vec<Track> {
+        &self.rows
+    }
+
+    pub fn get_children(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter().map(|cell| &cell.child)
+    }
+
+    pub fn get_children_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut().map(|cell| &mut cell.child)
+    }
+
+    // preferred width of the widest single column child in each column, only measured where tracks need it
+    fn measure_columns(&self, constraints: Constraints, measure_fractions: bool) -> Vec<f32>
+    where
+        T: Measure
+    {
+        let mut content_sizes = vec![0.0; self.columns.len()];
+        for cell in &self.cells {
+            let needs_measure = match self.columns[cell.column] {
+                Track::Content => true,
+                Track::Fraction(_) => measure_fractions,
+                Track::Fixed(_) => false
+            };
+            if cell.column_span != 1 || !needs_measure {
+                continue;
+            }
+
+            let width = cell.child.measure(constraints).preferred.width;
+            content_sizes[cell.column] = f32::max(content_sizes[cell.column], width);
+        }
+        content_sizes
+    }
+
+    fn compute_column_sizes(&self, width: f32) -> Vec<f32>
+    where
+        T: Measure
+    {
+        let content_sizes = self.measure_columns(Constraints::width(width), false);
+        resolve_tracks(&self.columns, &content_sizes, Some(width))
+    }
+
+    // height of the tallest single row child in each row, only probed where tracks need it
+    fn measure_rows(&mut self, column_sizes: &[f32], left: f32, ty: f32, measure_fractions: bool) -> Vec<f32>
+    where
+        T: Measure
+    {
+        let mut content_sizes = vec![0.0; self.rows.len()];
+        for cell in &mut self.cells {
+            let needs_measure = match self.rows[cell.row] {
+                Track::Content => true,
+                Track::Fraction(_) => measure_fractions,
+                Track::Fixed(_) => false
+            };
+            if cell.row_span != 1 || !needs_measure {
+                continue;
+            }
+
+            let (x, width) = span_range(column_sizes, cell.column, cell.column_span);
+            let height = cell.child.row_height(width/2.0, left+x+width/2.0, ty);
+            content_sizes[cell.row] = f32::max(content_sizes[cell.row], height);
+        }
+        content_sizes
+    }
+
+    fn fill_cells(&mut self, column_sizes: &[f32], row_sizes: &[f32], left: f32, ty: f32)
+    where
+        T: Fill
+    {
+        for cell in &mut self.cells {
+            let (x, width) = span_range(column_sizes, cell.column, cell.column_span);
+            let (y, height) = span_range(row_sizes, cell.row, cell.row_span);
+            cell.child.fill(SizeAndCenter {
+                sx: width/2.0,
+                sy: height/2.0,
+                cx: left+x+width/2.0,
+                cy: ty-y-height/2.0,
+            });
+        }
+    }
+}
+
+// Content rows take the height a child has when laid out by width, children that can't be
+// laid out that way count with their preferred height.
+trait RowHeight {
+    fn row_height(&mut self, sx: f32, cx: f32, ty: f32) -> f32;
+}
+
+impl<T: Measure> RowHeight for T {
+    default fn row_height(&mut self, sx: f32, _cx: f32, _ty: f32) -> f32 {
+        self.measure(Constraints::width(sx*2.0)).preferred.height
+    }
+}
+
+impl<T: Measure+FillWidth> RowHeight for T {
+    fn row_height(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        ty-self.fill_width(sx, cx, ty)
+    }
+}
+
+// available is None when the tracks may grow freely, fractions are then sized like content
+fn resolve_tracks(tracks: &[Track], content_sizes: &[f32], available: Option<f32>) -> Vec<f32> {
+    let mut used = 0.0;
+    let mut fraction_total = 0.0;
+    for (track, content_size) in tracks.iter().zip(content_sizes) {
+        match track {
+            Track::Fixed(size) => used += size,
+            Track::Content => used += content_size,
+            Track::Fraction(fraction) => match available {
+                Some(_) => fraction_total += fraction,
+                None => used += content_size
+            }
+        }
+    }
+
+    let remaining = available.map(|available| (available-used).max(0.0)).unwrap_or(0.0);
+    tracks.iter().zip(content_sizes).map(|(track, content_size)| match track {
+        Track::Fixed(size) => *size,
+        Track::Content => *content_size,
+        Track::Fraction(fraction) => match available {
+            Some(_) if fraction_total > 0.0 => remaining*fraction/fraction_total,
+            Some(_) => 0.0,
+            None => *content_size
+        }
+    }).collect()
+}
+
+// returns the offset of the first track and the combined size of the span
+fn span_range(sizes: &[f32], start: usize, span: usize) -> (f32, f32) {
+    let offset = sizes[..start].iter().sum();
+    let size = sizes[start..start+span].iter().sum();
+    (offset, size)
+}
+
+impl<T: Init> Init for GridContainer<T> {
+    fn init(&mut self) {
+        for cell in &mut self.cells {
+            cell.child.init();
+        }
+    }
+}
+
+impl<T: Fill+Measure> Fill for GridContainer<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        let left = fill_target.cx-fill_target.sx;
+        let ty = fill_target.cy+fill_target.sy;
+
+        let column_sizes = self.compute_column_sizes(fill_target.sx*2.0);
+        let content_sizes = self.measure_rows(&column_sizes, left, ty, false);
+        let row_sizes = resolve_tracks(&self.rows, &content_sizes, Some(fill_target.sy*2.0));
+
+        self.fill_cells(&column_sizes, &row_sizes, left, ty);
+        self.points = fill_target.into();
+        self.layout_target = Some(LayoutTarget::Fill(fill_target));
+    }
+}
+
+impl<T: Fill+Measure> FillWidth for GridContainer<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        let left = cx-sx;
+        self.layout_target = Some(LayoutTarget::FillWidth(sx, cx, ty));
+
+        let column_sizes = self.compute_column_sizes(sx*2.0);
+        let content_sizes = self.measure_rows(&column_sizes, left, ty, true);
+        let row_sizes = resolve_tracks(&self.rows, &content_sizes, None);
+
+        self.fill_cells(&column_sizes, &row_sizes, left, ty);
+        let by = ty-row_sizes.iter().sum::<f32>();
+        self.points = Points {
+            p1x: left,
+            p1y: ty,
+            p2x: cx+sx,
+            p2y: by,
+        };
+        by
+    }
+}
+
+// fractional tracks are measured like content, they can grow past it
+impl<T: Measure> Measure for GridContainer<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let column_sizes = resolve_tracks(&self.columns, &self.measure_columns(constraints, true), None);
+
+        let mut row_content_sizes = vec![0.0; self.rows.len()];
+        for cell in &self.cells {
+            if cell.row_span != 1 {
+                continue;
+            }
+            let (_, width) = span_range(&column_sizes, cell.column, cell.column_span);
+            let height = cell.child.measure(constraints.with_max_width(width)).preferred.height;
+            row_content_sizes[cell.row] = f32::max(row_content_sizes[cell.row], height);
+        }
+        let row_sizes = resolve_tracks(&self.rows, &row_content_sizes, None);
+
+        let fixed_total = |tracks: &Vec<Track>| tracks.iter().map(|track| match track {
+            Track::Fixed(size) => *size,
+            _ => 0.0
+        }).sum::<f32>();
+        let grows = |tracks: &Vec<Track>| tracks.iter().any(|track| matches!(track, Track::Fraction(_)));
+
+        let preferred = Size::new(column_sizes.iter().sum(), row_sizes.iter().sum());
+        SizeHint {
+            min: Size::new(fixed_total(&self.columns), fixed_total(&self.rows)),
+            preferred: constraints.constrain(preferred),
+            max: Size::new(
+                if grows(&self.columns) { f32::INFINITY } else { preferred.width },
+                if grows(&self.rows) { f32::INFINITY } else { preferred.height },
+            ),
+        }.normalized()
+    }
+}
+
+// a child that changed height moves the tracks or items after it, so the last layout is redone
+impl<T: Dirty+Fill+Measure> Dirty for GridContainer<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.cells.iter().fold(DirtyFlags::CLEAN, |dirty, cell| dirty.or(cell.child.get_dirty()))
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let mut changed = false;
+        for cell in &mut self.cells {
+            changed |= cell.child.update_layout() != 0.0;
+        }
+        if !changed {
+            return 0.0;
+        }
+
+        match self.layout_target {
+            Some(LayoutTarget::Fill(fill_target)) => {
+                self.fill(fill_target);
+                0.0
+            },
+            Some(LayoutTarget::FillWidth(sx, cx, ty)) => {
+                let by = self.points.p2y;
+                by-self.fill_width(sx, cx, ty)
+            },
+            None => 0.0
+        }
+    }
+
+    fn clear_dirty(&mut self) {
+        for cell in &mut self.cells {
+            cell.child.clear_dirty();
+        }
+    }
+}
+
+impl<T: TranslateX> TranslateX for GridContainer<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.points.p1x += dx;
+        self.points.p2x += dx;
+        if let Some(layout_target) = self.layout_target.as_mut() {
+            layout_target.translate_x(dx);
+        }
+        for cell in &mut self.cells {
+            cell.child.translate_x(dx);
+        }
+    }
+}
+
+impl<T: TranslateY> TranslateY for GridContainer<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.points.p1y += dy;
+        self.points.p2y += dy;
+        if let Some(layout_target) = self.layout_target.as_mut() {
+            layout_target.translate_y(dy);
+        }
+        for cell in &mut self.cells {
+            cell.child.translate_y(dy);
+        }
+    }
+}
+
+impl<T> GetPointBounds for GridContainer<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.points
+    }
+}
+
+impl<C: StandardConstructTarget, T: Construct<C>> Construct<C> for GridContainer<T> {
+    fn construct(&self) -> C {
+        let mut construct_target = C::EMPTY;
+        for cell in &self.cells {
+            construct_target.append(cell.child.construct());
+        }
+        construct_target
+    }
+}
+
+impl<C: StandardConstructTarget, T: ConstructStyled<C>> ConstructStyled<C> for GridContainer<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        let mut construct_target = C::EMPTY;
+        for cell in &self.cells {
+            construct_target.append(cell.child.construct_styled(theme));
+        }
+        construct_target
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for GridContainer<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        for cell in &self.cells {
+            cell.child.construct_retained(target);
+        }
+    }
+}
+
+impl<T: SignalReciever<S, R>, S, R: Default> SignalReciever<S, Vec<R>> for GridContainer<T> {
+    fn take_signal(&mut self, signal: &mut S) -> Vec<R> {
+        let mut responses = Vec::with_capacity(self.cells.len());
+        for cell in &mut self.cells {
+            if signal.is_handled() {
+                responses.push(R::default());
+            } else {
+                responses.push(cell.child.take_signal(signal));
+            }
+        }
+        responses
+    }
+}
+
+impl<T: SignalReciever<S, ReconstructCallback<C>>, S, C> SignalReciever<S, ReconstructCallback<C>> for GridContainer<T> {
+    fn take_signal(&mut self, signal: &mut S) -> ReconstructCallback<C> {
+        let reconstruct_callbacks: Vec<ReconstructCallback<C>> = self.take_signal(signal);
+        for reconstruct_callback in reconstruct_callbacks {
+            if reconstruct_callback.get_reconstruct() {
+                return reconstruct_callback
+            }
+        }
+        ReconstructCallback::new(false)
+    }
+}
+
+// every child gets the signal, animations keep running even when another one already asked for a redraw
+impl<T: SignalReciever<S, RedrawCallback>, S> SignalReciever<S, RedrawCallback> for GridContainer<T> {
+    fn take_signal(&mut self, signal: &mut S) -> RedrawCallback {
+        let redraw_callbacks: Vec<RedrawCallback> = self.take_signal(signal);
+        redraw_callbacks.into_iter().fold(RedrawCallback::new(false), |redraw_callback, other| redraw_callback.or_into(other))
+    }
+}
+
+impl<T: HitTest> HitTest for GridContainer<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        for (i, cell) in self.cells.iter().enumerate().rev() {
+            path.push(i);
+            if cell.child.hit_test(x, y, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+}
+
+impl<S, R: Default, T: RouteSignal<S, R>> RouteSignal<S, R> for GridContainer<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        match path.first().and_then(|i| self.cells.get_mut(*i)) {
+            Some(cell) => cell.child.route_signal(&path[1..], signal),
+            None => R::default()
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Block;
+
+    fn fill_target() -> SizeAndCenter {
+        SizeAndCenter {
+            sx: 1.0,
+            sy: 1.0,
+            cx: 0.0,
+            cy: 0.0,
+        }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a-b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn tracks_split_the_fill_area() {
+        let mut grid = GridContainer::new(vec![Track::Fixed(0.5), Track::Fraction(1.0), Track::Content], vec![Track::Fraction(1.0), Track::Fraction(3.0)])
+            .into_push(Block::new(0.1, 0.1), 0, 0)
+            .into_push(Block::new(0.1, 0.1), 0, 1)
+            .into_push(Block::new(0.3, 0.1), 1, 2);
+        grid.fill(fill_target());
+
+        let children: Vec<&Block> = grid.get_children().collect();
+        // 0.5 fixed, 0.3 for the content column and the rest for the fraction
+        assert_close(children[0].points.p1x, -1.0);
+        assert_close(children[0].points.p2x, -0.5);
+        assert_close(children[1].points.p2x, 0.7);
+        assert_close(children[2].points.p1x, 0.7);
+        assert_close(children[2].points.p2x, 1.0);
+        // a quarter and three quarters of the height
+        assert_close(children[0].points.p2y, 0.5);
+        assert_close(children[2].points.p1y, 0.5);
+        assert_close(children[2].points.p2y, -1.0);
+    }
+
+    #[test]
+    fn spanning_cells_cover_their_tracks() {
+        let mut grid = GridContainer::new(vec![Track::Fraction(1.0); 4], vec![Track::Fraction(1.0); 2])
+            .into_push_spanning(Block::new(0.0, 0.0), 0, 1, 2, 3);
+        grid.fill(fill_target());
+
+        let block = grid.get_children().next().unwrap();
+        assert_close(block.points.p1x, -0.5);
+        assert_close(block.points.p2x, 1.0);
+        assert_close(block.points.p1y, 1.0);
+        assert_close(block.points.p2y, -1.0);
+    }
+
+    // a child that can only be filled, so content rows fall back to its measured height
+    struct FillOnly(Block);
+
+    impl Fill for FillOnly {
+        fn fill(&mut self, fill_target: SizeAndCenter) {
+            self.0.fill(fill_target);
+        }
+    }
+
+    impl Measure for FillOnly {
+        fn measure(&self, constraints: Constraints) -> SizeHint {
+            self.0.measure(constraints)
+        }
+    }
+
+    #[test]
+    fn content_rows_of_fill_only_children_use_the_preferred_height() {
+        let mut grid = GridContainer::new(vec![Track::Fraction(1.0)], vec![Track::Content, Track::Fraction(1.0)])
+            .into_push(FillOnly(Block::new(0.5, 0.4)), 0, 0)
+            .into_push(FillOnly(Block::new(0.5, 0.1)), 1, 0);
+
+        assert_close(grid.fill_width(1.0, 0.0, 1.0), 0.5);
+        let children: Vec<&FillOnly> = grid.get_children().collect();
+        assert_close(children[0].0.points.p2y, 0.6);
+        assert_close(children[1].0.points.p2y, 0.5);
+
+        grid.fill(fill_target());
+        let children: Vec<&FillOnly> = grid.get_children().collect();
+        assert_close(children[0].0.points.p2y, 0.6);
+        assert_close(children[1].0.points.p2y, -1.0);
+    }
+
+    #[test]
+    fn grid_without_tracks_lays_out_empty() {
+        let mut grid: GridContainer<Block> = GridContainer::new(Vec::new(), Vec::new());
+        grid.fill(fill_target());
+        assert_eq!(grid.fill_width(1.0, 0.0, 1.0), 1.0);
+        assert_eq!(grid.measure(Constraints::UNBOUNDED).preferred, Size::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "grid columns 2..3 out of range for 2 columns")]
+    fn push_outside_the_tracks_panics() {
+        let mut grid = GridContainer::new(vec![Track::Fraction(1.0); 2], vec![Track::Fraction(1.0)]);
+        grid.push(Block::new(0.0, 0.0), 0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "grid rows 0..1 out of range for 0 rows")]
+    fn push_into_a_grid_without_tracks_panics() {
+        let mut grid = GridContainer::new(Vec::new(), Vec::new());
+        grid.push(Block::new(0.0, 0.0), 0, 0);
+    }
+}
\ No newline at end of file
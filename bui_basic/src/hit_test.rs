@@ -0,0 +1,427 @@
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::Construct, containers::{Fill, FillWidth, Init, TranslateX, TranslateY, GetPointBounds}, signal::{SignalReciever, forward_signals, ShortCircuitingCallback, Handled, Propagating, Phase, CursorMovedSignal, MouseLeftDownSignal, MouseLeftUpSignal, ReleasedElsewhereSignal, MouseButtonSignal, MouseButton, ButtonState, ClickSignal, ScrollSignal, FlingSignal, PinchSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}};
+
+// A hit path lists, from the root down, the child index picked by every
+// container holding more than one child. Single child containers and
+// widgets don't add an entry.
+pub trait HitTest {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool;
+}
+
+pub trait RouteSignal<S, R> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R;
+}
+
+pub fn compute_hit_path<T: HitTest>(root: &T, x: f32, y: f32) -> Option<Vec<usize>> {
+    let mut path = Vec::new();
+    if root.hit_test(x, y, &mut path) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PointerRouter<T> {
+    child: T,
+    hover_path: Option<Vec<usize>>,
+    capture_path: Option<Vec<usize>>,
+    capture_button: Option<MouseButton>,
+    release_path: Option<Vec<usize>>,
+    mousex: f32,
+    mousey: f32,
+}
+
+impl<T> PointerRouter<T> {
+    pub fn new(child: T) -> Self {
+        Self {
+            child,
+            hover_path: None,
+            capture_path: None,
+            capture_button: None,
+            release_path: None,
+            mousex: -1.0,
+            mousey: -1.0,
+        }
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    pub fn get_hover_path(&self) -> Option<&Vec<usize>> {
+        self.hover_path.as_ref()
+    }
+
+    pub fn is_captured(&self) -> bool {
+        self.capture_path.is_some()
+    }
+
+    pub fn release_capture(&mut self) {
+        self.capture_path = None;
+        self.capture_button = None;
+    }
+
+    // the first pressed button captures the hovered path, later presses go to the same path
+    fn begin_capture(&mut self, button: MouseButton) -> Option<Vec<usize>> {
+        if self.capture_path.is_none() {
+            self.capture_path = self.hover_path.clone();
+            self.capture_button = self.capture_path.as_ref().map(|_| button);
+        }
+        self.capture_path.clone()
+    }
+
+    // only releasing the button that started the capture ends it
+    fn end_capture(&mut self, button: MouseButton) -> Option<Vec<usize>> {
+        let path = if self.capture_button == Some(button) {
+            self.capture_button = None;
+            self.capture_path.take()
+        } else {
+            self.capture_path.clone()
+        };
+        path.or_else(|| self.hover_path.clone())
+    }
+
+    // tells the widget that took the previous left release when this one goes somewhere else,
+    // before the release itself is delivered so focus moves away before it moves in
+    fn release_elsewhere<R: Default>(&mut self, path: &Option<Vec<usize>>) -> R
+    where
+        T: RouteSignal<ReleasedElsewhereSignal, R>
+    {
+        let previous = std::mem::replace(&mut self.release_path, path.clone());
+        if previous.is_some() && previous != *path {
+            self.route(previous, &mut ReleasedElsewhereSignal())
+        } else {
+            R::default()
+        }
+    }
+
+    // call after the layout of the child changed without the cursor moving
+    pub fn refresh_hover(&mut self)
+    where
+        T: HitTest
+    {
+        self.hover_path = compute_hit_path(&self.child, self.mousex, self.mousey);
+    }
+
+    fn route<S, R: Default>(&mut self, path: Option<Vec<usize>>, signal: &mut S) -> R
+    where
+        T: RouteSignal<S, R>
+    {
+        match path {
+            Some(path) => self.child.route_signal(&path, signal),
+            None => R::default()
+        }
+    }
+
+    // Runs the capture pass from the root down to the target, then the bubble pass back up the
+    // same path. Once a widget marks the signal handled the remaining visits are skipped.
+    fn route_propagating<S, R: ShortCircuitingCallback+Default>(&mut self, path: Option<Vec<usize>>, signal: &mut Propagating<S>) -> R
+    where
+        T: RouteSignal<Propagating<S>, R>
+    {
+        signal.set_phase(Phase::Capture);
+        let callback: R = self.route(path.clone(), signal);
+        if signal.is_handled() {
+            return callback;
+        }
+        signal.set_phase(Phase::Bubble);
+        callback.or_into(self.route(path, signal))
+    }
+}
+
+impl<T: Init> Init for PointerRouter<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
+}
+
+impl<T: Fill> Fill for PointerRouter<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.child.fill(fill_target);
+    }
+}
+
+impl<T: FillWidth> FillWidth for PointerRouter<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        self.child.fill_width(sx, cx, ty)
+    }
+}
+
+impl<T: Dirty> Dirty for PointerRouter<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.child.update_layout()
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<T: Measure> Measure for PointerRouter<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.child.measure(constraints)
+    }
+}
+
+impl<T: TranslateX> TranslateX for PointerRouter<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.child.translate_x(dx);
+    }
+}
+
+impl<T: TranslateY> TranslateY for PointerRouter<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.child.translate_y(dy);
+    }
+}
+
+impl<T: GetPointBounds> GetPointBounds for PointerRouter<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.child.get_point_bounds()
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for PointerRouter<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for PointerRouter<T> {
+    fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for PointerRouter<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
+impl<R: ShortCircuitingCallback+Default, T: HitTest+RouteSignal<CursorMovedSignal, R>> SignalReciever<CursorMovedSignal, R> for PointerRouter<T> {
+    fn take_signal(&mut self, signal: &mut CursorMovedSignal) -> R {
+        self.mousex = signal.norm_posx;
+        self.mousey = signal.norm_posy;
+
+        if self.capture_path.is_some() {
+            return self.route(self.capture_path.clone(), signal);
+        }
+
+        let hover_path = compute_hit_path(&self.child, self.mousex, self.mousey);
+        if hover_path != self.hover_path {
+            // let the previously hovered widget notice the cursor left
+            let callback: R = self.route(self.hover_path.clone(), signal);
+            self.hover_path = hover_path;
+            callback.or_into(self.route(self.hover_path.clone(), signal))
+        } else {
+            self.route(self.hover_path.clone(), signal)
+        }
+    }
+}
+
+impl<R: Default, T: RouteSignal<MouseLeftDownSignal, R>> SignalReciever<MouseLeftDownSignal, R> for PointerRouter<T> {
+    fn take_signal(&mut self, signal: &mut MouseLeftDownSignal) -> R {
+        let path = self.begin_capture(MouseButton::Left);
+        self.route(path, signal)
+    }
+}
+
+impl<R: ShortCircuitingCallback+Default, T: HitTest+RouteSignal<MouseLeftUpSignal, R>+RouteSignal<ReleasedElsewhereSignal, R>> SignalReciever<MouseLeftUpSignal, R> for PointerRouter<T> {
+    fn take_signal(&mut self, signal: &mut MouseLeftUpSignal) -> R {
+        let path = self.end_capture(MouseButton::Left);
+        let callback: R = self.release_elsewhere(&path);
+        let callback = callback.or_into(self.route(path, signal));
+        self.refresh_hover();
+        callback
+    }
+}
+
+impl<R: ShortCircuitingCallback+Default, T: HitTest+RouteSignal<Propagating<CursorMovedSignal>, R>> SignalReciever<Propagating<CursorMovedSignal>, R> for PointerRouter<T> {
+    fn take_signal(&mut self, signal: &mut Propagating<CursorMovedSignal>) -> R {
+        self.mousex = signal.signal.norm_posx;
+        self.mousey = signal.signal.norm_posy;
+
+        if self.capture_path.is_some() {
+            return self.route_propagating(self.capture_path.clone(), signal);
+        }
+
+        let hover_path = compute_hit_path(&self.child, self.mousex, self.mousey);
+        if hover_path != self.hover_path {
+            // the previously hovered path gets passes of its own so handling there cannot hide the move from the new one
+            let mut left = Propagating::new(signal.signal);
+            let callback: R = self.route_propagating(self.hover_path.clone(), &mut left);
+            self.hover_path = hover_path;
+            callback.or_into(self.route_propagating(self.hover_path.clone(), signal))
+        } else {
+            self.route_propagating(self.hover_path.clone(), signal)
+        }
+    }
+}
+
+impl<R: ShortCircuitingCallback+Default, T: RouteSignal<Propagating<MouseLeftDownSignal>, R>> SignalReciever<Propagating<MouseLeftDownSignal>, R> for PointerRouter<T> {
+    fn take_signal(&mut self, signal: &mut Propagating<MouseLeftDownSignal>) -> R {
+        let path = self.begin_capture(MouseButton::Left);
+        self.route_propagating(path, signal)
+    }
+}
+
+impl<R: ShortCircuitingCallback+Default, T: HitTest+RouteSignal<Propagating<MouseLeftUpSignal>, R>+RouteSignal<ReleasedElsewhereSignal, R>> SignalReciever<Propagating<MouseLeftUpSignal>, R> for PointerRouter<T> {
+    fn take_signal(&mut self, signal: &mut Propagating<MouseLeftUpSignal>) -> R {
+        let path = self.end_capture(MouseButton::Left);
+        let callback: R = self.release_elsewhere(&path);
+        let callback = callback.or_into(self.route_propagating(path, signal));
+        self.refresh_hover();
+        callback
+    }
+}
+
+impl<R: ShortCircuitingCallback+Default, T: HitTest+RouteSignal<MouseButtonSignal, R>+RouteSignal<ReleasedElsewhereSignal, R>> SignalReciever<MouseButtonSignal, R> for PointerRouter<T> {
+    fn take_signal(&mut self, signal: &mut MouseButtonSignal) -> R {
+        match signal.state {
+            ButtonState::Pressed => {
+                let path = self.begin_capture(signal.button);
+                self.route(path, signal)
+            },
+            ButtonState::Released => {
+                let path = self.end_capture(signal.button);
+                let callback: R = if signal.is_left() {
+                    self.release_elsewhere(&path)
+                } else {
+                    R::default()
+                };
+                let callback = callback.or_into(self.route(path, signal));
+                self.refresh_hover();
+                callback
+            }
+        }
+    }
+}
+
+impl<R: Default, T: RouteSignal<ClickSignal, R>> SignalReciever<ClickSignal, R> for PointerRouter<T> {
+    fn take_signal(&mut self, signal: &mut ClickSignal) -> R {
+        let path = self.capture_path.clone().or_else(|| self.hover_path.clone());
+        self.route(path, signal)
+    }
+}
+
+// signals meant for whatever is under the cursor
+macro_rules! hover_routed_signals {
+    ($($signal:ident),+) => {$(
+        impl<R: Default, T: RouteSignal<$signal, R>> SignalReciever<$signal, R> for PointerRouter<T> {
+            fn take_signal(&mut self, signal: &mut $signal) -> R {
+                self.route(self.hover_path.clone(), signal)
+            }
+        }
+    )+};
+}
+
+hover_routed_signals!(ScrollSignal, FlingSignal, PinchSignal);
+
+forward_signals!(PointerRouter.child: ResizedSignal, ScaleFactorSignal, CharacterInputSignal, ImeSignal, TickSignal, KeySignal);
+
+
+#[cfg(test)]
+mod tests {
+    use bui::rect::SizeAndCenter;
+
+    use super::*;
+    use crate::{button::{Button, PressState}, construct::LineTarget, signal::ReconstructCallback, test_util::Block};
+
+    type Reconstruct = ReconstructCallback<LineTarget>;
+
+    fn router() -> PointerRouter<Button<Block>> {
+        PointerRouter::new(Button::new(Block::new(0.0, 0.0), SizeAndCenter {
+            sx: 0.5,
+            sy: 0.5,
+            cx: 0.0,
+            cy: 0.0,
+        }))
+    }
+
+    fn moved(x: f32, y: f32) -> Propagating<CursorMovedSignal> {
+        Propagating::new(CursorMovedSignal {
+            pixel_posx: 0.0,
+            pixel_posy: 0.0,
+            norm_posx: x,
+            norm_posy: y,
+        })
+    }
+
+    #[test]
+    fn capture_runs_before_bubble_along_the_path() {
+        let mut router = router();
+        let _: Reconstruct = router.take_signal(&mut moved(0.0, 0.0));
+        let _: Reconstruct = router.take_signal(&mut Propagating::new(MouseLeftDownSignal()));
+
+        let button = router.get_child();
+        assert_eq!(button.get_child().phases, vec![Phase::Capture, Phase::Bubble, Phase::Capture, Phase::Bubble]);
+        assert_eq!(button.get_press_state(), PressState::Pressed);
+    }
+
+    #[test]
+    fn target_handling_the_bubble_keeps_it_from_the_ancestor() {
+        let mut router = router();
+        let _: Reconstruct = router.take_signal(&mut moved(0.0, 0.0));
+        router.get_child_mut().get_child_mut().handles = Some(Phase::Bubble);
+        let _: Reconstruct = router.take_signal(&mut Propagating::new(MouseLeftDownSignal()));
+
+        assert_eq!(router.get_child().get_press_state(), PressState::Hovered);
+    }
+
+    #[test]
+    fn capturing_ancestor_takes_the_press_before_the_target() {
+        let mut router = router();
+        router.get_child_mut().set_phase(Phase::Capture);
+        let _: Reconstruct = router.take_signal(&mut moved(0.0, 0.0));
+        let _: Reconstruct = router.take_signal(&mut Propagating::new(MouseLeftDownSignal()));
+
+        let button = router.get_child();
+        assert_eq!(button.get_press_state(), PressState::Pressed);
+        // only the move reached the block, the press was handled during capture
+        assert_eq!(button.get_child().phases, vec![Phase::Capture, Phase::Bubble]);
+    }
+
+    #[test]
+    fn releasing_another_button_keeps_the_capture() {
+        let mut router = router();
+        let _: Reconstruct = router.take_signal(&mut moved(0.0, 0.0));
+        let _: Reconstruct = router.take_signal(&mut MouseButtonSignal::new(MouseButton::Left, ButtonState::Pressed));
+        let _: Reconstruct = router.take_signal(&mut MouseButtonSignal::new(MouseButton::Right, ButtonState::Pressed));
+        let _: Reconstruct = router.take_signal(&mut MouseButtonSignal::new(MouseButton::Right, ButtonState::Released));
+        assert!(router.is_captured());
+
+        let _: Reconstruct = router.take_signal(&mut MouseButtonSignal::new(MouseButton::Left, ButtonState::Released));
+        assert!(!router.is_captured());
+    }
+
+    #[test]
+    fn left_release_signal_ends_only_a_left_capture() {
+        let mut router = router();
+        let _: Reconstruct = router.take_signal(&mut moved(0.0, 0.0));
+        let _: Reconstruct = router.take_signal(&mut MouseButtonSignal::new(MouseButton::Middle, ButtonState::Pressed));
+        let _: Reconstruct = router.take_signal(&mut MouseLeftUpSignal());
+        assert!(router.is_captured());
+
+        let _: Reconstruct = router.take_signal(&mut MouseButtonSignal::new(MouseButton::Middle, ButtonState::Released));
+        assert!(!router.is_captured());
+    }
+
+    #[test]
+    fn pressing_outside_every_widget_captures_nothing() {
+        let mut router = router();
+        let _: Reconstruct = router.take_signal(&mut moved(0.9, 0.9));
+        let _: Reconstruct = router.take_signal(&mut MouseLeftDownSignal());
+        assert!(!router.is_captured());
+    }
+}
@@ -0,0 +1,309 @@
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::Construct, containers::{Fill, FillWidth, Init, GetPointBounds}, signal::{SignalReciever, ReconstructCallback, ResizedSignal, ScaleFactorSignal}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags, LayoutTarget}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}};
+
+// Sits at the root of the tree and lays everything out again top-down whenever the window
+// is resized or rescaled, so widgets caching their bounds stay in sync with the new layout.
+// Roots that only fill by width, like columns, flow down from the top of the target.
+#[derive(Debug, Clone)]
+pub struct LayoutRoot<T> {
+    child: T,
+    layout_target: LayoutTarget,
+    place: fn(&mut T, LayoutTarget) -> Points,
+    points: Points,
+}
+
+impl<T> LayoutRoot<T> {
+    pub const WINDOW: SizeAndCenter = SizeAndCenter {
+        sx: 1.0,
+        sy: 1.0,
+        cx: 0.0,
+        cy: 0.0,
+    };
+
+    pub fn new(child: T) -> Self
+    where
+        T: Fill
+    {
+        Self::new_with_target(child, Self::WINDOW)
+    }
+
+    pub fn new_with_target(child: T, fill_target: SizeAndCenter) -> Self
+    where
+        T: Fill
+    {
+        Self {
+            child,
+            layout_target: LayoutTarget::Fill(fill_target),
+            place: place_fill,
+            points: Points::ZERO,
+        }
+    }
+
+    // fills the window width from its top
+    pub fn new_width(child: T) -> Self
+    where
+        T: FillWidth
+    {
+        Self::new_width_with_target(child, Self::WINDOW)
+    }
+
+    pub fn new_width_with_target(child: T, fill_target: SizeAndCenter) -> Self
+    where
+        T: FillWidth
+    {
+        Self {
+            child,
+            layout_target: LayoutTarget::FillWidth(fill_target.sx, fill_target.cx, fill_target.cy+fill_target.sy),
+            place: place_fill_width,
+            points: Points::ZERO,
+        }
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    pub fn get_layout_target(&self) -> LayoutTarget {
+        self.layout_target
+    }
+
+    pub fn set_fill_target(&mut self, fill_target: SizeAndCenter) {
+        self.set_layout_target(LayoutTarget::Fill(fill_target));
+    }
+
+    pub fn set_layout_target(&mut self, layout_target: LayoutTarget) {
+        self.layout_target = layout_target;
+        self.relayout();
+    }
+
+    // call after changing the tree so the new children get their place
+    pub fn relayout(&mut self) {
+        self.points = (self.place)(&mut self.child, self.layout_target);
+    }
+}
+
+// a width target fills down to the bottom of the window
+fn place_fill<T: Fill>(child: &mut T, layout_target: LayoutTarget) -> Points {
+    let fill_target = match layout_target {
+        LayoutTarget::Fill(fill_target) => fill_target,
+        LayoutTarget::FillWidth(sx, cx, ty) => SizeAndCenter {
+            sx,
+            sy: (ty+1.0).max(0.0)/2.0,
+            cx,
+            cy: (ty-1.0)/2.0,
+        }
+    };
+    child.fill(fill_target);
+    fill_target.into()
+}
+
+// only the width and top of a full target are used, the child decides its height
+fn place_fill_width<T: FillWidth>(child: &mut T, layout_target: LayoutTarget) -> Points {
+    let (sx, cx, ty) = match layout_target {
+        LayoutTarget::Fill(fill_target) => (fill_target.sx, fill_target.cx, fill_target.cy+fill_target.sy),
+        LayoutTarget::FillWidth(sx, cx, ty) => (sx, cx, ty)
+    };
+    let by = child.fill_width(sx, cx, ty);
+    Points {
+        p1x: cx-sx,
+        p1y: ty,
+        p2x: cx+sx,
+        p2y: by,
+    }
+}
+
+impl<T> Init for LayoutRoot<T> {
+    fn init(&mut self) {
+        self.relayout();
+    }
+}
+
+impl<T> GetPointBounds for LayoutRoot<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.points
+    }
+}
+
+impl<T: Measure> Measure for LayoutRoot<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.child.measure(constraints)
+    }
+}
+
+impl<T: Dirty> Dirty for LayoutRoot<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    // the root has nothing after it to move, only its own bottom follows the child
+    fn update_layout(&mut self) -> f32 {
+        self.points.p2y -= self.child.update_layout();
+        0.0
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for LayoutRoot<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for LayoutRoot<T> {
+    fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for LayoutRoot<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
+impl<S, R, T: SignalReciever<S, R>> SignalReciever<S, R> for LayoutRoot<T> {
+    default fn take_signal(&mut self, signal: &mut S) -> R {
+        self.child.take_signal(signal)
+    }
+}
+
+// The children see the new resolution first, so containers converting pixel and dp lengths
+// have it before the layout is redone.
+impl<C, T: SignalReciever<ResizedSignal, ReconstructCallback<C>>> SignalReciever<ResizedSignal, ReconstructCallback<C>> for LayoutRoot<T> {
+    fn take_signal(&mut self, signal: &mut ResizedSignal) -> ReconstructCallback<C> {
+        let _: ReconstructCallback<C> = self.child.take_signal(signal);
+        self.relayout();
+        ReconstructCallback::new(true)
+    }
+}
+
+impl<C, T: SignalReciever<ScaleFactorSignal, ReconstructCallback<C>>> SignalReciever<ScaleFactorSignal, ReconstructCallback<C>> for LayoutRoot<T> {
+    fn take_signal(&mut self, signal: &mut ScaleFactorSignal) -> ReconstructCallback<C> {
+        let _: ReconstructCallback<C> = self.child.take_signal(signal);
+        self.relayout();
+        ReconstructCallback::new(true)
+    }
+}
+
+impl<T: HitTest> HitTest for LayoutRoot<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for LayoutRoot<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{construct::LineTarget, column::VecColumn, containers::HeightContainer, units::Length, test_util::Block};
+
+    type Reconstruct = ReconstructCallback<LineTarget>;
+
+    fn resized() -> ResizedSignal {
+        ResizedSignal {
+            resxp: 640,
+            resyp: 480,
+            resx: 1.0,
+            resy: 1.0,
+        }
+    }
+
+    #[test]
+    fn fill_root_fills_the_window() {
+        let mut root = LayoutRoot::new(Block::new(0.5, 0.5));
+        root.init();
+        let points = root.get_child().points;
+        assert_eq!((points.p1x, points.p1y, points.p2x, points.p2y), (-1.0, 1.0, 1.0, -1.0));
+        assert_eq!(root.get_point_bounds().p2y, -1.0);
+    }
+
+    #[test]
+    fn width_root_flows_from_the_top() {
+        let column = VecColumn::new()
+            .into_push(Block::new(0.0, 0.5))
+            .into_push(Block::new(0.0, 0.25));
+        let mut root = LayoutRoot::new_width(column);
+        root.init();
+
+        let children = root.get_child_mut().get_children_mut();
+        assert_eq!(children[0].points.p1y, 1.0);
+        assert_eq!(children[1].points.p1y, 0.5);
+        assert_eq!(children[1].points.p2y, 0.25);
+        assert_eq!(children[1].points.p1x, -1.0);
+        assert_eq!(children[1].points.p2x, 1.0);
+        assert_eq!(root.get_point_bounds().p2y, 0.25);
+    }
+
+    #[test]
+    fn fill_root_takes_a_width_target_down_to_the_bottom() {
+        let mut root = LayoutRoot::new(Block::new(0.5, 0.5));
+        root.set_layout_target(LayoutTarget::FillWidth(1.0, 0.0, 0.5));
+        assert_eq!(root.get_child().points.p1y, 0.5);
+        assert_eq!(root.get_child().points.p2y, -1.0);
+    }
+
+    #[test]
+    fn resize_lays_out_and_forwards() {
+        let mut root = LayoutRoot::new_width(VecColumn::new().into_push(Block::new(0.0, 0.5)));
+        root.set_fill_target(SizeAndCenter {
+            sx: 0.5,
+            sy: 1.0,
+            cx: 0.5,
+            cy: 0.0,
+        });
+        root.get_child_mut().get_children_mut()[0].points = Points::ZERO;
+
+        let reconstruct: Reconstruct = root.take_signal(&mut resized());
+        assert!(reconstruct.get_reconstruct());
+        let child = &root.get_child_mut().get_children_mut()[0];
+        assert_eq!(child.received, vec!["ResizedSignal"]);
+        assert_eq!(child.points.p1x, 0.0);
+        assert_eq!(child.points.p2y, 0.5);
+    }
+
+    fn pixel_rows(height: Length) -> LayoutRoot<VecColumn<HeightContainer<Block>>> {
+        let mut root = LayoutRoot::new_width(VecColumn::new()
+            .into_push(HeightContainer::new_with_length(Block::new(0.0, 0.0), height))
+            .into_push(HeightContainer::new_with_length(Block::new(0.0, 0.0), height)));
+        root.init();
+        root
+    }
+
+    #[test]
+    fn resize_lays_out_pixel_lengths_at_the_new_resolution() {
+        // 72 of 720 pixels is a tenth of the window height
+        let mut root = pixel_rows(Length::Px(72.0));
+        assert!((root.get_point_bounds().p2y-0.6).abs() < 1e-5);
+
+        let _: Reconstruct = root.take_signal(&mut ResizedSignal {
+            resxp: 640,
+            resyp: 360,
+            resx: 1.0,
+            resy: 1.0,
+        });
+        assert!((root.get_point_bounds().p2y-0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rescale_lays_out_dp_lengths_at_the_new_scale() {
+        let mut root = pixel_rows(Length::Dp(36.0));
+        assert!((root.get_point_bounds().p2y-0.8).abs() < 1e-5);
+
+        let _: Reconstruct = root.take_signal(&mut ScaleFactorSignal {
+            scale_factor: 2.0,
+        });
+        assert!((root.get_point_bounds().p2y-0.6).abs() < 1e-5);
+    }
+}
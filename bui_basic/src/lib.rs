@@ -7,4 +7,25 @@ pub mod column;
 pub mod text;
 pub mod signal;
 pub mod button;
-pub mod text_input;
\ No newline at end of file
+pub mod text_input;
+pub mod overlay;
+pub mod hit_test;
+pub mod click;
+pub mod gesture;
+pub mod scroll;
+pub mod platform;
+pub mod grid;
+pub mod flex;
+pub mod measure;
+pub mod units;
+pub mod layout;
+pub mod dirty;
+pub mod retained;
+pub mod style;
+pub mod draw_list;
+pub mod raster;
+pub mod transform;
+pub mod animation;
+pub mod tab_view;
+#[cfg(test)]
+mod test_util;
\ No newline at end of file
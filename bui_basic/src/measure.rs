@@ -0,0 +1,115 @@
+// Sizes are full lengths in normalized coordinates, not half sizes like SizeAndCenter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Size {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Size {
+    pub const ZERO: Self = Self::new(0.0, 0.0);
+    pub const INFINITY: Self = Self::new(f32::INFINITY, f32::INFINITY);
+
+    pub const fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+        }
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        Self::new(self.width.min(other.width), self.height.min(other.height))
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Self::new(self.width.max(other.width), self.height.max(other.height))
+    }
+}
+
+// the largest size the parent can give, either bound may be f32::INFINITY
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Constraints {
+    pub max_width: f32,
+    pub max_height: f32,
+}
+
+impl Constraints {
+    pub const UNBOUNDED: Self = Self::new(f32::INFINITY, f32::INFINITY);
+
+    pub const fn new(max_width: f32, max_height: f32) -> Self {
+        Self {
+            max_width,
+            max_height,
+        }
+    }
+
+    pub const fn width(max_width: f32) -> Self {
+        Self::new(max_width, f32::INFINITY)
+    }
+
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    pub fn with_max_height(mut self, max_height: f32) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    pub fn shrink(self, width: f32, height: f32) -> Self {
+        Self::new((self.max_width-width).max(0.0), (self.max_height-height).max(0.0))
+    }
+
+    pub fn constrain(&self, size: Size) -> Size {
+        size.min(Size::new(self.max_width, self.max_height))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeHint {
+    pub min: Size,
+    pub preferred: Size,
+    pub max: Size,
+}
+
+impl SizeHint {
+    pub const ZERO: Self = Self::fixed(Size::ZERO);
+
+    pub const fn fixed(size: Size) -> Self {
+        Self {
+            min: size,
+            preferred: size,
+            max: size,
+        }
+    }
+
+    pub const fn flexible(preferred: Size) -> Self {
+        Self {
+            min: Size::ZERO,
+            preferred,
+            max: Size::INFINITY,
+        }
+    }
+
+    pub fn map(self, f: impl Fn(Size) -> Size) -> Self {
+        Self {
+            min: f(self.min),
+            preferred: f(self.preferred),
+            max: f(self.max),
+        }
+    }
+
+    // keeps min <= preferred <= max after the parts were changed separately
+    pub fn normalized(self) -> Self {
+        let max = self.max.max(self.min);
+        Self {
+            min: self.min,
+            preferred: self.preferred.max(self.min).min(max),
+            max,
+        }
+    }
+}
+
+pub trait Measure {
+    fn measure(&self, constraints: Constraints) -> SizeHint;
+}
\ No newline at end of file
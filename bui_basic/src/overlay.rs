@@ -0,0 +1,688 @@
+use std::sync::{Arc, Mutex};
+
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::{Construct, StandardConstructTarget}, containers::{Fill, FillWidth, Init, TranslateX, TranslateY, GetPointBounds}, signal::{SignalReciever, ShortCircuitingCallback, Propagating, ReconstructCallback, RedrawCallback, ResizedSignal, ScaleFactorSignal, CursorMovedSignal, MouseLeftDownSignal, MouseLeftUpSignal, MouseButtonSignal, MouseButton, ButtonState, ClickSignal, ScrollSignal, FlingSignal, PinchSignal, CharacterInputSignal, ImeSignal, TickSignal, KeySignal}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DismissMode {
+    Manual,
+    ClickOutside,
+    AnyClick,
+}
+
+#[derive(Debug, Clone)]
+pub struct OverlayEntry<O> {
+    content: O,
+    dismiss_mode: DismissMode,
+}
+
+impl<O> OverlayEntry<O> {
+    pub fn new(content: O, dismiss_mode: DismissMode) -> Self {
+        Self {
+            content,
+            dismiss_mode,
+        }
+    }
+
+    pub fn get_content(&self) -> &O {
+        &self.content
+    }
+
+    pub fn get_content_mut(&mut self) -> &mut O {
+        &mut self.content
+    }
+
+    pub fn get_dismiss_mode(&self) -> DismissMode {
+        self.dismiss_mode
+    }
+}
+
+#[derive(Debug)]
+pub struct OverlayHandle<O> {
+    pending: Arc<Mutex<Vec<OverlayEntry<O>>>>,
+}
+
+impl<O> OverlayHandle<O> {
+    pub fn push(&self, content: O, dismiss_mode: DismissMode) {
+        self.pending.lock().unwrap().push(OverlayEntry::new(content, dismiss_mode));
+    }
+}
+
+impl<O> Clone for OverlayHandle<O> {
+    fn clone(&self) -> Self {
+        Self {
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OverlayContainer<T, O> {
+    base: T,
+    layers: Vec<OverlayEntry<O>>,
+    pending: Arc<Mutex<Vec<OverlayEntry<O>>>>,
+    mousex: f32,
+    mousey: f32,
+}
+
+impl<T, O> OverlayContainer<T, O> {
+    pub fn new(base: T) -> Self {
+        Self {
+            base,
+            layers: Vec::new(),
+            pending: Arc::new(Mutex::new(Vec::new())),
+            mousex: -1.0,
+            mousey: -1.0,
+        }
+    }
+
+    pub fn handle(&self) -> OverlayHandle<O> {
+        OverlayHandle {
+            pending: self.pending.clone(),
+        }
+    }
+
+    pub fn push(&mut self, content: O, dismiss_mode: DismissMode) {
+        self.layers.push(OverlayEntry::new(content, dismiss_mode));
+    }
+
+    pub fn pop(&mut self) -> Option<O> {
+        self.layers.pop().map(|entry| entry.content)
+    }
+
+    pub fn dismiss_all(&mut self) {
+        self.layers.clear();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.layers.len() > 0
+    }
+
+    pub fn get_base(&self) -> &T {
+        &self.base
+    }
+
+    pub fn get_base_mut(&mut self) -> &mut T {
+        &mut self.base
+    }
+
+    pub fn get_layers_mut(&mut self) -> &mut Vec<OverlayEntry<O>> {
+        &mut self.layers
+    }
+
+    // moves entries pushed through an OverlayHandle into the layer stack
+    fn take_pending(&mut self) -> bool {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.len() == 0 {
+            false
+        } else {
+            self.layers.append(&mut pending);
+            true
+        }
+    }
+
+    fn broadcast<S, R: ShortCircuitingCallback>(&mut self, signal: &mut S) -> R
+    where
+        T: SignalReciever<S, R>,
+        O: SignalReciever<S, R>
+    {
+        self.take_pending();
+        let mut callback = self.base.take_signal(signal);
+        for entry in &mut self.layers {
+            callback = callback.or_into(entry.content.take_signal(signal));
+        }
+        callback
+    }
+
+    fn top_contains_cursor(&self) -> bool
+    where
+        O: GetPointBounds
+    {
+        match self.layers.last() {
+            Some(entry) => entry.content.get_point_bounds().contains(self.mousex, self.mousey),
+            None => false
+        }
+    }
+
+    fn any_contains_cursor(&self) -> bool
+    where
+        O: GetPointBounds
+    {
+        self.layers.iter().any(|entry| entry.content.get_point_bounds().contains(self.mousex, self.mousey))
+    }
+}
+
+impl<T: Init, O: Init> Init for OverlayContainer<T, O> {
+    fn init(&mut self) {
+        self.base.init();
+        for entry in &mut self.layers {
+            entry.content.init();
+        }
+    }
+}
+
+impl<T: Fill, O> Fill for OverlayContainer<T, O> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.base.fill(fill_target);
+    }
+}
+
+impl<T: FillWidth, O> FillWidth for OverlayContainer<T, O> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        self.base.fill_width(sx, cx, ty)
+    }
+}
+
+// layers float above the base and do not take part in layout
+impl<T: Measure, O> Measure for OverlayContainer<T, O> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.base.measure(constraints)
+    }
+}
+
+impl<T: Dirty, O: Dirty> Dirty for OverlayContainer<T, O> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.layers.iter().fold(self.base.get_dirty(), |dirty, entry| dirty.or(entry.content.get_dirty()))
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        for entry in &mut self.layers {
+            entry.content.update_layout();
+        }
+        self.base.update_layout()
+    }
+
+    fn clear_dirty(&mut self) {
+        self.base.clear_dirty();
+        for entry in &mut self.layers {
+            entry.content.clear_dirty();
+        }
+    }
+}
+
+impl<T: TranslateX, O: TranslateX> TranslateX for OverlayContainer<T, O> {
+    fn translate_x(&mut self, dx: f32) {
+        self.base.translate_x(dx);
+        for entry in &mut self.layers {
+            entry.content.translate_x(dx);
+        }
+    }
+}
+
+impl<T: TranslateY, O: TranslateY> TranslateY for OverlayContainer<T, O> {
+    fn translate_y(&mut self, dy: f32) {
+        self.base.translate_y(dy);
+        for entry in &mut self.layers {
+            entry.content.translate_y(dy);
+        }
+    }
+}
+
+impl<T: GetPointBounds, O> GetPointBounds for OverlayContainer<T, O> {
+    fn get_point_bounds(&self) -> Points {
+        self.base.get_point_bounds()
+    }
+}
+
+impl<C: StandardConstructTarget, T: Construct<C>, O: Construct<C>> Construct<C> for OverlayContainer<T, O> {
+    fn construct(&self) -> C {
+        let mut construct_target = self.base.construct();
+        for entry in &self.layers {
+            construct_target.append(entry.content.construct());
+        }
+        construct_target
+    }
+}
+
+impl<C: StandardConstructTarget, T: ConstructStyled<C>, O: ConstructStyled<C>> ConstructStyled<C> for OverlayContainer<T, O> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        let mut construct_target = self.base.construct_styled(theme);
+        for entry in &self.layers {
+            construct_target.append(entry.content.construct_styled(theme));
+        }
+        construct_target
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>, O: RetainedConstruct<C>> RetainedConstruct<C> for OverlayContainer<T, O> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.base.construct_retained(target);
+        for entry in &self.layers {
+            entry.content.construct_retained(target);
+        }
+    }
+}
+
+impl<C, T: SignalReciever<CursorMovedSignal, ReconstructCallback<C>>, O: SignalReciever<CursorMovedSignal, ReconstructCallback<C>>+GetPointBounds> SignalReciever<CursorMovedSignal, ReconstructCallback<C>> for OverlayContainer<T, O> {
+    fn take_signal(&mut self, signal: &mut CursorMovedSignal) -> ReconstructCallback<C> {
+        let mut reconstruct_callback = ReconstructCallback::new(self.take_pending());
+        self.mousex = signal.norm_posx;
+        self.mousey = signal.norm_posy;
+
+        for entry in self.layers.iter_mut().rev() {
+            reconstruct_callback.or(entry.content.take_signal(signal));
+        }
+
+        if self.any_contains_cursor() {
+            // widgets under an overlay should drop their hover state
+            let mut occluded = CursorMovedSignal::OCCLUDED;
+            reconstruct_callback.or(self.base.take_signal(&mut occluded));
+        } else {
+            reconstruct_callback.or(self.base.take_signal(signal));
+        }
+        reconstruct_callback
+    }
+}
+
+impl<C, T: SignalReciever<MouseLeftDownSignal, ReconstructCallback<C>>, O: SignalReciever<MouseLeftDownSignal, ReconstructCallback<C>>+GetPointBounds> SignalReciever<MouseLeftDownSignal, ReconstructCallback<C>> for OverlayContainer<T, O> {
+    fn take_signal(&mut self, signal: &mut MouseLeftDownSignal) -> ReconstructCallback<C> {
+        let reconstruct_callback = ReconstructCallback::new(self.take_pending());
+
+        if !self.is_open() {
+            return reconstruct_callback.or_into(self.base.take_signal(signal));
+        }
+
+        if self.top_contains_cursor() {
+            reconstruct_callback.or_into(self.layers.last_mut().unwrap().content.take_signal(signal))
+        } else {
+            match self.layers.last().unwrap().dismiss_mode {
+                DismissMode::Manual => reconstruct_callback,
+                DismissMode::ClickOutside | DismissMode::AnyClick => {
+                    self.layers.pop();
+                    ReconstructCallback::new(true)
+                }
+            }
+        }
+    }
+}
+
+impl<C, T: SignalReciever<MouseLeftUpSignal, ReconstructCallback<C>>, O: SignalReciever<MouseLeftUpSignal, ReconstructCallback<C>>+GetPointBounds> SignalReciever<MouseLeftUpSignal, ReconstructCallback<C>> for OverlayContainer<T, O> {
+    fn take_signal(&mut self, signal: &mut MouseLeftUpSignal) -> ReconstructCallback<C> {
+        let reconstruct_callback = ReconstructCallback::new(self.take_pending());
+
+        if !self.is_open() {
+            return reconstruct_callback.or_into(self.base.take_signal(signal));
+        }
+
+        if self.top_contains_cursor() {
+            let reconstruct_callback = reconstruct_callback.or_into(self.layers.last_mut().unwrap().content.take_signal(signal));
+            if self.layers.last().unwrap().dismiss_mode == DismissMode::AnyClick {
+                self.layers.pop();
+                ReconstructCallback::new(true)
+            } else {
+                reconstruct_callback
+            }
+        } else {
+            reconstruct_callback
+        }
+    }
+}
+
+impl<C, T, O> SignalReciever<MouseButtonSignal, ReconstructCallback<C>> for OverlayContainer<T, O>
+where
+    T: SignalReciever<MouseButtonSignal, ReconstructCallback<C>>+SignalReciever<MouseLeftDownSignal, ReconstructCallback<C>>+SignalReciever<MouseLeftUpSignal, ReconstructCallback<C>>,
+    O: SignalReciever<MouseButtonSignal, ReconstructCallback<C>>+SignalReciever<MouseLeftDownSignal, ReconstructCallback<C>>+SignalReciever<MouseLeftUpSignal, ReconstructCallback<C>>+GetPointBounds
+{
+    fn take_signal(&mut self, signal: &mut MouseButtonSignal) -> ReconstructCallback<C> {
+        match (signal.button, signal.state) {
+            (MouseButton::Left, ButtonState::Pressed) => return self.take_signal(&mut MouseLeftDownSignal()),
+            (MouseButton::Left, ButtonState::Released) => return self.take_signal(&mut MouseLeftUpSignal()),
+            _ => {}
+        }
+
+        let reconstruct_callback = ReconstructCallback::new(self.take_pending());
+
+        if !self.is_open() {
+            return reconstruct_callback.or_into(self.base.take_signal(signal));
+        }
+
+        if self.top_contains_cursor() {
+            reconstruct_callback.or_into(self.layers.last_mut().unwrap().content.take_signal(signal))
+        } else if signal.state == ButtonState::Pressed && self.layers.last().unwrap().dismiss_mode != DismissMode::Manual {
+            self.layers.pop();
+            ReconstructCallback::new(true)
+        } else {
+            reconstruct_callback
+        }
+    }
+}
+
+// signals without a pointer position reach the base and every layer
+macro_rules! broadcast_signals {
+    ($($signal:ident),+) => {$(
+        impl<R: ShortCircuitingCallback, T: SignalReciever<$signal, R>, O: SignalReciever<$signal, R>> SignalReciever<$signal, R> for OverlayContainer<T, O> {
+            fn take_signal(&mut self, signal: &mut $signal) -> R {
+                self.broadcast(signal)
+            }
+        }
+    )+};
+}
+
+broadcast_signals!(ResizedSignal, ScaleFactorSignal, CharacterInputSignal, ImeSignal, TickSignal, KeySignal);
+
+impl<R: Default, T: SignalReciever<ClickSignal, R>, O: SignalReciever<ClickSignal, R>+GetPointBounds> SignalReciever<ClickSignal, R> for OverlayContainer<T, O> {
+    fn take_signal(&mut self, signal: &mut ClickSignal) -> R {
+        self.take_pending();
+        if self.top_contains_cursor() {
+            self.layers.last_mut().unwrap().content.take_signal(signal)
+        } else if self.is_open() {
+            R::default()
+        } else {
+            self.base.take_signal(signal)
+        }
+    }
+}
+
+// signals meant for whatever is under the cursor reach the top layer if it is there
+macro_rules! hover_signals {
+    ($($signal:ident),+) => {$(
+        impl<R: ShortCircuitingCallback, T: SignalReciever<$signal, R>, O: SignalReciever<$signal, R>+GetPointBounds> SignalReciever<$signal, R> for OverlayContainer<T, O> {
+            fn take_signal(&mut self, signal: &mut $signal) -> R {
+                self.take_pending();
+                if self.top_contains_cursor() {
+                    self.layers.last_mut().unwrap().content.take_signal(signal)
+                } else {
+                    self.base.take_signal(signal)
+                }
+            }
+        }
+    )+};
+}
+
+hover_signals!(ScrollSignal, FlingSignal, PinchSignal);
+
+// which routed pointer signals close the top layer
+trait DismissSignal {
+    // a press outside the top layer
+    fn is_press(&self) -> bool;
+    // a release inside the top layer
+    fn is_release(&self) -> bool;
+}
+
+impl<S> DismissSignal for S {
+    default fn is_press(&self) -> bool {
+        false
+    }
+
+    default fn is_release(&self) -> bool {
+        false
+    }
+}
+
+impl DismissSignal for MouseLeftDownSignal {
+    fn is_press(&self) -> bool {
+        true
+    }
+}
+
+impl DismissSignal for MouseLeftUpSignal {
+    fn is_release(&self) -> bool {
+        true
+    }
+}
+
+impl DismissSignal for MouseButtonSignal {
+    fn is_press(&self) -> bool {
+        self.state == ButtonState::Pressed
+    }
+
+    fn is_release(&self) -> bool {
+        self.button == MouseButton::Left && self.state == ButtonState::Released
+    }
+}
+
+impl<S> DismissSignal for Propagating<S> {
+    fn is_press(&self) -> bool {
+        self.signal.is_press()
+    }
+
+    fn is_release(&self) -> bool {
+        self.signal.is_release()
+    }
+}
+
+// The base is index 0 and overlay layers follow from the bottom up. Only the top layer can be
+// hit while one is open. A point outside it ends the path on the overlay itself, so routed
+// presses there dismiss the layer instead of reaching the widgets under it.
+impl<T: HitTest, O: HitTest> HitTest for OverlayContainer<T, O> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        match self.layers.last() {
+            Some(entry) => {
+                path.push(self.layers.len());
+                if entry.content.hit_test(x, y, path) {
+                    return true;
+                }
+                path.pop();
+                // modal layers block everything under them
+                entry.dismiss_mode != DismissMode::Manual
+            },
+            None => {
+                path.push(0);
+                if self.base.hit_test(x, y, path) {
+                    return true;
+                }
+                path.pop();
+                false
+            }
+        }
+    }
+}
+
+impl<S, R: Default+From<RedrawCallback>, T: RouteSignal<S, R>, O: RouteSignal<S, R>> RouteSignal<S, R> for OverlayContainer<T, O> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        match path.first() {
+            Some(0) => self.base.route_signal(&path[1..], signal),
+            Some(&i) => {
+                let dismiss = i == self.layers.len() && self.layers[i-1].dismiss_mode == DismissMode::AnyClick && signal.is_release();
+                let callback = match self.layers.get_mut(i-1) {
+                    Some(entry) => entry.content.route_signal(&path[1..], signal),
+                    None => return R::default()
+                };
+                if dismiss {
+                    self.layers.pop();
+                    R::from(RedrawCallback::new(true))
+                } else {
+                    callback
+                }
+            },
+            // the press landed outside the top layer, same as the unrouted path
+            None => match self.layers.last() {
+                Some(entry) if entry.dismiss_mode != DismissMode::Manual && signal.is_press() => {
+                    self.layers.pop();
+                    R::from(RedrawCallback::new(true))
+                },
+                _ => R::default()
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{construct::LineTarget, signal::ClickEvent, hit_test::{PointerRouter, compute_hit_path}, test_util::Block};
+
+    type Reconstruct = ReconstructCallback<LineTarget>;
+
+    fn overlay() -> OverlayContainer<Block, Block> {
+        OverlayContainer::new(Block::at(-1.0, 1.0, 1.0, -1.0))
+    }
+
+    fn popup() -> Block {
+        Block::at(-0.5, 0.5, 0.5, -0.5)
+    }
+
+    fn move_to(overlay: &mut OverlayContainer<Block, Block>, x: f32, y: f32) {
+        let _: Reconstruct = overlay.take_signal(&mut CursorMovedSignal {
+            pixel_posx: 0.0,
+            pixel_posy: 0.0,
+            norm_posx: x,
+            norm_posy: y,
+        });
+    }
+
+    fn tick() -> TickSignal {
+        TickSignal {
+            dt: Duration::from_millis(16),
+            now: Duration::from_millis(16),
+        }
+    }
+
+    #[test]
+    fn push_and_pop_layers() {
+        let mut overlay = overlay();
+        assert!(!overlay.is_open());
+        overlay.push(popup(), DismissMode::Manual);
+        overlay.push(popup(), DismissMode::ClickOutside);
+        assert_eq!(overlay.get_layers_mut().len(), 2);
+        assert!(overlay.pop().is_some());
+        overlay.dismiss_all();
+        assert!(!overlay.is_open());
+        assert!(overlay.pop().is_none());
+    }
+
+    #[test]
+    fn handle_pushes_on_next_signal() {
+        let mut overlay = overlay();
+        let handle = overlay.handle();
+        handle.clone().push(popup(), DismissMode::Manual);
+        assert!(!overlay.is_open());
+
+        let _: RedrawCallback = overlay.take_signal(&mut tick());
+        assert!(overlay.is_open());
+    }
+
+    #[test]
+    fn broadcast_reaches_base_and_every_layer() {
+        let mut overlay = overlay();
+        overlay.push(popup(), DismissMode::Manual);
+        overlay.push(popup(), DismissMode::AnyClick);
+
+        let _: RedrawCallback = overlay.take_signal(&mut tick());
+        assert_eq!(overlay.get_base().signals, 1);
+        for entry in overlay.get_layers_mut() {
+            assert_eq!(entry.get_content().signals, 1);
+        }
+    }
+
+    #[test]
+    fn press_inside_reaches_only_the_top_layer() {
+        let mut overlay = overlay();
+        overlay.push(popup(), DismissMode::ClickOutside);
+        move_to(&mut overlay, 0.0, 0.0);
+        let base_signals = overlay.get_base().signals;
+
+        let reconstruct_callback: Reconstruct = overlay.take_signal(&mut MouseLeftDownSignal());
+        assert!(!reconstruct_callback.get_reconstruct());
+        assert!(overlay.is_open());
+        assert_eq!(overlay.get_base().signals, base_signals);
+        assert_eq!(overlay.get_layers_mut()[0].get_content().signals, 2);
+    }
+
+    #[test]
+    fn press_outside_dismisses_and_is_swallowed() {
+        let mut overlay = overlay();
+        overlay.push(popup(), DismissMode::ClickOutside);
+        move_to(&mut overlay, 0.8, 0.8);
+        let base_signals = overlay.get_base().signals;
+
+        let reconstruct_callback: Reconstruct = overlay.take_signal(&mut MouseLeftDownSignal());
+        assert!(reconstruct_callback.get_reconstruct());
+        assert!(!overlay.is_open());
+        assert_eq!(overlay.get_base().signals, base_signals);
+    }
+
+    #[test]
+    fn manual_layer_ignores_press_outside() {
+        let mut overlay = overlay();
+        overlay.push(popup(), DismissMode::Manual);
+        move_to(&mut overlay, 0.8, 0.8);
+        let base_signals = overlay.get_base().signals;
+
+        let _: Reconstruct = overlay.take_signal(&mut MouseLeftDownSignal());
+        assert!(overlay.is_open());
+        assert_eq!(overlay.get_base().signals, base_signals);
+    }
+
+    #[test]
+    fn any_click_dismisses_on_release_inside() {
+        let mut overlay = overlay();
+        overlay.push(popup(), DismissMode::AnyClick);
+        move_to(&mut overlay, 0.0, 0.0);
+
+        let _: Reconstruct = overlay.take_signal(&mut MouseLeftDownSignal());
+        assert!(overlay.is_open());
+        let reconstruct_callback: Reconstruct = overlay.take_signal(&mut MouseLeftUpSignal());
+        assert!(reconstruct_callback.get_reconstruct());
+        assert!(!overlay.is_open());
+    }
+
+    #[test]
+    fn hit_test_ends_on_the_overlay_outside_a_dismissable_layer() {
+        let mut overlay = overlay();
+        assert_eq!(compute_hit_path(&overlay, 0.8, 0.8), Some(vec![0]));
+
+        overlay.push(popup(), DismissMode::ClickOutside);
+        assert_eq!(compute_hit_path(&overlay, 0.0, 0.0), Some(vec![1]));
+        assert_eq!(compute_hit_path(&overlay, 0.8, 0.8), Some(vec![]));
+
+        overlay.push(popup(), DismissMode::Manual);
+        assert_eq!(compute_hit_path(&overlay, 0.8, 0.8), None);
+    }
+
+    #[test]
+    fn routed_press_outside_dismisses_and_is_swallowed() {
+        let mut overlay = overlay();
+        overlay.push(popup(), DismissMode::ClickOutside);
+        let mut router = PointerRouter::new(overlay);
+        let _: Reconstruct = router.take_signal(&mut CursorMovedSignal {
+            pixel_posx: 0.0,
+            pixel_posy: 0.0,
+            norm_posx: 0.8,
+            norm_posy: 0.8,
+        });
+
+        let reconstruct_callback: Reconstruct = router.take_signal(&mut MouseLeftDownSignal());
+        assert!(reconstruct_callback.get_reconstruct());
+        assert!(!router.get_child().is_open());
+        assert_eq!(router.get_child().get_base().signals, 0);
+    }
+
+    #[test]
+    fn routed_release_inside_any_click_dismisses() {
+        let mut overlay = overlay();
+        overlay.push(popup(), DismissMode::AnyClick);
+        let mut router = PointerRouter::new(overlay);
+        let _: Reconstruct = router.take_signal(&mut CursorMovedSignal {
+            pixel_posx: 0.0,
+            pixel_posy: 0.0,
+            norm_posx: 0.0,
+            norm_posy: 0.0,
+        });
+
+        let _: Reconstruct = router.take_signal(&mut MouseLeftDownSignal());
+        assert!(router.get_child().is_open());
+        let reconstruct_callback: Reconstruct = router.take_signal(&mut MouseLeftUpSignal());
+        assert!(reconstruct_callback.get_reconstruct());
+        assert!(!router.get_child().is_open());
+    }
+
+    #[test]
+    fn click_signal_applies_pending_layers() {
+        let mut overlay = overlay();
+        overlay.handle().push(popup(), DismissMode::ClickOutside);
+
+        let _: Reconstruct = overlay.take_signal(&mut ClickSignal {
+            event: ClickEvent::DoubleClick,
+            button: MouseButton::Left,
+            norm_posx: 0.8,
+            norm_posy: 0.8,
+        });
+        assert!(overlay.is_open());
+        assert_eq!(overlay.get_base().signals, 0);
+    }
+}
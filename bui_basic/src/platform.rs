@@ -0,0 +1,216 @@
+use std::{fmt, sync::{Arc, Mutex}};
+
+use bui::rect::Points;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputType {
+    Text,
+    Number,
+    Password,
+    Email,
+    Url,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlatformInputError {
+    Unsupported,
+    Platform(String),
+}
+
+impl fmt::Display for PlatformInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlatformInputError::Unsupported => write!(f, "unsupported by platform"),
+            PlatformInputError::Platform(message) => write!(f, "platform error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PlatformInputError {}
+
+pub trait PlatformInput: fmt::Debug+Send {
+    fn show_keyboard(&mut self) -> Result<(), PlatformInputError>;
+    fn hide_keyboard(&mut self) -> Result<(), PlatformInputError>;
+    fn set_input_type(&mut self, input_type: InputType) -> Result<(), PlatformInputError>;
+    // cursor_rect is in normalized coordinates
+    fn set_cursor_rect(&mut self, cursor_rect: Points) -> Result<(), PlatformInputError>;
+}
+
+pub type SharedPlatformInput = Arc<Mutex<dyn PlatformInput>>;
+
+#[cfg(target_os="android")]
+pub fn default_platform_input() -> SharedPlatformInput {
+    Arc::new(Mutex::new(AndroidPlatformInput::new()))
+}
+
+#[cfg(not(target_os="android"))]
+pub fn default_platform_input() -> SharedPlatformInput {
+    Arc::new(Mutex::new(NoPlatformInput))
+}
+
+// a platform missing some request is expected and would otherwise log on every keystroke
+pub fn report_platform_input_error(result: Result<(), PlatformInputError>) {
+    match result {
+        Ok(()) => {},
+        Err(PlatformInputError::Unsupported) => log::debug!("platform input request unsupported"),
+        Err(error) => log::error!("platform input request failed: {}", error),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoPlatformInput;
+
+impl PlatformInput for NoPlatformInput {
+    fn show_keyboard(&mut self) -> Result<(), PlatformInputError> {
+        Ok(())
+    }
+
+    fn hide_keyboard(&mut self) -> Result<(), PlatformInputError> {
+        Ok(())
+    }
+
+    fn set_input_type(&mut self, _input_type: InputType) -> Result<(), PlatformInputError> {
+        Ok(())
+    }
+
+    fn set_cursor_rect(&mut self, _cursor_rect: Points) -> Result<(), PlatformInputError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PlatformInputCall {
+    ShowKeyboard,
+    HideKeyboard,
+    SetInputType(InputType),
+    SetCursorRect(Points),
+}
+
+// keeps every call so tests and desktop builds can check what a mobile platform would have seen
+#[derive(Debug, Clone, Default)]
+pub struct RecordingPlatformInput {
+    calls: Vec<PlatformInputCall>,
+    keyboard_visible: bool,
+}
+
+impl RecordingPlatformInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_calls(&self) -> &Vec<PlatformInputCall> {
+        &self.calls
+    }
+
+    pub fn take_calls(&mut self) -> Vec<PlatformInputCall> {
+        std::mem::take(&mut self.calls)
+    }
+
+    pub fn is_keyboard_visible(&self) -> bool {
+        self.keyboard_visible
+    }
+}
+
+impl PlatformInput for RecordingPlatformInput {
+    fn show_keyboard(&mut self) -> Result<(), PlatformInputError> {
+        self.keyboard_visible = true;
+        self.calls.push(PlatformInputCall::ShowKeyboard);
+        Ok(())
+    }
+
+    fn hide_keyboard(&mut self) -> Result<(), PlatformInputError> {
+        self.keyboard_visible = false;
+        self.calls.push(PlatformInputCall::HideKeyboard);
+        Ok(())
+    }
+
+    fn set_input_type(&mut self, input_type: InputType) -> Result<(), PlatformInputError> {
+        self.calls.push(PlatformInputCall::SetInputType(input_type));
+        Ok(())
+    }
+
+    fn set_cursor_rect(&mut self, cursor_rect: Points) -> Result<(), PlatformInputError> {
+        self.calls.push(PlatformInputCall::SetCursorRect(cursor_rect));
+        Ok(())
+    }
+}
+
+#[cfg(target_os="android")]
+pub use android::AndroidPlatformInput;
+
+#[cfg(target_os="android")]
+mod android {
+    use jni::{JavaVM, JNIEnv, objects::{JObject, JValue}};
+
+    use bui::rect::Points;
+
+    use super::{PlatformInput, PlatformInputError, InputType};
+
+    const SHOW_IMPLICIT: i32 = 1;
+    // HIDE_NOT_ALWAYS would leave a keyboard open that was shown without SHOW_IMPLICIT
+    const HIDE_FLAGS: i32 = 0;
+
+    impl From<jni::errors::Error> for PlatformInputError {
+        fn from(error: jni::errors::Error) -> Self {
+            PlatformInputError::Platform(error.to_string())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct AndroidPlatformInput;
+
+    impl AndroidPlatformInput {
+        pub fn new() -> Self {
+            Self
+        }
+    }
+
+    fn get_input_method_manager<'a>(env: &JNIEnv<'a>, context: JObject<'a>) -> Result<JObject<'a>, PlatformInputError> {
+        let context_class = env.find_class("android/content/Context")?;
+        let input_method_service_name = env.get_static_field(context_class, "INPUT_METHOD_SERVICE", "Ljava/lang/String;")?;
+        Ok(env.call_method(context, "getSystemService", "(Ljava/lang/String;)Ljava/lang/Object;", &[input_method_service_name])?.l()?)
+    }
+
+    fn get_decor_view<'a>(env: &JNIEnv<'a>, context: JObject<'a>) -> Result<JObject<'a>, PlatformInputError> {
+        let window = env.call_method(context, "getWindow", "()Landroid/view/Window;", &[])?.l()?;
+        Ok(env.call_method(window, "getDecorView", "()Landroid/view/View;", &[])?.l()?)
+    }
+
+    impl PlatformInput for AndroidPlatformInput {
+        fn show_keyboard(&mut self) -> Result<(), PlatformInputError> {
+            let ctx = ndk_context::android_context();
+            let vm = unsafe { JavaVM::from_raw(ctx.vm().cast()) }?;
+            let env = vm.attach_current_thread()?;
+            let context = JObject::from(ctx.context().cast());
+
+            // unlike toggleSoftInput this leaves a keyboard that is already showing open
+            let input_method_manager = get_input_method_manager(&env, context)?;
+            let decor_view = get_decor_view(&env, context)?;
+            env.call_method(input_method_manager, "showSoftInput", "(Landroid/view/View;I)Z", &[JValue::Object(decor_view), SHOW_IMPLICIT.into()])?;
+            Ok(())
+        }
+
+        fn hide_keyboard(&mut self) -> Result<(), PlatformInputError> {
+            let ctx = ndk_context::android_context();
+            let vm = unsafe { JavaVM::from_raw(ctx.vm().cast()) }?;
+            let env = vm.attach_current_thread()?;
+            let context = JObject::from(ctx.context().cast());
+
+            let input_method_manager = get_input_method_manager(&env, context)?;
+            let decor_view = get_decor_view(&env, context)?;
+            let window_token = env.call_method(decor_view, "getWindowToken", "()Landroid/os/IBinder;", &[])?.l()?;
+            env.call_method(input_method_manager, "hideSoftInputFromWindow", "(Landroid/os/IBinder;I)Z", &[JValue::Object(window_token), HIDE_FLAGS.into()])?;
+            Ok(())
+        }
+
+        // NativeActivity has no editor view whose EditorInfo could carry the input type
+        fn set_input_type(&mut self, _input_type: InputType) -> Result<(), PlatformInputError> {
+            Err(PlatformInputError::Unsupported)
+        }
+
+        // updateCursorAnchorInfo needs the editor view the input method is bound to
+        fn set_cursor_rect(&mut self, _cursor_rect: Points) -> Result<(), PlatformInputError> {
+            Err(PlatformInputError::Unsupported)
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use bui::freeform_2dcapsule::Freeform2DCapsule;
+
+use crate::{construct::{LineTarget, Freeform2DCapsuleTarget}, style::{Color, Stroke}};
+
+// 8 bit rgba, rows from the top
+#[derive(Debug, Clone, PartialEq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: u32, height: u32, background: Color) -> Self {
+        let background = color_to_rgba8(background);
+        Self {
+            width,
+            height,
+            pixels: background.iter().copied().cycle().take((width*height*4) as usize).collect(),
+        }
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y*self.width+x)*4) as usize;
+        [self.pixels[i], self.pixels[i+1], self.pixels[i+2], self.pixels[i+3]]
+    }
+
+    // source over, coverage scales the alpha of the color
+    pub fn blend_pixel(&mut self, x: u32, y: u32, color: Color, coverage: f32) {
+        let alpha = (color[3]*coverage).clamp(0.0, 1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+        let i = ((y*self.width+x)*4) as usize;
+        for c in 0..3 {
+            let dst = self.pixels[i+c] as f32/255.0;
+            self.pixels[i+c] = ((color[c].clamp(0.0, 1.0)*alpha+dst*(1.0-alpha))*255.0).round() as u8;
+        }
+        let dst_alpha = self.pixels[i+3] as f32/255.0;
+        self.pixels[i+3] = ((alpha+dst_alpha*(1.0-alpha))*255.0).round() as u8;
+    }
+
+    // normalized coordinates to pixel coordinates, y pointing down
+    fn to_pixel(&self, p: [f32; 2]) -> [f32; 2] {
+        [(p[0]+1.0)/2.0*self.width as f32, (1.0-p[1])/2.0*self.height as f32]
+    }
+
+    // normalized lengths are measured along x
+    fn to_pixel_length(&self, length: f32) -> f32 {
+        length/2.0*self.width as f32
+    }
+
+    // an antialiased capsule with the radius going from r1 at p1 to r2 at p2, all in pixels
+    fn draw_capsule_pixels(&mut self, p1: [f32; 2], p2: [f32; 2], r1: f32, r2: f32, color: Color) {
+        let reach = r1.max(r2)+1.0;
+        let xmin = (p1[0].min(p2[0])-reach).floor().max(0.0) as u32;
+        let ymin = (p1[1].min(p2[1])-reach).floor().max(0.0) as u32;
+        let xmax = ((p1[0].max(p2[0])+reach).ceil().max(0.0) as u32).min(self.width);
+        let ymax = ((p1[1].max(p2[1])+reach).ceil().max(0.0) as u32).min(self.height);
+
+        let d = [p2[0]-p1[0], p2[1]-p1[1]];
+        let length_squared = d[0]*d[0]+d[1]*d[1];
+        for y in ymin..ymax {
+            for x in xmin..xmax {
+                // sample at the pixel center
+                let p = [x as f32+0.5-p1[0], y as f32+0.5-p1[1]];
+                let t = if length_squared > 0.0 {
+                    ((p[0]*d[0]+p[1]*d[1])/length_squared).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let (dx, dy) = (p[0]-d[0]*t, p[1]-d[1]*t);
+                let radius = r1+(r2-r1)*t;
+                let coverage = (radius+0.5-(dx*dx+dy*dy).sqrt()).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend_pixel(x, y, color, coverage);
+                }
+            }
+        }
+    }
+
+    pub fn draw_lines(&mut self, target: &LineTarget, stroke: Stroke) {
+        let radius = stroke.width/2.0;
+        for line in &target.0 {
+            self.draw_capsule_pixels(self.to_pixel(line.p1), self.to_pixel(line.p2), radius, radius, stroke.color);
+        }
+    }
+
+    pub fn draw_capsule(&mut self, capsule: &Freeform2DCapsule, color: Color) {
+        self.draw_capsule_pixels(self.to_pixel(capsule.p1), self.to_pixel(capsule.p2), self.to_pixel_length(capsule.r1), self.to_pixel_length(capsule.r2), color);
+    }
+
+    pub fn draw_capsules(&mut self, target: &Freeform2DCapsuleTarget, color: Color) {
+        for capsule in &target.0 {
+            self.draw_capsule(capsule, color);
+        }
+    }
+
+    // how many pixels differ by more than channel_tolerance in any channel
+    pub fn count_differing_pixels(&self, other: &RgbaImage, channel_tolerance: u8) -> Option<usize> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+        Some(self.pixels.chunks_exact(4).zip(other.pixels.chunks_exact(4))
+            .filter(|(a, b)| a.iter().zip(b.iter()).any(|(a, b)| a.abs_diff(*b) > channel_tolerance))
+            .count())
+    }
+}
+
+fn color_to_rgba8(color: Color) -> [u8; 4] {
+    color.map(|c| (c.clamp(0.0, 1.0)*255.0).round() as u8)
+}
+
+#[cfg(feature = "png")]
+pub use snapshot::*;
+
+#[cfg(feature = "png")]
+mod snapshot {
+    use std::{fmt, fs::File, io::{BufReader, BufWriter}, path::{Path, PathBuf}};
+
+    use super::RgbaImage;
+
+    #[derive(Debug)]
+    pub enum RasterError {
+        Io(std::io::Error),
+        Encoding(png::EncodingError),
+        Decoding(png::DecodingError),
+        // only 8 bit rgba images can be compared
+        UnsupportedFormat,
+    }
+
+    impl fmt::Display for RasterError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                RasterError::Io(error) => write!(f, "io error: {}", error),
+                RasterError::Encoding(error) => write!(f, "png encoding error: {}", error),
+                RasterError::Decoding(error) => write!(f, "png decoding error: {}", error),
+                RasterError::UnsupportedFormat => write!(f, "unsupported png format"),
+            }
+        }
+    }
+
+    impl std::error::Error for RasterError {}
+
+    impl From<std::io::Error> for RasterError {
+        fn from(error: std::io::Error) -> Self {
+            RasterError::Io(error)
+        }
+    }
+
+    impl From<png::EncodingError> for RasterError {
+        fn from(error: png::EncodingError) -> Self {
+            RasterError::Encoding(error)
+        }
+    }
+
+    impl From<png::DecodingError> for RasterError {
+        fn from(error: png::DecodingError) -> Self {
+            RasterError::Decoding(error)
+        }
+    }
+
+    impl RgbaImage {
+        pub fn write_png<P: AsRef<Path>>(&self, path: P) -> Result<(), RasterError> {
+            let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), self.width, self.height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.write_header()?.write_image_data(&self.pixels)?;
+            Ok(())
+        }
+
+        pub fn read_png<P: AsRef<Path>>(path: P) -> Result<Self, RasterError> {
+            let mut reader = png::Decoder::new(BufReader::new(File::open(path)?)).read_info()?;
+            let mut pixels = vec![0; reader.output_buffer_size()];
+            let info = reader.next_frame(&mut pixels)?;
+            if info.color_type != png::ColorType::Rgba || info.bit_depth != png::BitDepth::Eight {
+                return Err(RasterError::UnsupportedFormat);
+            }
+            pixels.truncate(info.buffer_size());
+            Ok(Self {
+                width: info.width,
+                height: info.height,
+                pixels,
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct SnapshotTolerance {
+        // per channel difference still counted as equal
+        pub channel: u8,
+        // fraction of pixels allowed to differ
+        pub max_differing: f32,
+    }
+
+    impl SnapshotTolerance {
+        pub const EXACT: Self = Self {
+            channel: 0,
+            max_differing: 0.0,
+        };
+
+        // absorbs antialiasing differences between platforms
+        pub const DEFAULT: Self = Self {
+            channel: 2,
+            max_differing: 0.001,
+        };
+    }
+
+    impl Default for SnapshotTolerance {
+        fn default() -> Self {
+            Self::DEFAULT
+        }
+    }
+
+    // set to write the actual images as the new golden images instead of comparing
+    pub const UPDATE_SNAPSHOTS_VAR: &str = "BUI_BASIC_UPDATE_SNAPSHOTS";
+
+    // Compares against the golden image at path. A missing golden image fails the test unless
+    // UPDATE_SNAPSHOTS_VAR is set, so a moved or deleted golden is not silently recreated.
+    // On a mismatch the actual image is written next to it as <name>.actual.png.
+    pub fn assert_snapshot<P: AsRef<Path>>(image: &RgbaImage, path: P, tolerance: SnapshotTolerance) {
+        let path = path.as_ref();
+        if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).unwrap_or_else(|error| panic!("could not create {}: {}", parent.display(), error));
+            }
+            image.write_png(path).unwrap_or_else(|error| panic!("could not write snapshot {}: {}", path.display(), error));
+            return;
+        }
+        if !path.exists() {
+            let actual_path = actual_path(path);
+            let written = match image.write_png(&actual_path) {
+                Ok(()) => format!("actual image written to {}", actual_path.display()),
+                Err(error) => format!("could not write actual image: {}", error)
+            };
+            panic!("snapshot {} is missing, set {} to write it; {}", path.display(), UPDATE_SNAPSHOTS_VAR, written);
+        }
+
+        let golden = RgbaImage::read_png(path).unwrap_or_else(|error| panic!("could not read snapshot {}: {}", path.display(), error));
+        let failure = match image.count_differing_pixels(&golden, tolerance.channel) {
+            None => Some(format!("size {}x{} does not match the golden {}x{}", image.width, image.height, golden.width, golden.height)),
+            Some(differing) if differing as f32 > tolerance.max_differing*(image.width*image.height) as f32 => Some(format!("{} of {} pixels differ", differing, image.width*image.height)),
+            Some(_) => None
+        };
+
+        if let Some(failure) = failure {
+            let actual_path = actual_path(path);
+            let written = match image.write_png(&actual_path) {
+                Ok(()) => format!("actual image written to {}", actual_path.display()),
+                Err(error) => format!("could not write actual image: {}", error)
+            };
+            panic!("snapshot {} mismatch: {}; {}", path.display(), failure, written);
+        }
+    }
+
+    fn actual_path(path: &Path) -> PathBuf {
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+        path.with_file_name(format!("{}.actual.png", stem))
+    }
+}
+
+#[cfg(all(test, feature = "png"))]
+mod tests {
+    use bui::rect::SizeAndCenter;
+
+    use super::*;
+    use crate::{button::Button, construct::Construct, containers::NoContainer};
+
+    const WHITE: Stroke = Stroke::new([1.0, 1.0, 1.0, 1.0], 2.0);
+
+    fn golden_path(name: &str) -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("snapshots").join(name)
+    }
+
+    fn button_frame() -> RgbaImage {
+        let button = Button::new(NoContainer {}, SizeAndCenter {
+            sx: 0.5,
+            sy: 0.5,
+            cx: 0.0,
+            cy: 0.0,
+        });
+        let mut image = RgbaImage::new(32, 32, [0.0, 0.0, 0.0, 1.0]);
+        image.draw_lines(&button.construct(), WHITE);
+        image
+    }
+
+    #[test]
+    fn button_frame_matches_golden() {
+        assert_snapshot(&button_frame(), golden_path("button_frame.png"), SnapshotTolerance::DEFAULT);
+    }
+
+    #[test]
+    fn changed_pixel_is_counted() {
+        let golden = RgbaImage::read_png(golden_path("button_frame.png")).unwrap();
+        let mut image = golden.clone();
+        image.blend_pixel(16, 16, [1.0, 0.0, 0.0, 1.0], 1.0);
+        assert_eq!(image.count_differing_pixels(&golden, SnapshotTolerance::DEFAULT.channel), Some(1));
+        // small differences in a channel are antialiasing noise
+        let mut image = golden.clone();
+        image.pixels[0] += 1;
+        assert_eq!(image.count_differing_pixels(&golden, SnapshotTolerance::DEFAULT.channel), Some(0));
+        assert_eq!(image.count_differing_pixels(&golden, SnapshotTolerance::EXACT.channel), Some(1));
+    }
+
+    fn changed_snapshot(name: &str, changed: &[(u32, u32)], tolerance: SnapshotTolerance) {
+        let path = std::env::temp_dir().join(format!("bui_basic_{}_{}.png", name, std::process::id()));
+        button_frame().write_png(&path).unwrap();
+        let mut image = button_frame();
+        for (x, y) in changed {
+            image.blend_pixel(*x, *y, [1.0, 0.0, 0.0, 1.0], 1.0);
+        }
+        assert_snapshot(&image, &path, tolerance);
+    }
+
+    #[test]
+    #[should_panic(expected = "1 of 1024 pixels differ")]
+    fn exact_snapshot_catches_a_changed_pixel() {
+        changed_snapshot("exact", &[(16, 16)], SnapshotTolerance::EXACT);
+    }
+
+    #[test]
+    #[should_panic(expected = "4 of 1024 pixels differ")]
+    fn default_snapshot_catches_changed_pixels() {
+        changed_snapshot("default", &[(15, 15), (16, 15), (15, 16), (16, 16)], SnapshotTolerance::DEFAULT);
+    }
+
+    #[test]
+    #[should_panic(expected = "is missing, set BUI_BASIC_UPDATE_SNAPSHOTS to write it")]
+    fn missing_snapshot_fails() {
+        let path = std::env::temp_dir().join(format!("bui_basic_missing_{}.png", std::process::id()));
+        assert_snapshot(&button_frame(), &path, SnapshotTolerance::DEFAULT);
+    }
+
+    #[test]
+    fn size_mismatch_is_not_counted() {
+        let image = RgbaImage::new(2, 2, [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(image.count_differing_pixels(&RgbaImage::new(2, 3, [0.0, 0.0, 0.0, 1.0]), 0), None);
+    }
+}
\ No newline at end of file
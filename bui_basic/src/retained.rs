@@ -0,0 +1,274 @@
+use std::{ops::Range, sync::atomic::{AtomicU64, Ordering}, collections::HashMap};
+
+use crate::construct::{LineTarget, Freeform2DCapsuleTarget};
+
+static NEXT_RETAINED_ID: AtomicU64 = AtomicU64::new(0);
+
+// Identifies the widget owning a slot. Clones get a new id so they never reuse the original's slot.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RetainedId(u64);
+
+impl RetainedId {
+    pub fn new() -> Self {
+        Self(NEXT_RETAINED_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Clone for RetainedId {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl Default for RetainedId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub trait IntoPrimitives {
+    type Primitive: Clone;
+    fn into_primitives(self) -> Vec<Self::Primitive>;
+}
+
+impl IntoPrimitives for LineTarget {
+    type Primitive = bui::line::LineRaw;
+
+    fn into_primitives(self) -> Vec<Self::Primitive> {
+        self.0
+    }
+}
+
+impl IntoPrimitives for Freeform2DCapsuleTarget {
+    type Primitive = bui::freeform_2dcapsule::Freeform2DCapsule;
+
+    fn into_primitives(self) -> Vec<Self::Primitive> {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SlotRecord {
+    id: u64,
+    start: usize,
+    capacity: usize,
+}
+
+// A buffer shared by the whole tree where every widget keeps its primitives in its own slot.
+// Slots are visited in construct order each frame and found again by the widget's id; a widget
+// whose slot did not move and that is not dirty is skipped, everything else is written again
+// and reported in the changed ranges.
+// Unused room at the end of a slot is filled with the blank primitive, which must draw nothing.
+#[derive(Debug, Clone)]
+pub struct RetainedTarget<C: IntoPrimitives> {
+    buffer: Vec<C::Primitive>,
+    blank: C::Primitive,
+    slots: Vec<SlotRecord>,
+    previous_slots: HashMap<u64, SlotRecord>,
+    cursor: usize,
+    changed: Vec<Range<usize>>,
+}
+
+impl<C: IntoPrimitives> RetainedTarget<C> {
+    pub fn new(blank: C::Primitive) -> Self {
+        Self {
+            buffer: Vec::new(),
+            blank,
+            slots: Vec::new(),
+            previous_slots: HashMap::new(),
+            cursor: 0,
+            changed: Vec::new(),
+        }
+    }
+
+    pub fn get_buffer(&self) -> &Vec<C::Primitive> {
+        &self.buffer
+    }
+
+    // sorted and merged, the buffer length is reported separately through get_buffer
+    pub fn take_changed(&mut self) -> Vec<Range<usize>> {
+        let mut changed = std::mem::take(&mut self.changed);
+        changed.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(changed.len());
+        for range in changed {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range)
+            }
+        }
+        merged
+    }
+
+    // forget every slot so the next frame writes the whole buffer again
+    pub fn invalidate(&mut self) {
+        self.slots.clear();
+        self.previous_slots.clear();
+    }
+
+    pub fn construct<T: RetainedConstruct<C>+?Sized>(&mut self, root: &T) {
+        self.previous_slots = self.slots.drain(..).map(|slot| (slot.id, slot)).collect();
+        self.cursor = 0;
+
+        root.construct_retained(self);
+
+        if self.buffer.len() > self.cursor {
+            self.buffer.truncate(self.cursor);
+        }
+    }
+
+    pub fn write_slot(&mut self, id: &RetainedId, dirty: bool, construct: impl FnOnce() -> C) {
+        let start = self.cursor;
+        let previous = self.previous_slots.get(&id.0)
+            .copied()
+            .filter(|previous| previous.start == start);
+
+        if let (Some(previous), false) = (previous, dirty) {
+            self.slots.push(previous);
+            self.cursor += previous.capacity;
+            return;
+        }
+
+        let primitives = construct().into_primitives();
+        // leave some room so a slot that keeps growing a little does not move everything after it
+        let capacity = match previous {
+            Some(previous) if primitives.len() <= previous.capacity => previous.capacity,
+            _ => primitives.len()+primitives.len()/2
+        };
+        let end = start+capacity;
+
+        if self.buffer.len() < end {
+            self.buffer.resize(end, self.blank.clone());
+        }
+        let filled = start+primitives.len();
+        for (slot, primitive) in self.buffer[start..filled].iter_mut().zip(primitives) {
+            *slot = primitive;
+        }
+        for slot in &mut self.buffer[filled..end] {
+            *slot = self.blank.clone();
+        }
+
+        if end > start {
+            self.changed.push(start..end);
+        }
+        self.slots.push(SlotRecord {
+            id: id.0,
+            start,
+            capacity,
+        });
+        self.cursor = end;
+    }
+}
+
+pub trait RetainedConstruct<C: IntoPrimitives> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Numbers(Vec<u32>);
+
+    impl IntoPrimitives for Numbers {
+        type Primitive = u32;
+
+        fn into_primitives(self) -> Vec<Self::Primitive> {
+            self.0
+        }
+    }
+
+    struct Leaf {
+        id: RetainedId,
+        value: u32,
+        len: usize,
+        dirty: bool,
+    }
+
+    impl Leaf {
+        fn new(value: u32, len: usize) -> Self {
+            Self {
+                id: RetainedId::new(),
+                value,
+                len,
+                dirty: true,
+            }
+        }
+    }
+
+    impl RetainedConstruct<Numbers> for Leaf {
+        fn construct_retained(&self, target: &mut RetainedTarget<Numbers>) {
+            target.write_slot(&self.id, self.dirty, || Numbers(vec![self.value; self.len]));
+        }
+    }
+
+    impl RetainedConstruct<Numbers> for Vec<Leaf> {
+        fn construct_retained(&self, target: &mut RetainedTarget<Numbers>) {
+            for leaf in self {
+                leaf.construct_retained(target);
+            }
+        }
+    }
+
+    fn clean(leaves: &mut Vec<Leaf>) {
+        for leaf in leaves {
+            leaf.dirty = false;
+        }
+    }
+
+    #[test]
+    fn clean_tree_reports_no_changes() {
+        let mut leaves = vec![Leaf::new(1, 2), Leaf::new(2, 2)];
+        let mut target = RetainedTarget::new(0);
+        target.construct(&leaves);
+        assert_eq!(target.take_changed(), vec![0..6]);
+
+        clean(&mut leaves);
+        target.construct(&leaves);
+        assert!(target.take_changed().is_empty());
+        assert_eq!(target.get_buffer(), &vec![1, 1, 0, 2, 2, 0]);
+    }
+
+    #[test]
+    fn dirty_leaf_rewrites_only_its_slot() {
+        let mut leaves = vec![Leaf::new(1, 2), Leaf::new(2, 2)];
+        let mut target = RetainedTarget::new(0);
+        target.construct(&leaves);
+        target.take_changed();
+
+        clean(&mut leaves);
+        leaves[1].value = 3;
+        leaves[1].dirty = true;
+        target.construct(&leaves);
+        assert_eq!(target.take_changed(), vec![3..6]);
+        assert_eq!(target.get_buffer(), &vec![1, 1, 0, 3, 3, 0]);
+    }
+
+    #[test]
+    fn slots_are_found_by_id_not_position() {
+        let mut leaves = vec![Leaf::new(1, 2), Leaf::new(2, 2)];
+        let mut target = RetainedTarget::new(0);
+        target.construct(&leaves);
+        target.take_changed();
+
+        // an empty leaf takes no room, so the ones after it keep their place
+        clean(&mut leaves);
+        leaves.insert(0, Leaf::new(9, 0));
+        target.construct(&leaves);
+        assert!(target.take_changed().is_empty());
+    }
+
+    #[test]
+    fn moved_slot_is_written_again() {
+        let mut leaves = vec![Leaf::new(1, 2), Leaf::new(2, 2)];
+        let mut target = RetainedTarget::new(0);
+        target.construct(&leaves);
+        target.take_changed();
+
+        clean(&mut leaves);
+        leaves.remove(0);
+        target.construct(&leaves);
+        assert_eq!(target.take_changed(), vec![0..3]);
+        assert_eq!(target.get_buffer(), &vec![2, 2, 0]);
+    }
+}
\ No newline at end of file
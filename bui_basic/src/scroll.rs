@@ -0,0 +1,496 @@
+use std::time::Duration;
+
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::{Construct, StandardConstructTarget}, containers::{Fill, FillWidth, Init, TranslateX, TranslateY, GetPointBounds}, signal::{SignalReciever, ShortCircuitingCallback, ReconstructCallback, RedrawCallback, ResizedSignal, CursorMovedSignal, ScrollSignal, FlingSignal, TickSignal}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, Size, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}, draw_list::DrawList, units::DEFAULT_RESYP};
+
+// Lays its child out by width inside a viewport and scrolls it vertically.
+// Content outside the viewport is still constructed; only a DrawList clips it to the viewport.
+#[derive(Debug, Clone)]
+pub struct ScrollContainer<T> {
+    child: T,
+    viewport: SizeAndCenter,
+    points: Points,
+    offset: f32,
+    content_height: f32,
+    pixel_size_y: f32,
+    mousex: f32,
+    mousey: f32,
+    // decay rate per second of the velocity left after scrolling, None for no momentum
+    friction: Option<f32>,
+    // normalized units per second
+    velocity: f32,
+    // scrolled by input since the last tick
+    pending_delta: f32,
+}
+
+impl<T> ScrollContainer<T> {
+    pub const DEFAULT_FRICTION: f32 = 5.0;
+    // below this the content stops coasting
+    pub const MIN_VELOCITY: f32 = 0.01;
+
+    pub fn new(child: T) -> Self {
+        Self::new_with_resyp(child, DEFAULT_RESYP)
+    }
+
+    pub fn new_with_resyp(child: T, resyp: u32) -> Self {
+        Self {
+            child,
+            viewport: SizeAndCenter {
+                sx: 0.0,
+                sy: 0.0,
+                cx: 0.0,
+                cy: 0.0,
+            },
+            points: Points::ZERO,
+            offset: 0.0,
+            content_height: 0.0,
+            pixel_size_y: 2.0/resyp.max(1) as f32,
+            mousex: -1.0,
+            mousey: -1.0,
+            friction: None,
+            velocity: 0.0,
+            pending_delta: 0.0,
+        }
+    }
+
+    // keep coasting after scroll input stops, slowing down by friction per second
+    pub fn with_momentum(mut self, friction: f32) -> Self {
+        self.friction = Some(friction);
+        self
+    }
+
+    pub fn set_momentum(&mut self, friction: Option<f32>) {
+        self.friction = friction;
+        if friction.is_none() {
+            self.velocity = 0.0;
+        }
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    pub fn get_offset(&self) -> f32 {
+        self.offset
+    }
+
+    pub fn get_max_offset(&self) -> f32 {
+        (self.content_height-self.viewport.sy*2.0).max(0.0)
+    }
+
+    // positive dy moves the content up
+    pub fn scroll_by(&mut self, dy: f32) -> bool
+    where
+        T: TranslateY
+    {
+        self.scroll_to(self.offset+dy)
+    }
+
+    pub fn scroll_to(&mut self, offset: f32) -> bool
+    where
+        T: TranslateY
+    {
+        let offset = offset.clamp(0.0, self.get_max_offset());
+        if offset == self.offset {
+            false
+        } else {
+            self.child.translate_y(offset-self.offset);
+            self.offset = offset;
+            true
+        }
+    }
+
+    pub fn scroll_by_pixels(&mut self, py: f32) -> bool
+    where
+        T: TranslateY
+    {
+        let dy = -py*self.pixel_size_y;
+        self.pending_delta += dy;
+        self.scroll_by(dy)
+    }
+
+    // starts coasting at the given speed in pixels per second, ignored without momentum
+    pub fn fling(&mut self, vy: f32) {
+        if self.friction.is_some() {
+            self.velocity = -vy*self.pixel_size_y;
+            self.pending_delta = 0.0;
+        }
+    }
+
+    // returns whether the content moved
+    fn tick_momentum(&mut self, dt: Duration) -> bool
+    where
+        T: TranslateY
+    {
+        let friction = match self.friction {
+            Some(friction) => friction,
+            None => {
+                self.pending_delta = 0.0;
+                return false;
+            }
+        };
+        let dt = dt.as_secs_f32();
+        if dt <= 0.0 {
+            return false;
+        }
+
+        // while input keeps coming the velocity follows it, coasting only starts once it stops
+        if self.pending_delta != 0.0 {
+            self.velocity = self.pending_delta/dt;
+            self.pending_delta = 0.0;
+            return false;
+        }
+        if self.velocity == 0.0 {
+            return false;
+        }
+
+        let moved = self.scroll_by(self.velocity*dt);
+        self.velocity *= (-friction*dt).exp();
+        if !moved || self.velocity.abs() < Self::MIN_VELOCITY {
+            self.velocity = 0.0;
+        }
+        moved
+    }
+
+    fn clip(&self, content: DrawList) -> DrawList {
+        let mut target = DrawList::EMPTY;
+        target.push_clip(self.points);
+        target.append(content);
+        target.pop_clip();
+        target
+    }
+}
+
+impl<T: Init> Init for ScrollContainer<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
+}
+
+impl<T: FillWidth+TranslateY> Fill for ScrollContainer<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.viewport = fill_target;
+        self.points = fill_target.into();
+
+        let ty = fill_target.cy+fill_target.sy;
+        let by = self.child.fill_width(fill_target.sx, fill_target.cx, ty+self.offset);
+        self.content_height = ty+self.offset-by;
+
+        let offset = self.offset;
+        self.offset = offset.min(self.get_max_offset());
+        if self.offset != offset {
+            self.child.translate_y(self.offset-offset);
+        }
+    }
+}
+
+// can shrink to nothing in height since the rest is scrolled to
+impl<T: Measure> Measure for ScrollContainer<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let child = self.child.measure(Constraints::width(constraints.max_width));
+        SizeHint {
+            min: Size::new(child.min.width, 0.0),
+            preferred: constraints.constrain(child.preferred),
+            max: Size::new(child.max.width, f32::INFINITY),
+        }
+    }
+}
+
+// a child growing or shrinking only changes how far there is to scroll
+impl<T: Dirty+TranslateY> Dirty for ScrollContainer<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let height_change = self.child.update_layout();
+        if height_change != 0.0 {
+            self.content_height += height_change;
+            self.scroll_to(self.offset);
+        }
+        0.0
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<T: TranslateX> TranslateX for ScrollContainer<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.viewport.cx += dx;
+        self.points.p1x += dx;
+        self.points.p2x += dx;
+        self.child.translate_x(dx);
+    }
+}
+
+impl<T: TranslateY> TranslateY for ScrollContainer<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.viewport.cy += dy;
+        self.points.p1y += dy;
+        self.points.p2y += dy;
+        self.child.translate_y(dy);
+    }
+}
+
+impl<T> GetPointBounds for ScrollContainer<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.points
+    }
+}
+
+impl<T: HitTest> HitTest for ScrollContainer<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.points.contains(x, y) && self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for ScrollContainer<T> {
+    default fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
+impl<C, T: TranslateY+RouteSignal<ScrollSignal, ReconstructCallback<C>>> RouteSignal<ScrollSignal, ReconstructCallback<C>> for ScrollContainer<T> {
+    fn route_signal(&mut self, _path: &[usize], signal: &mut ScrollSignal) -> ReconstructCallback<C> {
+        ReconstructCallback::new(self.scroll_by_pixels(signal.py))
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for ScrollContainer<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for ScrollContainer<T> {
+    default fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+impl<T: Construct<DrawList>> Construct<DrawList> for ScrollContainer<T> {
+    fn construct(&self) -> DrawList {
+        self.clip(self.child.construct())
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for ScrollContainer<T> {
+    default fn construct_styled(&self, theme: &Theme) -> C {
+        self.child.construct_styled(theme)
+    }
+}
+
+impl<T: ConstructStyled<DrawList>> ConstructStyled<DrawList> for ScrollContainer<T> {
+    fn construct_styled(&self, theme: &Theme) -> DrawList {
+        self.clip(self.child.construct_styled(theme))
+    }
+}
+
+impl<S, R, T: SignalReciever<S, R>> SignalReciever<S, R> for ScrollContainer<T> {
+    default fn take_signal(&mut self, signal: &mut S) -> R {
+        self.child.take_signal(signal)
+    }
+}
+
+impl<R, T: SignalReciever<ResizedSignal, R>> SignalReciever<ResizedSignal, R> for ScrollContainer<T> {
+    fn take_signal(&mut self, signal: &mut ResizedSignal) -> R {
+        self.pixel_size_y = 2.0/signal.resyp.max(1) as f32;
+        self.child.take_signal(signal)
+    }
+}
+
+impl<R, T: SignalReciever<CursorMovedSignal, R>> SignalReciever<CursorMovedSignal, R> for ScrollContainer<T> {
+    fn take_signal(&mut self, signal: &mut CursorMovedSignal) -> R {
+        self.mousex = signal.norm_posx;
+        self.mousey = signal.norm_posy;
+        self.child.take_signal(signal)
+    }
+}
+
+impl<T: TranslateY+SignalReciever<TickSignal, RedrawCallback>> SignalReciever<TickSignal, RedrawCallback> for ScrollContainer<T> {
+    fn take_signal(&mut self, signal: &mut TickSignal) -> RedrawCallback {
+        RedrawCallback::new(self.tick_momentum(signal.dt)).or_into(self.child.take_signal(signal))
+    }
+}
+
+impl<C, T: TranslateY+SignalReciever<TickSignal, ReconstructCallback<C>>> SignalReciever<TickSignal, ReconstructCallback<C>> for ScrollContainer<T> {
+    fn take_signal(&mut self, signal: &mut TickSignal) -> ReconstructCallback<C> {
+        ReconstructCallback::new(self.tick_momentum(signal.dt)).or_into(self.child.take_signal(signal))
+    }
+}
+
+// the content keeps moving on the following ticks
+impl<R: Default, T: SignalReciever<FlingSignal, R>> SignalReciever<FlingSignal, R> for ScrollContainer<T> {
+    fn take_signal(&mut self, signal: &mut FlingSignal) -> R {
+        if self.points.contains(self.mousex, self.mousey) {
+            self.fling(signal.vy);
+            R::default()
+        } else {
+            self.child.take_signal(signal)
+        }
+    }
+}
+
+impl<C, T: TranslateY+SignalReciever<ScrollSignal, ReconstructCallback<C>>> SignalReciever<ScrollSignal, ReconstructCallback<C>> for ScrollContainer<T> {
+    fn take_signal(&mut self, signal: &mut ScrollSignal) -> ReconstructCallback<C> {
+        if self.points.contains(self.mousex, self.mousey) {
+            ReconstructCallback::new(self.scroll_by_pixels(signal.py))
+        } else {
+            self.child.take_signal(signal)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{construct::LineTarget, test_util::Block};
+
+    type Reconstruct = ReconstructCallback<LineTarget>;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a-b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    // a 4 high column in a 1 high viewport, with the cursor over it
+    fn scroll() -> ScrollContainer<Block> {
+        let mut scroll = ScrollContainer::new(Block::new(1.0, 4.0));
+        scroll.fill(SizeAndCenter {
+            sx: 0.5,
+            sy: 0.5,
+            cx: 0.0,
+            cy: 0.0,
+        });
+        let _: Reconstruct = scroll.take_signal(&mut CursorMovedSignal {
+            pixel_posx: 0.0,
+            pixel_posy: 0.0,
+            norm_posx: 0.0,
+            norm_posy: 0.0,
+        });
+        scroll
+    }
+
+    fn tick(scroll: &mut ScrollContainer<Block>, millis: u64) -> bool {
+        let callback: RedrawCallback = scroll.take_signal(&mut TickSignal {
+            dt: Duration::from_millis(millis),
+            now: Duration::ZERO,
+        });
+        callback.get_redraw()
+    }
+
+    #[test]
+    fn scroll_signal_moves_the_content_by_pixels() {
+        let mut scroll = scroll();
+        assert_close(scroll.get_max_offset(), 3.0);
+
+        let callback: Reconstruct = scroll.take_signal(&mut ScrollSignal {
+            px: 0.0,
+            py: -72.0,
+        });
+        assert!(callback.get_reconstruct());
+        assert_close(scroll.get_offset(), 0.2);
+        assert_close(scroll.get_child().points.p1y, 0.7);
+    }
+
+    #[test]
+    fn offset_is_clamped_to_the_content() {
+        let mut scroll = scroll();
+        let _: Reconstruct = scroll.take_signal(&mut ScrollSignal {
+            px: 0.0,
+            py: -100000.0,
+        });
+        assert_close(scroll.get_offset(), 3.0);
+        assert_close(scroll.get_child().points.p2y, -0.5);
+
+        let _: Reconstruct = scroll.take_signal(&mut ScrollSignal {
+            px: 0.0,
+            py: 100000.0,
+        });
+        assert_close(scroll.get_offset(), 0.0);
+        assert_close(scroll.get_child().points.p1y, 0.5);
+
+        let callback: Reconstruct = scroll.take_signal(&mut ScrollSignal {
+            px: 0.0,
+            py: 10.0,
+        });
+        assert!(!callback.get_reconstruct());
+    }
+
+    #[test]
+    fn scrolling_outside_the_viewport_goes_to_the_child() {
+        let mut scroll = scroll();
+        let _: Reconstruct = scroll.take_signal(&mut CursorMovedSignal {
+            pixel_posx: 0.0,
+            pixel_posy: 0.0,
+            norm_posx: 0.9,
+            norm_posy: 0.0,
+        });
+        let _: Reconstruct = scroll.take_signal(&mut ScrollSignal {
+            px: 0.0,
+            py: -72.0,
+        });
+        assert_close(scroll.get_offset(), 0.0);
+        assert_eq!(scroll.get_child().received.last(), Some(&"ScrollSignal"));
+    }
+
+    #[test]
+    fn pixels_follow_the_window_height() {
+        let mut scroll = scroll();
+        let _: Reconstruct = scroll.take_signal(&mut ResizedSignal {
+            resxp: 640,
+            resyp: 360,
+            resx: 640.0,
+            resy: 360.0,
+        });
+        let _: Reconstruct = scroll.take_signal(&mut ScrollSignal {
+            px: 0.0,
+            py: -36.0,
+        });
+        assert_close(scroll.get_offset(), 0.2);
+    }
+
+    #[test]
+    fn fling_without_momentum_is_ignored() {
+        let mut scroll = scroll();
+        let _: Reconstruct = scroll.take_signal(&mut FlingSignal {
+            vx: 0.0,
+            vy: -720.0,
+        });
+        assert!(!tick(&mut scroll, 100));
+        assert_close(scroll.get_offset(), 0.0);
+    }
+
+    #[test]
+    fn fling_coasts_and_slows_down() {
+        let mut scroll = scroll().with_momentum(ScrollContainer::<Block>::DEFAULT_FRICTION);
+        let _: Reconstruct = scroll.take_signal(&mut FlingSignal {
+            vx: 0.0,
+            vy: -720.0,
+        });
+        assert!(tick(&mut scroll, 100));
+        assert_close(scroll.get_offset(), 0.2);
+
+        assert!(tick(&mut scroll, 100));
+        let second_step = scroll.get_offset()-0.2;
+        assert!(second_step > 0.0 && second_step < 0.2);
+    }
+
+    #[test]
+    fn fling_stops_at_the_end_of_the_content() {
+        let mut scroll = scroll().with_momentum(ScrollContainer::<Block>::DEFAULT_FRICTION);
+        let _: Reconstruct = scroll.take_signal(&mut FlingSignal {
+            vx: 0.0,
+            vy: -72000.0,
+        });
+        assert!(tick(&mut scroll, 100));
+        assert_close(scroll.get_offset(), 3.0);
+        assert!(!tick(&mut scroll, 100));
+    }
+}
@@ -1,9 +1,38 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, time::Duration};
 
 pub trait SignalReciever<T, R> {
     fn take_signal(&mut self, signal: &mut T) -> R;
 }
 
+// passes the listed signals unchanged to a single-child wrapper's child field
+// usage: forward_signals!(ClickDetector.child: ResizedSignal, TickSignal);
+macro_rules! forward_signals {
+    ($wrapper:ident.$child:ident: $($signal:ident),+ $(,)?) => {$(
+        impl<R, T: $crate::signal::SignalReciever<$crate::signal::$signal, R>> $crate::signal::SignalReciever<$crate::signal::$signal, R> for $wrapper<T> {
+            fn take_signal(&mut self, signal: &mut $crate::signal::$signal) -> R {
+                $crate::signal::SignalReciever::take_signal(&mut self.$child, signal)
+            }
+        }
+    )+};
+}
+pub(crate) use forward_signals;
+
+// answers the listed signals with the default callback for widgets that never react to them
+// usage: ignore_signals!(Text: ScrollSignal); or ignore_signals!([T] Button<T>: ScrollSignal);
+macro_rules! ignore_signals {
+    ([$($generic:ident),*] $widget:ty: $($signal:ident),+ $(,)?) => {$(
+        impl<$($generic,)* R: Default> $crate::signal::SignalReciever<$crate::signal::$signal, R> for $widget {
+            fn take_signal(&mut self, _signal: &mut $crate::signal::$signal) -> R {
+                R::default()
+            }
+        }
+    )+};
+    ($widget:ty: $($signal:ident),+ $(,)?) => {
+        $crate::signal::ignore_signals!([] $widget: $($signal),+);
+    };
+}
+pub(crate) use ignore_signals;
+
 // pub struct SignalInterceptor<Child, Signal, Callback> {
 //     child: Child,
 //     function: fn(&mut Child, &mut Signal) -> Callback,
@@ -72,6 +101,87 @@ pub trait ShortCircuitingCallback {
     fn or_into(self, other: Self) -> Self;
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Capture,
+    Bubble,
+}
+
+// Wraps a signal so recievers can stop it from reaching the rest of the tree.
+// In the capture phase a widget reacts before its children, in the bubble phase after.
+#[derive(Debug, Clone)]
+pub struct Propagating<S> {
+    pub signal: S,
+    phase: Phase,
+    handled: bool,
+}
+
+impl<S> Propagating<S> {
+    pub fn new(signal: S) -> Self {
+        Self::new_with_phase(signal, Phase::Bubble)
+    }
+
+    pub fn new_with_phase(signal: S, phase: Phase) -> Self {
+        Self {
+            signal,
+            phase,
+            handled: false,
+        }
+    }
+
+    pub fn get_phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    pub fn set_handled(&mut self) {
+        self.handled = true;
+    }
+}
+
+pub trait Handled {
+    fn is_handled(&self) -> bool;
+}
+
+impl<S> Handled for S {
+    default fn is_handled(&self) -> bool {
+        false
+    }
+}
+
+impl<S> Handled for Propagating<S> {
+    fn is_handled(&self) -> bool {
+        self.handled
+    }
+}
+
+// The normalized pointer position a signal carries, if any.
+pub trait PointerPosition {
+    fn get_norm_position(&self) -> Option<[f32; 2]>;
+    fn set_norm_position(&mut self, position: [f32; 2]);
+}
+
+impl<S> PointerPosition for S {
+    default fn get_norm_position(&self) -> Option<[f32; 2]> {
+        None
+    }
+
+    default fn set_norm_position(&mut self, _position: [f32; 2]) {}
+}
+
+impl<S: PointerPosition> PointerPosition for Propagating<S> {
+    fn get_norm_position(&self) -> Option<[f32; 2]> {
+        self.signal.get_norm_position()
+    }
+
+    fn set_norm_position(&mut self, position: [f32; 2]) {
+        self.signal.set_norm_position(position);
+    }
+}
+
 pub struct ResizedSignal {
     pub resxp: u32,
     pub resyp: u32,
@@ -79,6 +189,14 @@ pub struct ResizedSignal {
     pub resy: f32,
 }
 
+// physical pixels per density independent pixel, sent when the window moves to another display
+#[derive(Debug, Clone, Copy)]
+pub struct ScaleFactorSignal {
+    pub scale_factor: f32,
+}
+
+// Containers that map positions into their child's space, like Transform, only map the
+// normalized position.
 #[derive(Debug, Clone, Copy)]
 pub struct CursorMovedSignal {
     pub pixel_posx: f32,
@@ -87,18 +205,255 @@ pub struct CursorMovedSignal {
     pub norm_posy: f32,
 }
 
+impl CursorMovedSignal {
+    pub const OCCLUDED: Self = Self {
+        pixel_posx: f32::NAN,
+        pixel_posy: f32::NAN,
+        norm_posx: f32::NAN,
+        norm_posy: f32::NAN,
+    };
+}
+
+impl PointerPosition for CursorMovedSignal {
+    fn get_norm_position(&self) -> Option<[f32; 2]> {
+        Some([self.norm_posx, self.norm_posy])
+    }
+
+    fn set_norm_position(&mut self, position: [f32; 2]) {
+        self.norm_posx = position[0];
+        self.norm_posy = position[1];
+    }
+}
+
 pub struct MouseLeftDownSignal();
 pub struct MouseLeftUpSignal();
+// Sent by a PointerRouter along the path that took the previous left release once a release
+// lands somewhere else, so widgets that only see routed releases can still lose focus.
+pub struct ReleasedElsewhereSignal();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Back,
+    Forward,
+    Other(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonState {
+    Pressed,
+    Released,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MouseButtonSignal {
+    pub button: MouseButton,
+    pub state: ButtonState,
+    pub timestamp: Option<Duration>,
+}
+
+impl MouseButtonSignal {
+    pub const fn new(button: MouseButton, state: ButtonState) -> Self {
+        Self {
+            button,
+            state,
+            timestamp: None,
+        }
+    }
+
+    pub const fn new_with_timestamp(button: MouseButton, state: ButtonState, timestamp: Duration) -> Self {
+        Self {
+            button,
+            state,
+            timestamp: Some(timestamp),
+        }
+    }
+
+    pub fn is_left(&self) -> bool {
+        self.button == MouseButton::Left
+    }
+}
+
+impl From<MouseLeftDownSignal> for MouseButtonSignal {
+    fn from(_signal: MouseLeftDownSignal) -> Self {
+        Self::new(MouseButton::Left, ButtonState::Pressed)
+    }
+}
+
+impl From<MouseLeftUpSignal> for MouseButtonSignal {
+    fn from(_signal: MouseLeftUpSignal) -> Self {
+        Self::new(MouseButton::Left, ButtonState::Released)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClickEvent {
+    DoubleClick,
+    TripleClick,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClickSignal {
+    pub event: ClickEvent,
+    pub button: MouseButton,
+    pub norm_posx: f32,
+    pub norm_posy: f32,
+}
+
+impl PointerPosition for ClickSignal {
+    fn get_norm_position(&self) -> Option<[f32; 2]> {
+        Some([self.norm_posx, self.norm_posy])
+    }
+
+    fn set_norm_position(&mut self, position: [f32; 2]) {
+        self.norm_posx = position[0];
+        self.norm_posy = position[1];
+    }
+}
+
+// Sent once per frame. now is the time since an arbitrary start, dt the time since the last tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickSignal {
+    pub dt: Duration,
+    pub now: Duration,
+}
 
 pub struct ScrollSignal {
     pub px: f32,
     pub py: f32,
 }
 
+// a touch pan that let go while moving, in pixels per second
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlingSignal {
+    pub vx: f32,
+    pub vy: f32,
+}
+
+// scale is the distance between two touches relative to where they started
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PinchSignal {
+    pub scale: f32,
+    pub norm_centerx: f32,
+    pub norm_centery: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchSignal {
+    pub id: u64,
+    pub phase: TouchPhase,
+    pub pixel_posx: f32,
+    pub pixel_posy: f32,
+    pub norm_posx: f32,
+    pub norm_posy: f32,
+    pub timestamp: Duration,
+}
+
+impl TouchSignal {
+    pub fn to_cursor_moved(&self) -> CursorMovedSignal {
+        CursorMovedSignal {
+            pixel_posx: self.pixel_posx,
+            pixel_posy: self.pixel_posy,
+            norm_posx: self.norm_posx,
+            norm_posy: self.norm_posy,
+        }
+    }
+}
+
+impl PointerPosition for TouchSignal {
+    fn get_norm_position(&self) -> Option<[f32; 2]> {
+        Some([self.norm_posx, self.norm_posy])
+    }
+
+    fn set_norm_position(&mut self, position: [f32; 2]) {
+        self.norm_posx = position[0];
+        self.norm_posy = position[1];
+    }
+}
+
 pub struct CharacterInputSignal {
     pub input: char,
 }
 
+// keys that are not text, text arrives as CharacterInputSignal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Tab,
+    Enter,
+    Escape,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self {
+        shift: false,
+        ctrl: false,
+        alt: false,
+        logo: false,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeySignal {
+    pub key: Key,
+    pub state: ButtonState,
+    pub modifiers: Modifiers,
+}
+
+impl KeySignal {
+    pub const fn new(key: Key, state: ButtonState, modifiers: Modifiers) -> Self {
+        Self {
+            key,
+            state,
+            modifiers,
+        }
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.state == ButtonState::Pressed
+    }
+}
+
+// cursor ranges are byte offsets into the preedit text
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImeSignal {
+    PreeditStart,
+    PreeditUpdate {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    Commit(String),
+    PreeditEnd,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct ReconstructCallback<T> {
     reconstruct: bool,
@@ -143,7 +498,7 @@ pub struct RedrawCallback {
 }
 
 impl RedrawCallback {
-    pub fn new(redraw: bool) -> Self {
+    pub const fn new(redraw: bool) -> Self {
         Self {
             redraw,
         }
@@ -158,4 +513,24 @@ impl RedrawCallback {
     pub fn get_redraw(&self) -> bool {
         self.redraw
     }
+}
+
+impl Default for RedrawCallback {
+    fn default() -> Self {
+        RedrawCallback::new(false)
+    }
+}
+
+impl ShortCircuitingCallback for RedrawCallback {
+    fn or_into(mut self, other: Self) -> Self {
+        self.or(other);
+        self
+    }
+}
+
+// a frame that has to be redrawn has to be constructed again
+impl<T> From<RedrawCallback> for ReconstructCallback<T> {
+    fn from(redraw_callback: RedrawCallback) -> Self {
+        ReconstructCallback::new(redraw_callback.get_redraw())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,392 @@
+use bui::{line::LineRaw, rect::{SizeAndCenter, Points}};
+
+use crate::{construct::{Construct, StandardConstructTarget}, containers::{Init, Fill, FillWidth, TranslateX, TranslateY, GetPointBounds}, signal::SignalReciever, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, button::PressState};
+
+// linear rgba
+pub type Color = [f32; 4];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    pub color: Color,
+    // in pixels
+    pub width: f32,
+}
+
+impl Stroke {
+    pub const fn new(color: Color, width: f32) -> Self {
+        Self {
+            color,
+            width,
+        }
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.color[3] *= alpha;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PressStyles {
+    pub none: Stroke,
+    pub hovered: Stroke,
+    pub pressed: Stroke,
+}
+
+impl PressStyles {
+    pub fn for_state(&self, press_state: PressState) -> Stroke {
+        match press_state {
+            PressState::None => self.none,
+            PressState::Hovered => self.hovered,
+            PressState::Pressed => self.pressed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusStyles {
+    pub unfocused: Stroke,
+    pub focused: Stroke,
+}
+
+impl FocusStyles {
+    pub fn for_focus(&self, focused: bool) -> Stroke {
+        if focused {
+            self.focused
+        } else {
+            self.unfocused
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub text: Stroke,
+    pub selection: Stroke,
+    // applied to the content of a button, replacing the text stroke inside it
+    pub button: PressStyles,
+    pub text_input_border: FocusStyles,
+    pub preedit_underline: Stroke,
+    // multiplies the alpha of disabled widgets
+    pub disabled_alpha: f32,
+}
+
+impl Theme {
+    pub const DARK: Self = Self {
+        text: Stroke::new([0.9, 0.9, 0.9, 1.0], 1.5),
+        selection: Stroke::new([0.3, 0.5, 0.9, 1.0], 1.5),
+        button: PressStyles {
+            none: Stroke::new([0.9, 0.9, 0.9, 1.0], 1.5),
+            hovered: Stroke::new([1.0, 1.0, 1.0, 1.0], 2.0),
+            pressed: Stroke::new([0.6, 0.7, 1.0, 1.0], 2.0),
+        },
+        text_input_border: FocusStyles {
+            unfocused: Stroke::new([0.5, 0.5, 0.5, 1.0], 1.0),
+            focused: Stroke::new([0.3, 0.5, 0.9, 1.0], 2.0),
+        },
+        preedit_underline: Stroke::new([0.9, 0.9, 0.9, 1.0], 1.0),
+        disabled_alpha: 0.4,
+    };
+
+    pub const LIGHT: Self = Self {
+        text: Stroke::new([0.1, 0.1, 0.1, 1.0], 1.5),
+        selection: Stroke::new([0.2, 0.4, 0.8, 1.0], 1.5),
+        button: PressStyles {
+            none: Stroke::new([0.1, 0.1, 0.1, 1.0], 1.5),
+            hovered: Stroke::new([0.0, 0.0, 0.0, 1.0], 2.0),
+            pressed: Stroke::new([0.2, 0.4, 0.8, 1.0], 2.0),
+        },
+        text_input_border: FocusStyles {
+            unfocused: Stroke::new([0.6, 0.6, 0.6, 1.0], 1.0),
+            focused: Stroke::new([0.2, 0.4, 0.8, 1.0], 2.0),
+        },
+        preedit_underline: Stroke::new([0.1, 0.1, 0.1, 1.0], 1.0),
+        disabled_alpha: 0.4,
+    };
+
+    pub fn with_text(mut self, text: Stroke) -> Self {
+        self.text = text;
+        self
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StyledLine {
+    pub line: LineRaw,
+    pub stroke: Stroke,
+}
+
+#[derive(Debug, Clone)]
+pub struct StyledLineTarget (pub Vec<StyledLine>);
+
+impl StyledLineTarget {
+    pub fn from_lines(lines: &[LineRaw], stroke: Stroke) -> Self {
+        Self(lines.iter().map(|line| StyledLine {
+            line: *line,
+            stroke,
+        }).collect())
+    }
+
+    pub fn push_line(&mut self, line: LineRaw, stroke: Stroke) {
+        self.0.push(StyledLine {
+            line,
+            stroke,
+        });
+    }
+}
+
+impl StandardConstructTarget for StyledLineTarget {
+    const EMPTY: Self = StyledLineTarget(vec![]);
+
+    fn append(&mut self, mut value: Self) {
+        self.0.append(&mut value.0);
+    }
+
+    fn append_into(mut self, mut value: Self) -> Self {
+        self.0.append(&mut value.0);
+        self
+    }
+}
+
+impl Default for StyledLineTarget {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+// Construct targets that carry colors and can be made more transparent.
+pub trait FadeTarget {
+    // false when the target has no colors to change
+    fn can_fade(&self) -> bool;
+    // multiplies the alpha of every color
+    fn fade(&mut self, alpha: f32);
+}
+
+impl<C> FadeTarget for C {
+    default fn can_fade(&self) -> bool {
+        false
+    }
+
+    default fn fade(&mut self, _alpha: f32) {}
+}
+
+impl FadeTarget for StyledLineTarget {
+    fn can_fade(&self) -> bool {
+        true
+    }
+
+    fn fade(&mut self, alpha: f32) {
+        for styled_line in &mut self.0 {
+            styled_line.stroke = styled_line.stroke.with_alpha(alpha);
+        }
+    }
+}
+
+// Like Construct, with the theme of the enclosing subtree handed down by every container.
+pub trait ConstructStyled<C> {
+    fn construct_styled(&self, theme: &Theme) -> C;
+}
+
+// Replaces the theme for everything below it.
+#[derive(Debug, Clone)]
+pub struct Themed<T> {
+    child: T,
+    theme: Theme,
+}
+
+impl<T> Themed<T> {
+    pub fn new(child: T, theme: Theme) -> Self {
+        Self {
+            child,
+            theme,
+        }
+    }
+
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+}
+
+impl<C, T: ConstructStyled<C>> ConstructStyled<C> for Themed<T> {
+    fn construct_styled(&self, _theme: &Theme) -> C {
+        self.child.construct_styled(&self.theme)
+    }
+}
+
+impl<T: Init> Init for Themed<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
+}
+
+impl<T: Fill> Fill for Themed<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.child.fill(fill_target);
+    }
+}
+
+impl<T: FillWidth> FillWidth for Themed<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        self.child.fill_width(sx, cx, ty)
+    }
+}
+
+impl<T: TranslateX> TranslateX for Themed<T> {
+    fn translate_x(&mut self, dx: f32) {
+        self.child.translate_x(dx);
+    }
+}
+
+impl<T: TranslateY> TranslateY for Themed<T> {
+    fn translate_y(&mut self, dy: f32) {
+        self.child.translate_y(dy);
+    }
+}
+
+impl<T: GetPointBounds> GetPointBounds for Themed<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.child.get_point_bounds()
+    }
+}
+
+impl<T: Measure> Measure for Themed<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.child.measure(constraints)
+    }
+}
+
+impl<T: Dirty> Dirty for Themed<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.child.update_layout()
+    }
+
+    fn clear_dirty(&mut self) {
+        self.child.clear_dirty();
+    }
+}
+
+impl<C, T: Construct<C>> Construct<C> for Themed<T> {
+    fn construct(&self) -> C {
+        self.child.construct()
+    }
+}
+
+impl<C: IntoPrimitives, T: RetainedConstruct<C>> RetainedConstruct<C> for Themed<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.child.construct_retained(target);
+    }
+}
+
+impl<S, R, T: SignalReciever<S, R>> SignalReciever<S, R> for Themed<T> {
+    fn take_signal(&mut self, signal: &mut S) -> R {
+        self.child.take_signal(signal)
+    }
+}
+
+impl<T: HitTest> HitTest for Themed<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.child.hit_test(x, y, path)
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for Themed<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.child.route_signal(path, signal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::containers::VStackContainer;
+
+    const LINE: LineRaw = LineRaw {
+        p1: [0.0, 0.0],
+        p2: [1.0, 0.0],
+    };
+
+    // draws one line in the text stroke of the theme it is handed
+    struct Label;
+
+    impl ConstructStyled<StyledLineTarget> for Label {
+        fn construct_styled(&self, theme: &Theme) -> StyledLineTarget {
+            StyledLineTarget::from_lines(&[LINE], theme.text)
+        }
+    }
+
+    fn strokes(target: &StyledLineTarget) -> Vec<Stroke> {
+        target.0.iter().map(|styled_line| styled_line.stroke).collect()
+    }
+
+    #[test]
+    fn themed_replaces_the_theme_of_its_subtree_only() {
+        let stack = VStackContainer::new(Label, Themed::new(Label, Theme::LIGHT));
+        let target: StyledLineTarget = stack.construct_styled(&Theme::DARK);
+        assert_eq!(strokes(&target), vec![Theme::DARK.text, Theme::LIGHT.text]);
+    }
+
+    #[test]
+    fn innermost_themed_wins() {
+        let red = Stroke::new([1.0, 0.0, 0.0, 1.0], 3.0);
+        let themed = Themed::new(Themed::new(Label, Theme::LIGHT.with_text(red)), Theme::DARK);
+        let target: StyledLineTarget = themed.construct_styled(&Theme::default());
+        assert_eq!(strokes(&target), vec![red]);
+    }
+
+    #[test]
+    fn press_styles_pick_the_stroke_of_the_state() {
+        let styles = Theme::DARK.button;
+        assert_eq!(styles.for_state(PressState::None), styles.none);
+        assert_eq!(styles.for_state(PressState::Hovered), styles.hovered);
+        assert_eq!(styles.for_state(PressState::Pressed), styles.pressed);
+    }
+
+    #[test]
+    fn focus_styles_pick_the_stroke_of_the_focus() {
+        let styles = Theme::LIGHT.text_input_border;
+        assert_eq!(styles.for_focus(true), styles.focused);
+        assert_eq!(styles.for_focus(false), styles.unfocused);
+    }
+
+    #[test]
+    fn fading_multiplies_only_the_alpha() {
+        let mut target = StyledLineTarget::from_lines(&[LINE, LINE], Stroke::new([0.2, 0.4, 0.6, 0.8], 2.0));
+        assert!(target.can_fade());
+        target.fade(0.5);
+        for stroke in strokes(&target) {
+            assert_eq!(stroke.color[..3], [0.2, 0.4, 0.6]);
+            assert!((stroke.color[3]-0.4).abs() < 1e-5);
+            assert_eq!(stroke.width, 2.0);
+        }
+        target.fade(0.0);
+        assert!(strokes(&target).iter().all(|stroke| stroke.color[3] == 0.0));
+    }
+
+    #[test]
+    fn targets_without_colors_cannot_fade() {
+        let target = crate::construct::LineTarget(vec![LINE]);
+        assert!(!target.can_fade());
+    }
+}
\ No newline at end of file
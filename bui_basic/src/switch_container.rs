@@ -1,11 +1,120 @@
-use std::{ops::IndexMut, marker::PhantomData};
+use std::{ops::IndexMut, marker::PhantomData, time::Duration};
 
-use crate::{containers::{Init, FillWidth, TranslateX, TranslateY}, construct::Construct, signal::SignalReciever};
+use crate::{containers::{Init, FillWidth, TranslateX, TranslateY}, construct::{Construct, StandardConstructTarget}, signal::{SignalReciever, ShortCircuitingCallback, ReconstructCallback, RedrawCallback, TickSignal}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme, FadeTarget}, transform::{Affine, TransformTarget}, animation::{Animated, Easing}, button::ClickedCallback};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwitchTransition {
+    None,
+    // The old page moves out by (dx, dy) in normalized units while the new one moves in from the opposite side.
+    // Only drawn by construct targets that can be transformed.
+    Slide {
+        dx: f32,
+        dy: f32,
+        duration: Duration,
+        easing: Easing,
+    },
+    // The old page fades out while the new one fades in.
+    // Targets without colors show the old page for the first half and the new one for the second.
+    Fade {
+        duration: Duration,
+        easing: Easing,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwitchCallback<A> {
+    NoChange,
+    Switched {
+        from: A,
+        to: A,
+    },
+}
+
+impl<A> SwitchCallback<A> {
+    pub fn is_switched(&self) -> bool {
+        match self {
+            SwitchCallback::NoChange => false,
+            SwitchCallback::Switched { .. } => true
+        }
+    }
+}
+
+impl<A, C> From<SwitchCallback<A>> for ReconstructCallback<C> {
+    fn from(switch_callback: SwitchCallback<A>) -> Self {
+        ReconstructCallback::new(switch_callback.is_switched())
+    }
+}
+
+impl<A> From<SwitchCallback<A>> for RedrawCallback {
+    fn from(switch_callback: SwitchCallback<A>) -> Self {
+        RedrawCallback::new(switch_callback.is_switched())
+    }
+}
+
+// Whether the data has a page at an index, so switching can refuse one that would panic later.
+// Data that can't tell accepts every index.
+pub trait HasPage<A> {
+    fn has_page(&self, page: A) -> bool;
+}
+
+impl<A, D: ?Sized> HasPage<A> for D {
+    default fn has_page(&self, _page: A) -> bool {
+        true
+    }
+}
+
+impl<T> HasPage<usize> for Vec<T> {
+    fn has_page(&self, page: usize) -> bool {
+        page < self.len()
+    }
+}
+
+impl<T, const N: usize> HasPage<usize> for [T; N] {
+    fn has_page(&self, page: usize) -> bool {
+        page < N
+    }
+}
+
+// pages that can't be filled by width are left where they are
+trait LayoutPage {
+    fn layout_page(&mut self, sx: f32, cx: f32, ty: f32) -> Option<f32>;
+}
+
+impl<T: ?Sized> LayoutPage for T {
+    default fn layout_page(&mut self, _sx: f32, _cx: f32, _ty: f32) -> Option<f32> {
+        None
+    }
+}
+
+impl<T: ?Sized+FillWidth> LayoutPage for T {
+    fn layout_page(&mut self, sx: f32, cx: f32, ty: f32) -> Option<f32> {
+        Some(self.fill_width(sx, cx, ty))
+    }
+}
+
+// where the two pages are drawn at one point of a transition
+struct TransitionFrame<A> {
+    previous: A,
+    previous_offset: Affine,
+    active_offset: Affine,
+    // how far the fade is, None when not fading
+    fade: Option<f32>,
+}
 
 pub struct SwitchContainer<A: Copy, T: ?Sized, D: IndexMut<A, Output=T>> {
     active: A,
     data: D,
     active_data: PhantomData<T>,
+    transition: SwitchTransition,
+    // the page being switched away from while the transition runs
+    previous: Option<A>,
+    backward: bool,
+    progress: Animated<f32>,
+    width_target: Option<(f32, f32, f32)>,
+    // bottom of the active page from the last layout
+    by: f32,
+    // how much taller the active page got through switches since the last update_layout
+    height_change: f32,
 }
 
 impl<A: Copy, T: ?Sized, D: IndexMut<A, Output=T>> SwitchContainer<A, T, D> {
@@ -14,8 +123,189 @@ impl<A: Copy, T: ?Sized, D: IndexMut<A, Output=T>> SwitchContainer<A, T, D> {
             active,
             data,
             active_data: PhantomData,
+            transition: SwitchTransition::None,
+            previous: None,
+            backward: false,
+            progress: Animated::new(1.0),
+            width_target: None,
+            by: 0.0,
+            height_change: 0.0,
+        }
+    }
+
+    pub fn with_transition(mut self, transition: SwitchTransition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    pub fn set_transition(&mut self, transition: SwitchTransition) {
+        self.transition = transition;
+    }
+
+    pub fn get_active(&self) -> A {
+        self.active
+    }
+
+    pub fn get_active_data(&self) -> &T {
+        &self.data[self.active]
+    }
+
+    pub fn get_active_data_mut(&mut self) -> &mut T {
+        &mut self.data[self.active]
+    }
+
+    // Pages can be added or removed through this. After removing one call jump_to,
+    // so neither the active nor a transitioning page points past the end.
+    pub fn get_data(&self) -> &D {
+        &self.data
+    }
+
+    pub fn get_data_mut(&mut self) -> &mut D {
+        &mut self.data
+    }
+
+    // switches without a transition, ending one that is running
+    pub fn jump_to(&mut self, active: A)
+    where
+        D: HasPage<A>
+    {
+        assert!(self.data.has_page(active), "switched to a page that does not exist");
+        self.previous = None;
+        self.progress.set(1.0);
+        self.active = active;
+        self.layout_active();
+    }
+
+    // The new page is laid out like the last fill_width call, if there was one.
+    // Panics if the data has no page at active.
+    pub fn set_active(&mut self, active: A) -> SwitchCallback<A>
+    where
+        A: PartialEq,
+        D: HasPage<A>
+    {
+        self.switch(active, false)
+    }
+
+    // like set_active with the slide going the other way
+    pub fn set_active_backward(&mut self, active: A) -> SwitchCallback<A>
+    where
+        A: PartialEq,
+        D: HasPage<A>
+    {
+        self.switch(active, true)
+    }
+
+    // switches when a button reports a click, for wiring a button's callback straight to a page
+    pub fn set_active_on_click(&mut self, clicked: ClickedCallback, active: A) -> SwitchCallback<A>
+    where
+        A: PartialEq,
+        D: HasPage<A>
+    {
+        match clicked {
+            ClickedCallback::Clicked => self.set_active(active),
+            ClickedCallback::NoClick => SwitchCallback::NoChange
+        }
+    }
+
+    fn switch(&mut self, active: A, backward: bool) -> SwitchCallback<A>
+    where
+        A: PartialEq,
+        D: HasPage<A>
+    {
+        assert!(self.data.has_page(active), "switched to a page that does not exist");
+        if active == self.active {
+            return SwitchCallback::NoChange;
+        }
+        match self.transition {
+            SwitchTransition::None => self.previous = None,
+            SwitchTransition::Slide { duration, easing, .. } | SwitchTransition::Fade { duration, easing } => {
+                self.previous = Some(self.active);
+                self.backward = backward;
+                self.progress.set(0.0);
+                self.progress.animate_to(1.0, duration, easing);
+            }
+        }
+        let from = self.active;
+        self.active = active;
+        self.layout_active();
+        SwitchCallback::Switched {
+            from,
+            to: active,
         }
     }
+
+    pub fn is_transitioning(&self) -> bool {
+        self.previous.is_some()
+    }
+
+    // lays the active page out like the last fill_width call, returns its bottom
+    pub fn layout_active(&mut self) -> Option<f32> {
+        let (sx, cx, ty) = self.width_target?;
+        let by = self.data[self.active].layout_page(sx, cx, ty)?;
+        self.height_change += self.by-by;
+        self.by = by;
+        Some(by)
+    }
+
+    fn transition_frame(&self) -> Option<TransitionFrame<A>> {
+        let previous = self.previous?;
+        let t = self.progress.get();
+        match self.transition {
+            SwitchTransition::None => None,
+            SwitchTransition::Slide { dx, dy, .. } => {
+                let sign = if self.backward {
+                    -1.0
+                } else {
+                    1.0
+                };
+                Some(TransitionFrame {
+                    previous,
+                    previous_offset: Affine::translate(-dx*t*sign, -dy*t*sign),
+                    active_offset: Affine::translate(dx*(1.0-t)*sign, dy*(1.0-t)*sign),
+                    fade: None,
+                })
+            },
+            SwitchTransition::Fade { .. } => Some(TransitionFrame {
+                previous,
+                previous_offset: Affine::IDENTITY,
+                active_offset: Affine::IDENTITY,
+                fade: Some(t),
+            })
+        }
+    }
+
+    // both pages during a transition, the active one otherwise
+    fn construct_transition<C: StandardConstructTarget+TransformTarget+FadeTarget>(&self, construct: impl Fn(&T) -> C) -> C {
+        let frame = match self.transition_frame() {
+            Some(frame) => frame,
+            None => return construct(&self.data[self.active])
+        };
+
+        let mut previous_target = construct(&self.data[frame.previous]);
+        let mut active_target = construct(&self.data[self.active]);
+        if let Some(t) = frame.fade {
+            if !(previous_target.can_fade() && active_target.can_fade()) {
+                return if t < 0.5 {
+                    previous_target
+                } else {
+                    active_target
+                };
+            }
+            previous_target.fade(1.0-t);
+            active_target.fade(t);
+        }
+        previous_target.transform(&frame.previous_offset);
+        active_target.transform(&frame.active_offset);
+        previous_target.append_into(active_target)
+    }
+
+    fn tick_transition(&mut self, dt: Duration) -> bool {
+        let animating = self.progress.tick(dt);
+        if !self.progress.is_animating() {
+            self.previous = None;
+        }
+        animating
+    }
 }
 
 impl<A: Copy, T: ?Sized+Init, D: IndexMut<A, Output=T>> Init for SwitchContainer<A, T, D> {
@@ -26,30 +316,318 @@ impl<A: Copy, T: ?Sized+Init, D: IndexMut<A, Output=T>> Init for SwitchContainer
 
 impl<A: Copy, T: ?Sized+FillWidth, D: IndexMut<A, Output=T>> FillWidth for SwitchContainer<A, T, D> {
     fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
-        self.data[self.active].fill_width(sx, cx, ty)
+        self.width_target = Some((sx, cx, ty));
+        self.height_change = 0.0;
+        self.by = self.data[self.active].fill_width(sx, cx, ty);
+        self.by
+    }
+}
+
+impl<A: Copy, T: ?Sized+Measure, D: IndexMut<A, Output=T>> Measure for SwitchContainer<A, T, D> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.data[self.active].measure(constraints)
+    }
+}
+
+impl<A: Copy, T: ?Sized+Dirty, D: IndexMut<A, Output=T>> Dirty for SwitchContainer<A, T, D> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.data[self.active].get_dirty().or(DirtyFlags::new(self.height_change != 0.0, false))
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let height_change = self.data[self.active].update_layout();
+        self.by -= height_change;
+        height_change+std::mem::take(&mut self.height_change)
+    }
+
+    fn clear_dirty(&mut self) {
+        self.data[self.active].clear_dirty();
     }
 }
 
 impl<A: Copy, T: ?Sized+TranslateX, D: IndexMut<A, Output=T>> TranslateX for SwitchContainer<A, T, D> {
     fn translate_x(&mut self, dx: f32) {
+        if let Some((_, cx, _)) = self.width_target.as_mut() {
+            *cx += dx;
+        }
         self.data[self.active].translate_x(dx)
     }
 }
 
 impl<A: Copy, T: ?Sized+TranslateY, D: IndexMut<A, Output=T>> TranslateY for SwitchContainer<A, T, D> {
     fn translate_y(&mut self, dy: f32) {
+        if let Some((_, _, ty)) = self.width_target.as_mut() {
+            *ty += dy;
+        }
+        self.by += dy;
         self.data[self.active].translate_y(dy)
     }
 }
 
 impl<C, A: Copy, T: ?Sized+Construct<C>, D: IndexMut<A, Output=T>> Construct<C> for SwitchContainer<A, T, D> {
-    fn construct(&self) -> C {
+    default fn construct(&self) -> C {
         self.data[self.active].construct()
     }
 }
 
+impl<C: StandardConstructTarget+TransformTarget, A: Copy, T: ?Sized+Construct<C>, D: IndexMut<A, Output=T>> Construct<C> for SwitchContainer<A, T, D> {
+    fn construct(&self) -> C {
+        self.construct_transition(|page| page.construct())
+    }
+}
+
+impl<C, A: Copy, T: ?Sized+ConstructStyled<C>, D: IndexMut<A, Output=T>> ConstructStyled<C> for SwitchContainer<A, T, D> {
+    default fn construct_styled(&self, theme: &Theme) -> C {
+        self.data[self.active].construct_styled(theme)
+    }
+}
+
+impl<C: StandardConstructTarget+TransformTarget, A: Copy, T: ?Sized+ConstructStyled<C>, D: IndexMut<A, Output=T>> ConstructStyled<C> for SwitchContainer<A, T, D> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        self.construct_transition(|page| page.construct_styled(theme))
+    }
+}
+
+impl<C: IntoPrimitives, A: Copy, T: ?Sized+RetainedConstruct<C>, D: IndexMut<A, Output=T>> RetainedConstruct<C> for SwitchContainer<A, T, D> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        self.data[self.active].construct_retained(target);
+    }
+}
+
 impl<S, O, A: Copy, T: ?Sized+SignalReciever<S, O>, D: IndexMut<A, Output=T>> SignalReciever<S, O> for SwitchContainer<A, T, D> {
-    fn take_signal(&mut self, signal: &mut S) -> O {
+    default fn take_signal(&mut self, signal: &mut S) -> O {
         self.data[self.active].take_signal(signal)
     }
+}
+
+impl<A: Copy, T: ?Sized+SignalReciever<TickSignal, RedrawCallback>, D: IndexMut<A, Output=T>> SignalReciever<TickSignal, RedrawCallback> for SwitchContainer<A, T, D> {
+    fn take_signal(&mut self, signal: &mut TickSignal) -> RedrawCallback {
+        RedrawCallback::new(self.tick_transition(signal.dt)).or_into(self.data[self.active].take_signal(signal))
+    }
+}
+
+impl<C, A: Copy, T: ?Sized+SignalReciever<TickSignal, ReconstructCallback<C>>, D: IndexMut<A, Output=T>> SignalReciever<TickSignal, ReconstructCallback<C>> for SwitchContainer<A, T, D> {
+    fn take_signal(&mut self, signal: &mut TickSignal) -> ReconstructCallback<C> {
+        ReconstructCallback::new(self.tick_transition(signal.dt)).or_into(self.data[self.active].take_signal(signal))
+    }
+}
+
+impl<A: Copy, T: ?Sized+HitTest, D: IndexMut<A, Output=T>> HitTest for SwitchContainer<A, T, D> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        self.data[self.active].hit_test(x, y, path)
+    }
+}
+
+impl<S, R, A: Copy, T: ?Sized+RouteSignal<S, R>, D: IndexMut<A, Output=T>> RouteSignal<S, R> for SwitchContainer<A, T, D> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.data[self.active].route_signal(path, signal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bui::line::LineRaw;
+
+    use super::*;
+    use crate::{test_util::Block, construct::LineTarget, style::StyledLineTarget};
+
+    fn pages() -> SwitchContainer<usize, Block, Vec<Block>> {
+        SwitchContainer::new(0, vec![Block::new(0.0, 0.5), Block::new(0.0, 0.25)])
+    }
+
+    #[test]
+    #[should_panic(expected = "switched to a page that does not exist")]
+    fn set_active_past_the_end_panics() {
+        pages().set_active(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "switched to a page that does not exist")]
+    fn jump_to_past_the_end_panics() {
+        pages().jump_to(5);
+    }
+
+    #[test]
+    fn switch_lays_out_the_new_page() {
+        let mut switch = pages();
+        assert_eq!(switch.fill_width(0.5, 0.0, 1.0), 0.5);
+
+        assert!(switch.set_active(1).is_switched());
+        let page = &switch.get_data()[1];
+        assert_eq!(page.points.p1y, 1.0);
+        assert_eq!(page.points.p2y, 0.75);
+        assert_eq!(page.points.p2x, 0.5);
+
+        // the page is shorter, the parent moves what comes after it up
+        assert!(switch.get_dirty().layout);
+        assert_eq!(switch.update_layout(), -0.25);
+        assert!(!switch.get_dirty().layout);
+    }
+
+    #[test]
+    fn switch_before_any_layout_leaves_the_page() {
+        let mut switch = pages();
+        switch.set_active(1);
+        assert_eq!(switch.get_data()[1].points.p2y, 0.0);
+        assert!(!switch.get_dirty().layout);
+    }
+
+    #[test]
+    fn layout_follows_translation() {
+        let mut switch = pages();
+        switch.fill_width(0.5, 0.0, 1.0);
+        switch.translate_y(-0.5);
+        switch.set_active(1);
+        assert_eq!(switch.get_data()[1].points.p1y, 0.5);
+        assert_eq!(switch.update_layout(), -0.25);
+    }
+
+    #[test]
+    fn same_page_is_no_change() {
+        assert_eq!(pages().set_active(0), SwitchCallback::NoChange);
+    }
+
+    // a vertical line at x
+    struct Page(f32);
+
+    impl Page {
+        fn line(&self) -> LineRaw {
+            LineRaw {
+                p1: [self.0, 0.0],
+                p2: [self.0, 1.0],
+            }
+        }
+    }
+
+    impl Construct<LineTarget> for Page {
+        fn construct(&self) -> LineTarget {
+            LineTarget(vec![self.line()])
+        }
+    }
+
+    impl ConstructStyled<StyledLineTarget> for Page {
+        fn construct_styled(&self, theme: &Theme) -> StyledLineTarget {
+            StyledLineTarget::from_lines(&[self.line()], theme.text)
+        }
+    }
+
+    fn switching(transition: SwitchTransition) -> SwitchContainer<usize, Page, Vec<Page>> {
+        let mut switch = SwitchContainer::new(0, vec![Page(0.0), Page(0.5)]).with_transition(transition);
+        assert!(switch.set_active(1).is_switched());
+        switch
+    }
+
+    fn slide() -> SwitchTransition {
+        SwitchTransition::Slide {
+            dx: 1.0,
+            dy: 0.0,
+            duration: Duration::from_millis(100),
+            easing: Easing::Linear,
+        }
+    }
+
+    fn fade() -> SwitchTransition {
+        SwitchTransition::Fade {
+            duration: Duration::from_millis(100),
+            easing: Easing::Linear,
+        }
+    }
+
+    fn xs(target: &LineTarget) -> Vec<f32> {
+        target.0.iter().map(|line| line.p1[0]).collect()
+    }
+
+    fn assert_xs(actual: Vec<f32>, expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} != {:?}", actual, expected);
+        for (a, b) in actual.iter().zip(expected) {
+            assert!((a-b).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    fn alphas(target: &StyledLineTarget) -> Vec<f32> {
+        target.0.iter().map(|styled_line| styled_line.stroke.color[3]).collect()
+    }
+
+    #[test]
+    fn slide_moves_the_old_page_out_and_the_new_one_in() {
+        let mut switch = switching(slide());
+        assert!(switch.is_transitioning());
+        assert_xs(xs(&switch.construct()), &[0.0, 1.5]);
+
+        assert!(switch.tick_transition(Duration::from_millis(50)));
+        assert_xs(xs(&switch.construct()), &[-0.5, 1.0]);
+
+        // the tick that reaches the end still asks for a redraw, then the old page is gone
+        assert!(switch.tick_transition(Duration::from_millis(50)));
+        assert!(!switch.is_transitioning());
+        assert_xs(xs(&switch.construct()), &[0.5]);
+        assert!(!switch.tick_transition(Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn backward_slide_goes_the_other_way() {
+        let mut switch = SwitchContainer::new(1, vec![Page(0.0), Page(0.5)]).with_transition(slide());
+        assert!(switch.set_active_backward(0).is_switched());
+        switch.tick_transition(Duration::from_millis(25));
+        assert_xs(xs(&switch.construct()), &[0.75, -0.75]);
+    }
+
+    #[test]
+    fn jump_to_ends_a_running_transition() {
+        let mut switch = switching(slide());
+        switch.tick_transition(Duration::from_millis(50));
+        switch.jump_to(0);
+        assert!(!switch.is_transitioning());
+        assert_xs(xs(&switch.construct()), &[0.0]);
+        assert!(!switch.tick_transition(Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn fade_without_colors_shows_one_page_at_a_time() {
+        let mut switch = switching(fade());
+        assert_xs(xs(&switch.construct()), &[0.0]);
+
+        switch.tick_transition(Duration::from_millis(40));
+        assert_xs(xs(&switch.construct()), &[0.0]);
+
+        switch.tick_transition(Duration::from_millis(20));
+        assert!(switch.is_transitioning());
+        assert_xs(xs(&switch.construct()), &[0.5]);
+
+        switch.tick_transition(Duration::from_millis(40));
+        assert!(!switch.is_transitioning());
+        assert_xs(xs(&switch.construct()), &[0.5]);
+    }
+
+    #[test]
+    fn fade_crossfades_styled_lines() {
+        let mut switch = switching(fade());
+        switch.tick_transition(Duration::from_millis(25));
+        let target: StyledLineTarget = switch.construct_styled(&Theme::DARK);
+        assert_xs(target.0.iter().map(|styled_line| styled_line.line.p1[0]).collect(), &[0.0, 0.5]);
+        assert_xs(alphas(&target), &[0.75, 0.25]);
+
+        switch.tick_transition(Duration::from_millis(75));
+        assert!(!switch.is_transitioning());
+        let target: StyledLineTarget = switch.construct_styled(&Theme::DARK);
+        assert_xs(target.0.iter().map(|styled_line| styled_line.line.p1[0]).collect(), &[0.5]);
+        assert_xs(alphas(&target), &[1.0]);
+    }
+
+    #[test]
+    fn slide_moves_styled_lines_without_fading_them() {
+        let mut switch = switching(slide());
+        switch.tick_transition(Duration::from_millis(50));
+        let target: StyledLineTarget = switch.construct_styled(&Theme::DARK);
+        assert_xs(target.0.iter().map(|styled_line| styled_line.line.p1[0]).collect(), &[-0.5, 1.0]);
+        assert_xs(alphas(&target), &[1.0, 1.0]);
+    }
+
+    #[test]
+    fn no_transition_switches_at_once() {
+        let mut switch = switching(SwitchTransition::None);
+        assert!(!switch.is_transitioning());
+        assert_xs(xs(&switch.construct()), &[0.5]);
+        assert!(!switch.tick_transition(Duration::from_millis(16)));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,948 @@
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::{Construct, StandardConstructTarget, LineTarget, CapsuleFill}, containers::{Init, Fill, TranslateX, TranslateY, GetPointBounds, Padding, Insets}, switch_container::{SwitchContainer, SwitchTransition, SwitchCallback}, button::{Button, ButtonFrame, FrameStyle, PressStateCallback, ClickedCallback}, text::Text, signal::{SignalReciever, ShortCircuitingCallback, ReconstructCallback, RedrawCallback, ResizedSignal, ScaleFactorSignal, CursorMovedSignal, MouseLeftDownSignal, MouseLeftUpSignal, MouseButtonSignal, MouseButton, ButtonState, TickSignal, KeySignal, Key}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, Size, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, IntoPrimitives}, style::{ConstructStyled, Theme}, units::Length};
+
+// the label is usually Text, anything that can be measured and filled works
+pub type TabButton<L = Text> = Button<Padding<L>>;
+
+struct Tab<L> {
+    label: TabButton<L>,
+    close: Option<TabButton<L>>,
+}
+
+impl<L> Tab<L> {
+    // room between the label and the tab, vertically a share of the bar height
+    const LABEL_INSETS: Insets = Insets::symmetric(Length::Dp(8.0), Length::Fraction(0.2));
+
+    fn new_button(label: L, frame: ButtonFrame) -> TabButton<L> {
+        let fill_area = SizeAndCenter {
+            sx: 0.0,
+            sy: 0.0,
+            cx: 0.0,
+            cy: 0.0,
+        };
+        Button::new_with_frame(Padding::new(label, Self::LABEL_INSETS), fill_area, frame)
+    }
+
+    fn buttons(&self) -> impl Iterator<Item=&TabButton<L>> {
+        std::iter::once(&self.label).chain(&self.close)
+    }
+
+    fn buttons_mut(&mut self) -> impl Iterator<Item=&mut TabButton<L>> {
+        std::iter::once(&mut self.label).chain(&mut self.close)
+    }
+
+    // the label at its preferred width, followed by a square close button
+    fn get_width(&self, bar_height: f32) -> f32
+    where
+        L: Measure
+    {
+        let label_width = self.label.measure(Constraints::new(f32::INFINITY, bar_height)).preferred.width;
+        match self.close {
+            Some(_) => label_width+bar_height,
+            None => label_width
+        }
+    }
+
+    fn fill(&mut self, left: f32, width: f32, top: f32, bar_height: f32)
+    where
+        L: Fill
+    {
+        let close_width = match self.close {
+            Some(_) => bar_height.min(width),
+            None => 0.0
+        };
+        let label_width = width-close_width;
+        self.label.fill(SizeAndCenter {
+            sx: label_width/2.0,
+            sy: bar_height/2.0,
+            cx: left+label_width/2.0,
+            cy: top-bar_height/2.0,
+        });
+        if let Some(close) = self.close.as_mut() {
+            close.fill(SizeAndCenter {
+                sx: close_width/2.0,
+                sy: bar_height/2.0,
+                cx: left+label_width+close_width/2.0,
+                cy: top-bar_height/2.0,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TabChangedCallback {
+    NoChange,
+    Changed {
+        from: usize,
+        to: usize,
+    },
+    // The page was dropped and the tabs after it moved down by one. active is the tab shown now.
+    Closed {
+        index: usize,
+        active: usize,
+    },
+}
+
+impl TabChangedCallback {
+    pub fn is_changed(&self) -> bool {
+        !matches!(self, TabChangedCallback::NoChange)
+    }
+}
+
+impl From<SwitchCallback<usize>> for TabChangedCallback {
+    fn from(switch_callback: SwitchCallback<usize>) -> Self {
+        match switch_callback {
+            SwitchCallback::NoChange => TabChangedCallback::NoChange,
+            SwitchCallback::Switched { from, to } => TabChangedCallback::Changed {
+                from,
+                to,
+            }
+        }
+    }
+}
+
+impl<C> From<TabChangedCallback> for ReconstructCallback<C> {
+    fn from(tab_changed: TabChangedCallback) -> Self {
+        ReconstructCallback::new(tab_changed.is_changed())
+    }
+}
+
+impl From<TabChangedCallback> for RedrawCallback {
+    fn from(tab_changed: TabChangedCallback) -> Self {
+        RedrawCallback::new(tab_changed.is_changed())
+    }
+}
+
+// A row of labelled tabs over the page of the active one.
+// Ctrl+Tab and Ctrl+Shift+Tab move to the next and previous tab, the page does not get the presses
+// that switched. There is always at least one tab, so the last one cannot be closed.
+pub struct TabView<T, L = Text> {
+    tabs: Vec<Tab<L>>,
+    pages: SwitchContainer<usize, T, Vec<T>>,
+    // full height of the tab bar in normalized units
+    tab_height: f32,
+    frame: ButtonFrame,
+    active_frame: ButtonFrame,
+    close_frame: ButtonFrame,
+    fill_area: SizeAndCenter,
+    bar_points: Points,
+    // tabs were added, removed or renamed since the last layout
+    layout_dirty: bool,
+}
+
+impl<T, L> TabView<T, L> {
+    pub const DEFAULT_TAB_HEIGHT: f32 = 0.1;
+
+    // the background stays filled while the tab is active
+    pub const ACTIVE_FRAME: ButtonFrame = ButtonFrame {
+        none: FrameStyle {
+            inset: 0.0,
+            border: true,
+            background: Some(CapsuleFill::solid(0.004)),
+        },
+        hovered: FrameStyle {
+            inset: 0.0,
+            border: true,
+            background: Some(CapsuleFill::solid(0.004)),
+        },
+        pressed: ButtonFrame::DEFAULT.pressed,
+        disabled: ButtonFrame::DEFAULT.disabled,
+    };
+
+    // only drawn while the pointer is over it
+    pub const CLOSE_FRAME: ButtonFrame = ButtonFrame {
+        none: FrameStyle::NONE,
+        hovered: ButtonFrame::DEFAULT.hovered,
+        pressed: ButtonFrame::DEFAULT.pressed,
+        disabled: FrameStyle::NONE,
+    };
+
+    pub fn new(label: L, page: T, fill_area: SizeAndCenter) -> Self {
+        Self {
+            tabs: vec![Tab {
+                label: Tab::new_button(label, Self::ACTIVE_FRAME),
+                close: None,
+            }],
+            pages: SwitchContainer::new(0, vec![page]),
+            tab_height: Self::DEFAULT_TAB_HEIGHT,
+            frame: ButtonFrame::DEFAULT,
+            active_frame: Self::ACTIVE_FRAME,
+            close_frame: Self::CLOSE_FRAME,
+            fill_area,
+            bar_points: Points::ZERO,
+            layout_dirty: true,
+        }
+    }
+
+    pub fn with_tab_height(mut self, tab_height: f32) -> Self {
+        self.tab_height = tab_height;
+        self.layout_dirty = true;
+        self
+    }
+
+    pub fn with_transition(mut self, transition: SwitchTransition) -> Self {
+        self.pages.set_transition(transition);
+        self
+    }
+
+    pub fn with_frames(mut self, frame: ButtonFrame, active_frame: ButtonFrame) -> Self {
+        self.set_frames(frame, active_frame);
+        self
+    }
+
+    pub fn set_frames(&mut self, frame: ButtonFrame, active_frame: ButtonFrame) {
+        self.frame = frame;
+        self.active_frame = active_frame;
+        self.update_tab_frames();
+    }
+
+    pub fn set_close_frame(&mut self, close_frame: ButtonFrame) {
+        self.close_frame = close_frame;
+        for tab in &mut self.tabs {
+            if let Some(close) = tab.close.as_mut() {
+                close.set_frame(close_frame);
+            }
+        }
+    }
+
+    // returns the index of the new tab
+    pub fn push_tab(&mut self, label: L, page: T) -> usize {
+        self.push(label, None, page)
+    }
+
+    // close_label is drawn on the close button at the end of the tab
+    pub fn push_closable_tab(&mut self, label: L, close_label: L, page: T) -> usize {
+        self.push(label, Some(close_label), page)
+    }
+
+    fn push(&mut self, label: L, close_label: Option<L>, page: T) -> usize {
+        self.tabs.push(Tab {
+            label: Tab::new_button(label, self.frame),
+            close: close_label.map(|close_label| Tab::new_button(close_label, self.close_frame)),
+        });
+        self.pages.get_data_mut().push(page);
+        self.layout_dirty = true;
+        self.tabs.len()-1
+    }
+
+    pub fn get_tab_count(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn get_active(&self) -> usize {
+        self.pages.get_active()
+    }
+
+    pub fn set_active(&mut self, index: usize) -> TabChangedCallback {
+        if index >= self.tabs.len() {
+            return TabChangedCallback::NoChange;
+        }
+        // slide towards the side the tab is on
+        let switch_callback = if index < self.get_active() {
+            self.pages.set_active_backward(index)
+        } else {
+            self.pages.set_active(index)
+        };
+        if switch_callback.is_switched() {
+            self.update_tab_frames();
+        }
+        switch_callback.into()
+    }
+
+    // wraps around at the ends
+    pub fn select_next(&mut self) -> TabChangedCallback {
+        self.set_active(next_index(self.get_active(), self.tabs.len()))
+    }
+
+    pub fn select_previous(&mut self) -> TabChangedCallback {
+        self.set_active(previous_index(self.get_active(), self.tabs.len()))
+    }
+
+    // Returns the page of the closed tab with Closed, or None with NoChange when nothing was closed.
+    // The last tab cannot be closed.
+    pub fn close_tab(&mut self, index: usize) -> (Option<T>, TabChangedCallback) {
+        if index >= self.tabs.len() || self.tabs.len() == 1 {
+            return (None, TabChangedCallback::NoChange);
+        }
+        let active = active_after_close(self.get_active(), index, self.tabs.len()-1);
+        self.tabs.remove(index);
+        let page = self.pages.get_data_mut().remove(index);
+        self.pages.jump_to(active);
+        self.update_tab_frames();
+        self.layout_dirty = true;
+        (Some(page), TabChangedCallback::Closed {
+            index,
+            active,
+        })
+    }
+
+    // None takes the close button away
+    pub fn set_close_label(&mut self, index: usize, close_label: Option<L>) {
+        if let Some(tab) = self.tabs.get_mut(index) {
+            tab.close = close_label.map(|close_label| Tab::new_button(close_label, self.close_frame));
+            self.layout_dirty = true;
+        }
+    }
+
+    pub fn is_closable(&self, index: usize) -> bool {
+        self.tabs.get(index).is_some_and(|tab| tab.close.is_some())
+    }
+
+    pub fn get_label(&self, index: usize) -> Option<&L> {
+        self.tabs.get(index).map(|tab| tab.label.get_child().get_child())
+    }
+
+    // call relayout_tabs after changing the text, the tab widths depend on it
+    pub fn get_label_mut(&mut self, index: usize) -> Option<&mut L> {
+        self.tabs.get_mut(index).map(|tab| tab.label.get_child_mut().get_child_mut())
+    }
+
+    pub fn relayout_tabs(&mut self) {
+        self.layout_dirty = true;
+    }
+
+    pub fn get_page(&self, index: usize) -> Option<&T> {
+        self.pages.get_data().get(index)
+    }
+
+    pub fn get_page_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.pages.get_data_mut().get_mut(index)
+    }
+
+    pub fn get_active_page(&self) -> &T {
+        self.pages.get_active_data()
+    }
+
+    pub fn get_active_page_mut(&mut self) -> &mut T {
+        self.pages.get_active_data_mut()
+    }
+
+    fn update_tab_frames(&mut self) {
+        let active = self.get_active();
+        for (i, tab) in self.tabs.iter_mut().enumerate() {
+            tab.label.set_frame(if i == active {
+                self.active_frame
+            } else {
+                self.frame
+            });
+        }
+    }
+
+    fn get_bar_height(&self) -> f32 {
+        self.tab_height.min(self.fill_area.sy*2.0)
+    }
+
+    // tabs keep their preferred widths unless the row is too long, then they all shrink alike
+    fn layout(&mut self)
+    where
+        T: Fill,
+        L: Fill+Measure
+    {
+        let area = self.fill_area;
+        let (left, top) = (area.cx-area.sx, area.cy+area.sy);
+        let bar_height = self.get_bar_height();
+
+        let widths: Vec<f32> = self.tabs.iter().map(|tab| tab.get_width(bar_height)).collect();
+        let total: f32 = widths.iter().sum();
+        let scale = if total > area.sx*2.0 {
+            area.sx*2.0/total
+        } else {
+            1.0
+        };
+        let mut tab_left = left;
+        for (tab, width) in self.tabs.iter_mut().zip(widths) {
+            tab.fill(tab_left, width*scale, top, bar_height);
+            tab_left += width*scale;
+        }
+        self.bar_points = Points {
+            p1x: left,
+            p1y: top,
+            p2x: area.cx+area.sx,
+            p2y: top-bar_height,
+        };
+
+        // every page is laid out, so a transition can draw the outgoing one
+        let page_height = area.sy*2.0-bar_height;
+        let page_area = SizeAndCenter {
+            sx: area.sx,
+            sy: page_height/2.0,
+            cx: area.cx,
+            cy: top-bar_height-page_height/2.0,
+        };
+        for page in self.pages.get_data_mut() {
+            page.fill(page_area);
+        }
+        self.layout_dirty = false;
+    }
+}
+
+impl<T: Fill, L: Fill+Measure> Init for TabView<T, L> {
+    fn init(&mut self) {
+        self.layout();
+    }
+}
+
+impl<T: Fill, L: Fill+Measure> Fill for TabView<T, L> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.fill_area = fill_target;
+        self.layout();
+    }
+}
+
+impl<T: Measure, L: Measure> Measure for TabView<T, L> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        let bar_width: f32 = self.tabs.iter().map(|tab| tab.get_width(self.tab_height)).sum();
+        self.pages.measure(constraints.shrink(0.0, self.tab_height))
+            .map(|size| Size::new(size.width.max(bar_width), size.height+self.tab_height))
+            .normalized()
+    }
+}
+
+// the tab view fills its area, so its height never changes
+impl<T: Dirty+Fill, L: Dirty+Fill+Measure> Dirty for TabView<T, L> {
+    fn get_dirty(&self) -> DirtyFlags {
+        let dirty = self.pages.get_dirty().or(DirtyFlags::new(false, self.layout_dirty));
+        self.tabs.iter().flat_map(|tab| tab.buttons()).fold(dirty, |dirty, button| dirty.or(button.get_dirty()))
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        for tab in &mut self.tabs {
+            for button in tab.buttons_mut() {
+                button.update_layout();
+            }
+        }
+        self.pages.update_layout();
+        if self.layout_dirty {
+            self.layout();
+        }
+        0.0
+    }
+
+    fn clear_dirty(&mut self) {
+        for tab in &mut self.tabs {
+            for button in tab.buttons_mut() {
+                button.clear_dirty();
+            }
+        }
+        self.pages.clear_dirty();
+    }
+}
+
+impl<T: TranslateX, L: TranslateX> TranslateX for TabView<T, L> {
+    fn translate_x(&mut self, dx: f32) {
+        self.fill_area.cx += dx;
+        self.bar_points.p1x += dx;
+        self.bar_points.p2x += dx;
+        for tab in &mut self.tabs {
+            for button in tab.buttons_mut() {
+                button.translate_x(dx);
+            }
+        }
+        for page in self.pages.get_data_mut() {
+            page.translate_x(dx);
+        }
+    }
+}
+
+impl<T: TranslateY, L: TranslateY> TranslateY for TabView<T, L> {
+    fn translate_y(&mut self, dy: f32) {
+        self.fill_area.cy += dy;
+        self.bar_points.p1y += dy;
+        self.bar_points.p2y += dy;
+        for tab in &mut self.tabs {
+            for button in tab.buttons_mut() {
+                button.translate_y(dy);
+            }
+        }
+        for page in self.pages.get_data_mut() {
+            page.translate_y(dy);
+        }
+    }
+}
+
+impl<T, L> GetPointBounds for TabView<T, L> {
+    fn get_point_bounds(&self) -> Points {
+        self.fill_area.into()
+    }
+}
+
+// the tab bar first, then the page
+impl<C: StandardConstructTarget, T, L> Construct<C> for TabView<T, L>
+where
+    TabButton<L>: Construct<C>,
+    SwitchContainer<usize, T, Vec<T>>: Construct<C>
+{
+    fn construct(&self) -> C {
+        let mut construct_target = C::EMPTY;
+        for button in self.tabs.iter().flat_map(|tab| tab.buttons()) {
+            construct_target.append(button.construct());
+        }
+        construct_target.append(self.pages.construct());
+        construct_target
+    }
+}
+
+impl<C: StandardConstructTarget, T, L> ConstructStyled<C> for TabView<T, L>
+where
+    TabButton<L>: ConstructStyled<C>,
+    SwitchContainer<usize, T, Vec<T>>: ConstructStyled<C>
+{
+    fn construct_styled(&self, theme: &Theme) -> C {
+        let mut construct_target = C::EMPTY;
+        for button in self.tabs.iter().flat_map(|tab| tab.buttons()) {
+            construct_target.append(button.construct_styled(theme));
+        }
+        construct_target.append(self.pages.construct_styled(theme));
+        construct_target
+    }
+}
+
+impl<C: IntoPrimitives, T, L> RetainedConstruct<C> for TabView<T, L>
+where
+    TabButton<L>: RetainedConstruct<C>,
+    SwitchContainer<usize, T, Vec<T>>: RetainedConstruct<C>
+{
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        for button in self.tabs.iter().flat_map(|tab| tab.buttons()) {
+            button.construct_retained(target);
+        }
+        self.pages.construct_retained(target);
+    }
+}
+
+fn next_index(active: usize, len: usize) -> usize {
+    (active+1)%len
+}
+
+fn previous_index(active: usize, len: usize) -> usize {
+    (active+len-1)%len
+}
+
+// the tabs after the closed one move down, closing the active last tab selects the one before it
+fn active_after_close(active: usize, closed: usize, remaining: usize) -> usize {
+    if active > closed || active == remaining {
+        active-1
+    } else {
+        active
+    }
+}
+
+// What the tab bar does with a signal before the active page gets it.
+// The redraw callback reports tab buttons that changed how they look.
+trait TabBarSignal<L> {
+    fn take_tab_bar_signal<T>(&mut self, tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback);
+}
+
+impl<S, L> TabBarSignal<L> for S {
+    default fn take_tab_bar_signal<T>(&mut self, _tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback) {
+        (RedrawCallback::new(false), TabChangedCallback::NoChange)
+    }
+}
+
+fn press_changed(press_state_callback: PressStateCallback) -> bool {
+    matches!(press_state_callback, PressStateCallback::Update(_))
+}
+
+impl<L> TabBarSignal<L> for ResizedSignal
+where
+    TabButton<L>: SignalReciever<ResizedSignal, ReconstructCallback<LineTarget>>
+{
+    fn take_tab_bar_signal<T>(&mut self, tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback) {
+        for button in tab_view.tabs.iter_mut().flat_map(|tab| tab.buttons_mut()) {
+            let _: ReconstructCallback<LineTarget> = button.take_signal(self);
+        }
+        // label insets are in dp
+        tab_view.layout_dirty = true;
+        (RedrawCallback::new(true), TabChangedCallback::NoChange)
+    }
+}
+
+impl<L> TabBarSignal<L> for ScaleFactorSignal
+where
+    TabButton<L>: SignalReciever<ScaleFactorSignal, ReconstructCallback<LineTarget>>
+{
+    fn take_tab_bar_signal<T>(&mut self, tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback) {
+        for button in tab_view.tabs.iter_mut().flat_map(|tab| tab.buttons_mut()) {
+            let _: ReconstructCallback<LineTarget> = button.take_signal(self);
+        }
+        tab_view.layout_dirty = true;
+        (RedrawCallback::new(true), TabChangedCallback::NoChange)
+    }
+}
+
+impl<L> TabBarSignal<L> for CursorMovedSignal {
+    fn take_tab_bar_signal<T>(&mut self, tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback) {
+        let mut redraw = false;
+        for button in tab_view.tabs.iter_mut().flat_map(|tab| tab.buttons_mut()) {
+            redraw |= press_changed(button.take_signal(self));
+        }
+        (RedrawCallback::new(redraw), TabChangedCallback::NoChange)
+    }
+}
+
+impl<L> TabBarSignal<L> for MouseLeftDownSignal {
+    fn take_tab_bar_signal<T>(&mut self, tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback) {
+        let mut redraw = false;
+        for button in tab_view.tabs.iter_mut().flat_map(|tab| tab.buttons_mut()) {
+            redraw |= press_changed(button.take_signal(self));
+        }
+        (RedrawCallback::new(redraw), TabChangedCallback::NoChange)
+    }
+}
+
+impl<L> TabBarSignal<L> for MouseLeftUpSignal {
+    fn take_tab_bar_signal<T>(&mut self, tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback) {
+        // every button gets the signal so none stays pressed
+        let mut selected = None;
+        let mut closed = None;
+        for (i, tab) in tab_view.tabs.iter_mut().enumerate() {
+            let clicked: ClickedCallback = tab.label.take_signal(self);
+            if clicked == ClickedCallback::Clicked {
+                selected = Some(i);
+            }
+            if let Some(close) = tab.close.as_mut() {
+                let clicked: ClickedCallback = close.take_signal(self);
+                if clicked == ClickedCallback::Clicked {
+                    closed = Some(i);
+                }
+            }
+        }
+
+        let tab_changed = match (closed, selected) {
+            (Some(index), _) => tab_view.close_tab(index).1,
+            (None, Some(index)) => tab_view.set_active(index),
+            (None, None) => TabChangedCallback::NoChange
+        };
+        // a click always releases a pressed button
+        let redraw = selected.is_some() || closed.is_some();
+        (RedrawCallback::new(redraw), tab_changed)
+    }
+}
+
+impl<L> TabBarSignal<L> for MouseButtonSignal {
+    fn take_tab_bar_signal<T>(&mut self, tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback) {
+        match (self.button, self.state) {
+            (MouseButton::Left, ButtonState::Pressed) => MouseLeftDownSignal().take_tab_bar_signal(tab_view),
+            (MouseButton::Left, ButtonState::Released) => MouseLeftUpSignal().take_tab_bar_signal(tab_view),
+            _ => (RedrawCallback::new(false), TabChangedCallback::NoChange)
+        }
+    }
+}
+
+impl<L> TabBarSignal<L> for KeySignal {
+    fn take_tab_bar_signal<T>(&mut self, tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback) {
+        if !self.is_pressed() || self.key != Key::Tab || !self.modifiers.ctrl {
+            return (RedrawCallback::new(false), TabChangedCallback::NoChange);
+        }
+        let tab_changed = if self.modifiers.shift {
+            tab_view.select_previous()
+        } else {
+            tab_view.select_next()
+        };
+        (tab_changed.into(), tab_changed)
+    }
+}
+
+impl<L> TabBarSignal<L> for TickSignal
+where
+    TabButton<L>: SignalReciever<TickSignal, RedrawCallback>
+{
+    fn take_tab_bar_signal<T>(&mut self, tab_view: &mut TabView<T, L>) -> (RedrawCallback, TabChangedCallback) {
+        let mut redraw_callback = RedrawCallback::new(false);
+        for button in tab_view.tabs.iter_mut().flat_map(|tab| tab.buttons_mut()) {
+            redraw_callback = redraw_callback.or_into(button.take_signal(self));
+        }
+        (redraw_callback, TabChangedCallback::NoChange)
+    }
+}
+
+// Whether the active page still gets a signal after the tab bar had it.
+trait ReachesPage {
+    fn reaches_page(&self, tab_changed: TabChangedCallback) -> bool;
+}
+
+impl<S> ReachesPage for S {
+    default fn reaches_page(&self, _tab_changed: TabChangedCallback) -> bool {
+        true
+    }
+}
+
+// a Ctrl+Tab that switched tabs is not typed into the page as well
+impl ReachesPage for KeySignal {
+    fn reaches_page(&self, tab_changed: TabChangedCallback) -> bool {
+        !tab_changed.is_changed()
+    }
+}
+
+impl<S, C, T: SignalReciever<S, ReconstructCallback<C>>, L> SignalReciever<S, (ReconstructCallback<C>, TabChangedCallback)> for TabView<T, L> {
+    fn take_signal(&mut self, signal: &mut S) -> (ReconstructCallback<C>, TabChangedCallback) {
+        let (redraw_callback, tab_changed) = signal.take_tab_bar_signal(self);
+        let reconstruct_callback: ReconstructCallback<C> = redraw_callback.into();
+        if !signal.reaches_page(tab_changed) {
+            return (reconstruct_callback, tab_changed);
+        }
+        (reconstruct_callback.or_into(self.pages.take_signal(signal)), tab_changed)
+    }
+}
+
+impl<S, T: SignalReciever<S, RedrawCallback>, L> SignalReciever<S, (RedrawCallback, TabChangedCallback)> for TabView<T, L> {
+    fn take_signal(&mut self, signal: &mut S) -> (RedrawCallback, TabChangedCallback) {
+        let (redraw_callback, tab_changed) = signal.take_tab_bar_signal(self);
+        if !signal.reaches_page(tab_changed) {
+            return (redraw_callback, tab_changed);
+        }
+        (redraw_callback.or_into(self.pages.take_signal(signal)), tab_changed)
+    }
+}
+
+impl<S, C, T: SignalReciever<S, ReconstructCallback<C>>, L> SignalReciever<S, ReconstructCallback<C>> for TabView<T, L> {
+    fn take_signal(&mut self, signal: &mut S) -> ReconstructCallback<C> {
+        let (reconstruct_callback, _): (ReconstructCallback<C>, TabChangedCallback) = self.take_signal(signal);
+        reconstruct_callback
+    }
+}
+
+impl<S, T: SignalReciever<S, RedrawCallback>, L> SignalReciever<S, RedrawCallback> for TabView<T, L> {
+    fn take_signal(&mut self, signal: &mut S) -> RedrawCallback {
+        let (redraw_callback, _): (RedrawCallback, TabChangedCallback) = self.take_signal(signal);
+        redraw_callback
+    }
+}
+
+// the tab bar is index 0 and the page index 1
+impl<T: HitTest, L> HitTest for TabView<T, L> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        if self.bar_points.contains(x, y) {
+            path.push(0);
+            return true;
+        }
+        path.push(1);
+        if self.pages.hit_test(x, y, path) {
+            return true;
+        }
+        path.pop();
+        false
+    }
+}
+
+// The tab bar takes the whole signal, the page ignores pointer positions outside of it.
+impl<S, R: Default, T: RouteSignal<S, R>, L> RouteSignal<S, R> for TabView<T, L>
+where
+    TabView<T, L>: SignalReciever<S, R>
+{
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        match path.first() {
+            Some(0) => self.take_signal(signal),
+            Some(1) => self.pages.route_signal(&path[1..], signal),
+            _ => R::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{signal::Modifiers, test_util::Block};
+
+    #[test]
+    fn next_wraps_to_the_first_tab() {
+        assert_eq!(next_index(0, 3), 1);
+        assert_eq!(next_index(2, 3), 0);
+        assert_eq!(next_index(0, 1), 0);
+    }
+
+    #[test]
+    fn previous_wraps_to_the_last_tab() {
+        assert_eq!(previous_index(2, 3), 1);
+        assert_eq!(previous_index(0, 3), 2);
+        assert_eq!(previous_index(0, 1), 0);
+    }
+
+    #[test]
+    fn closing_the_active_tab_shows_the_next_one() {
+        // tabs 0 1 2, 1 is active and closed, the old 2 moves to 1
+        assert_eq!(active_after_close(1, 1, 2), 1);
+    }
+
+    #[test]
+    fn closing_the_active_last_tab_shows_the_one_before() {
+        assert_eq!(active_after_close(2, 2, 2), 1);
+    }
+
+    #[test]
+    fn closing_a_tab_before_the_active_one_keeps_it_shown() {
+        assert_eq!(active_after_close(2, 0, 2), 1);
+    }
+
+    #[test]
+    fn closing_a_tab_after_the_active_one_changes_nothing() {
+        assert_eq!(active_after_close(0, 2, 2), 0);
+    }
+
+    #[test]
+    fn closed_is_a_change() {
+        assert!(TabChangedCallback::Closed {
+            index: 0,
+            active: 0,
+        }.is_changed());
+        let redraw: RedrawCallback = TabChangedCallback::Closed {
+            index: 0,
+            active: 0,
+        }.into();
+        assert!(redraw.get_redraw());
+    }
+
+    type BlockTabs = TabView<Block, Block>;
+
+    const CTRL: Modifiers = Modifiers {
+        ctrl: true,
+        ..Modifiers::NONE
+    };
+
+    // two tabs over the whole window, the first one active
+    fn tab_view() -> BlockTabs {
+        let mut tab_view = TabView::new(Block::new(0.2, 0.05), Block::new(2.0, 1.0), SizeAndCenter {
+            sx: 1.0,
+            sy: 1.0,
+            cx: 0.0,
+            cy: 0.0,
+        });
+        tab_view.push_tab(Block::new(0.3, 0.05), Block::new(2.0, 1.0));
+        tab_view.init();
+        tab_view
+    }
+
+    fn send<S>(tab_view: &mut BlockTabs, mut signal: S) -> TabChangedCallback {
+        let (_, tab_changed): (RedrawCallback, TabChangedCallback) = tab_view.take_signal(&mut signal);
+        tab_changed
+    }
+
+    fn click(tab_view: &mut BlockTabs, button: impl Fn(&BlockTabs) -> &TabButton<Block>) -> TabChangedCallback {
+        let points = button(tab_view).get_point_bounds();
+        send(tab_view, CursorMovedSignal {
+            pixel_posx: 0.0,
+            pixel_posy: 0.0,
+            norm_posx: (points.p1x+points.p2x)/2.0,
+            norm_posy: (points.p1y+points.p2y)/2.0,
+        });
+        send(tab_view, MouseLeftDownSignal());
+        send(tab_view, MouseLeftUpSignal())
+    }
+
+    fn page_signals(tab_view: &BlockTabs) -> Vec<usize> {
+        (0..tab_view.get_tab_count()).map(|i| tab_view.get_page(i).unwrap().signals).collect()
+    }
+
+    fn assert_active_frame(tab_view: &BlockTabs) {
+        for (i, tab) in tab_view.tabs.iter().enumerate() {
+            let expected = if i == tab_view.get_active() {
+                BlockTabs::ACTIVE_FRAME
+            } else {
+                ButtonFrame::DEFAULT
+            };
+            assert_eq!(tab.label.get_frame(), &expected, "frame of tab {}", i);
+        }
+    }
+
+    #[test]
+    fn ctrl_tab_switches_tabs_without_reaching_the_page() {
+        let mut tab_view = tab_view();
+        let tab_changed = send(&mut tab_view, KeySignal::new(Key::Tab, ButtonState::Pressed, CTRL));
+        assert_eq!(tab_changed, TabChangedCallback::Changed {
+            from: 0,
+            to: 1,
+        });
+        assert_eq!(tab_view.get_active(), 1);
+        assert_eq!(page_signals(&tab_view), vec![0, 0]);
+
+        // releasing it and other keys still reach the active page
+        assert_eq!(send(&mut tab_view, KeySignal::new(Key::Tab, ButtonState::Released, CTRL)), TabChangedCallback::NoChange);
+        assert_eq!(send(&mut tab_view, KeySignal::new(Key::Tab, ButtonState::Pressed, Modifiers::NONE)), TabChangedCallback::NoChange);
+        assert_eq!(page_signals(&tab_view), vec![0, 2]);
+
+        let ctrl_shift = Modifiers {
+            shift: true,
+            ..CTRL
+        };
+        let tab_changed = send(&mut tab_view, KeySignal::new(Key::Tab, ButtonState::Pressed, ctrl_shift));
+        assert_eq!(tab_changed, TabChangedCallback::Changed {
+            from: 1,
+            to: 0,
+        });
+        assert_eq!(page_signals(&tab_view), vec![0, 2]);
+    }
+
+    #[test]
+    fn ctrl_tab_with_one_tab_reaches_the_page() {
+        let mut tab_view = tab_view();
+        tab_view.close_tab(1);
+        assert_eq!(send(&mut tab_view, KeySignal::new(Key::Tab, ButtonState::Pressed, CTRL)), TabChangedCallback::NoChange);
+        assert_eq!(page_signals(&tab_view), vec![1]);
+    }
+
+    #[test]
+    fn clicking_a_label_selects_its_tab() {
+        let mut tab_view = tab_view();
+        assert_active_frame(&tab_view);
+
+        let tab_changed = click(&mut tab_view, |tab_view| &tab_view.tabs[1].label);
+        assert_eq!(tab_changed, TabChangedCallback::Changed {
+            from: 0,
+            to: 1,
+        });
+        assert_eq!(tab_view.get_active(), 1);
+        assert_active_frame(&tab_view);
+
+        // clicking the active tab again changes nothing
+        assert_eq!(click(&mut tab_view, |tab_view| &tab_view.tabs[1].label), TabChangedCallback::NoChange);
+    }
+
+    #[test]
+    fn clicking_the_page_selects_no_tab() {
+        let mut tab_view = tab_view();
+        send(&mut tab_view, CursorMovedSignal {
+            pixel_posx: 0.0,
+            pixel_posy: 0.0,
+            norm_posx: 0.0,
+            norm_posy: 0.0,
+        });
+        send(&mut tab_view, MouseLeftDownSignal());
+        assert_eq!(send(&mut tab_view, MouseLeftUpSignal()), TabChangedCallback::NoChange);
+        assert_eq!(tab_view.get_active(), 0);
+        assert_eq!(page_signals(&tab_view), vec![3, 0]);
+    }
+
+    #[test]
+    fn clicking_a_close_button_closes_its_tab() {
+        let mut tab_view = tab_view();
+        let index = tab_view.push_closable_tab(Block::new(0.2, 0.05), Block::new(0.05, 0.05), Block::new(2.0, 1.0));
+        tab_view.relayout_tabs();
+        tab_view.update_layout();
+        assert!(tab_view.is_closable(index));
+        assert!(!tab_view.is_closable(0));
+        tab_view.set_active(index);
+
+        let tab_changed = click(&mut tab_view, |tab_view| tab_view.tabs[2].close.as_ref().unwrap());
+        assert_eq!(tab_changed, TabChangedCallback::Closed {
+            index: 2,
+            active: 1,
+        });
+        assert_eq!(tab_view.get_tab_count(), 2);
+        assert_eq!(tab_view.get_active(), 1);
+        assert_active_frame(&tab_view);
+    }
+
+    #[test]
+    fn active_frame_follows_every_switch() {
+        let mut tab_view = tab_view();
+        tab_view.push_tab(Block::new(0.2, 0.05), Block::new(2.0, 1.0));
+        tab_view.set_active(2);
+        assert_active_frame(&tab_view);
+        tab_view.select_next();
+        assert_eq!(tab_view.get_active(), 0);
+        assert_active_frame(&tab_view);
+        tab_view.close_tab(0);
+        assert_active_frame(&tab_view);
+    }
+}
\ No newline at end of file
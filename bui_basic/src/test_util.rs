@@ -0,0 +1,214 @@
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{containers::{Fill, FillWidth, Init, TranslateX, TranslateY, GetPointBounds}, signal::{SignalReciever, Propagating, Phase}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, Size, SizeHint}, dirty::{Dirty, DirtyFlags}, style::{ConstructStyled, Theme}};
+
+// A plain rectangle that logs the signals it recieves and the phases propagating ones arrive
+// in. Tests use it in place of Text, which needs a loaded font.
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub points: Points,
+    pub preferred: Size,
+    pub signals: usize,
+    // type names of the plain signals in the order they arrived
+    pub received: Vec<&'static str>,
+    pub phases: Vec<Phase>,
+    // marks propagating signals handled when they arrive in this phase
+    pub handles: Option<Phase>,
+}
+
+impl Block {
+    // not placed yet, asks for the given full size when measured
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            points: Points::ZERO,
+            preferred: Size::new(width, height),
+            signals: 0,
+            received: Vec::new(),
+            phases: Vec::new(),
+            handles: None,
+        }
+    }
+
+    // already placed at the given corners, top left then bottom right
+    pub fn at(p1x: f32, p1y: f32, p2x: f32, p2y: f32) -> Self {
+        Self {
+            points: Points {
+                p1x,
+                p1y,
+                p2x,
+                p2y,
+            },
+            preferred: Size::new(p2x-p1x, p1y-p2y),
+            signals: 0,
+            received: Vec::new(),
+            phases: Vec::new(),
+            handles: None,
+        }
+    }
+
+    pub fn get_size(&self) -> Size {
+        Size::new(self.points.p2x-self.points.p1x, self.points.p1y-self.points.p2y)
+    }
+
+    pub fn get_center(&self) -> (f32, f32) {
+        ((self.points.p1x+self.points.p2x)/2.0, (self.points.p1y+self.points.p2y)/2.0)
+    }
+}
+
+impl Init for Block {
+    fn init(&mut self) {}
+}
+
+impl Fill for Block {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.points = Points {
+            p1x: fill_target.cx-fill_target.sx,
+            p1y: fill_target.cy+fill_target.sy,
+            p2x: fill_target.cx+fill_target.sx,
+            p2y: fill_target.cy-fill_target.sy,
+        };
+    }
+}
+
+impl FillWidth for Block {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        self.points = Points {
+            p1x: cx-sx,
+            p1y: ty,
+            p2x: cx+sx,
+            p2y: ty-self.preferred.height,
+        };
+        ty-self.preferred.height
+    }
+}
+
+impl Measure for Block {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        SizeHint::flexible(constraints.constrain(self.preferred))
+    }
+}
+
+impl Dirty for Block {
+    fn get_dirty(&self) -> DirtyFlags {
+        DirtyFlags::CLEAN
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        0.0
+    }
+
+    fn clear_dirty(&mut self) {}
+}
+
+impl TranslateX for Block {
+    fn translate_x(&mut self, dx: f32) {
+        self.points.p1x += dx;
+        self.points.p2x += dx;
+    }
+}
+
+impl TranslateY for Block {
+    fn translate_y(&mut self, dy: f32) {
+        self.points.p1y += dy;
+        self.points.p2y += dy;
+    }
+}
+
+impl GetPointBounds for Block {
+    fn get_point_bounds(&self) -> Points {
+        self.points
+    }
+}
+
+// draws nothing, so only what containers add around it is constructed
+impl<C: Default> ConstructStyled<C> for Block {
+    fn construct_styled(&self, _theme: &Theme) -> C {
+        C::default()
+    }
+}
+
+impl<S, R: Default> SignalReciever<S, R> for Block {
+    default fn take_signal(&mut self, _signal: &mut S) -> R {
+        self.signals += 1;
+        self.received.push(std::any::type_name::<S>().rsplit("::").next().unwrap());
+        R::default()
+    }
+}
+
+impl<S, R: Default> SignalReciever<Propagating<S>, R> for Block {
+    fn take_signal(&mut self, signal: &mut Propagating<S>) -> R {
+        self.signals += 1;
+        self.phases.push(signal.get_phase());
+        if self.handles == Some(signal.get_phase()) {
+            signal.set_handled();
+        }
+        R::default()
+    }
+}
+
+impl HitTest for Block {
+    fn hit_test(&self, x: f32, y: f32, _path: &mut Vec<usize>) -> bool {
+        self.points.contains(x, y)
+    }
+}
+
+impl<S, R: Default> RouteSignal<S, R> for Block {
+    fn route_signal(&mut self, _path: &[usize], signal: &mut S) -> R {
+        self.take_signal(signal)
+    }
+}
+
+// A Block that can change its height after it was placed, the way a wrapping Text does when
+// its content changes. The change is applied by the next update_layout.
+#[derive(Debug, Clone)]
+pub struct Growing {
+    pub block: Block,
+    growth: f32,
+    dirty: DirtyFlags,
+}
+
+impl Growing {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            block: Block::new(width, height),
+            growth: 0.0,
+            dirty: DirtyFlags::CLEAN,
+        }
+    }
+
+    pub fn grow(&mut self, dh: f32) {
+        self.growth += dh;
+        self.dirty = DirtyFlags::new(true, true);
+    }
+}
+
+impl FillWidth for Growing {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        self.block.fill_width(sx, cx, ty)
+    }
+}
+
+impl Dirty for Growing {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.dirty
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        // keeps the top edge, the bottom moves down
+        let growth = self.growth;
+        self.block.points.p2y -= growth;
+        self.block.preferred.height += growth;
+        self.growth = 0.0;
+        growth
+    }
+
+    fn clear_dirty(&mut self) {
+        self.dirty = DirtyFlags::CLEAN;
+    }
+}
+
+impl TranslateY for Growing {
+    fn translate_y(&mut self, dy: f32) {
+        self.block.translate_y(dy);
+    }
+}
\ No newline at end of file
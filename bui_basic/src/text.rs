@@ -1,8 +1,8 @@
-use std::sync::{Arc, Mutex};
+use std::{sync::{Arc, Mutex}, cell::Cell};
 
 use bui::{ttf::CachedFace, ttf_outline::{compute_unfit_chars, compute_square_transform, transform_lines, transform_points, transform_points_vec, compute_square_transform_by_width}, rect::{SizeAndCenter, Points}, line::LineRaw};
 
-use crate::{construct::{LineTarget, Construct}, containers::{Fill, GetPointBounds, GetCenterPosition, FillWidth, TranslateY, TranslateX, Init}, signal::{SignalReciever, ResizedSignal, CursorMovedSignal, MouseLeftDownSignal, MouseLeftUpSignal, ReconstructCallback, CharacterInputSignal}};
+use crate::{construct::{LineTarget, Freeform2DCapsuleTarget, ShapeTarget, CapsuleFill, Construct, StandardConstructTarget, fill_capsules}, containers::{Fill, GetPointBounds, GetCenterPosition, FillWidth, TranslateY, TranslateX, Init}, signal::{SignalReciever, ignore_signals, Propagating, Phase, ResizedSignal, CursorMovedSignal, MouseLeftDownSignal, MouseLeftUpSignal, MouseButtonSignal, MouseButton, ButtonState, ClickSignal, ClickEvent, ReconstructCallback}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, Size, SizeHint}, units::{WINDOW_NORM, DEFAULT_RESYP}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, RetainedId}, style::{ConstructStyled, StyledLineTarget, Theme}, draw_list::DrawList};
 
 #[derive(Debug, Clone)]
 pub enum TextSizeMode {
@@ -81,9 +81,19 @@ pub struct Text {
     mousey: f32,
     select_state: SelectState,
     selection_lines: Option<[LineRaw; 4]>,
+    dirty: DirtyFlags,
+    height_change: f32,
+    retained_id: RetainedId,
+    // height of the glyphs in pixels when nothing else decides it
+    natural_height: f32,
+    resyp: u32,
+    // glyph bounds before any transform, measuring happens far more often than the text changes
+    unfit_bounds: Cell<Option<Points>>,
 }
 
 impl Text {
+    pub const DEFAULT_NATURAL_HEIGHT: f32 = 24.0;
+
     pub fn new<T: Into<String>>(text: T, face: Arc<Mutex<CachedFace>>) -> Self {
         Self::new_with_res(text.into(), face, 1.0, 1.0)
     }
@@ -102,12 +112,41 @@ impl Text {
             mousey: -1.0,
             select_state: SelectState::None,
             selection_lines: None,
+            dirty: DirtyFlags::new(true, true),
+            height_change: 0.0,
+            retained_id: RetainedId::new(),
+            natural_height: Self::DEFAULT_NATURAL_HEIGHT,
+            resyp: DEFAULT_RESYP,
+            unfit_bounds: Cell::new(None),
         }
     }
 
+    pub fn with_natural_height(mut self, natural_height: f32) -> Self {
+        self.natural_height = natural_height;
+        self
+    }
+
+    pub fn set_natural_height(&mut self, natural_height: f32) {
+        self.natural_height = natural_height;
+    }
+
+    pub fn get_natural_height(&self) -> f32 {
+        self.natural_height
+    }
+
+    fn get_unfit_bounds(&self) -> Points {
+        if let Some(unfit_bounds) = self.unfit_bounds.get() {
+            return unfit_bounds;
+        }
+        let (_, unfit_bounds, _) = compute_unfit_chars(&mut self.face.lock().unwrap(), self.text.as_str(), 5);
+        self.unfit_bounds.set(Some(unfit_bounds));
+        unfit_bounds
+    }
+
     fn compute_chars(&mut self) {
         let (lines, chars_bounds, char_bounds) = match self.size_mode {
-            TextSizeMode::Unconstrained => todo!(),
+            // nothing to lay the glyphs into before the first fill
+            TextSizeMode::Unconstrained => (None, None, None),
             TextSizeMode::Fill(fill_target) => {
                 let (mut lines, mut chars_bounds, mut char_bounds) = compute_unfit_chars(&mut self.face.lock().unwrap(), self.text.as_str(), 5);
                 let transform = compute_square_transform(chars_bounds, fill_target, self.resx, self.resy);
@@ -131,6 +170,18 @@ impl Text {
         self.lines = lines;
         self.chars_bounds = chars_bounds;
         self.char_bounds = char_bounds;
+        self.dirty.construct = true;
+    }
+
+    // filling by width keeps the top in place, so a new height only moves the bottom
+    fn recompute_chars(&mut self) {
+        let by = self.get_bottom_y();
+        self.compute_chars();
+        let height_change = by-self.get_bottom_y();
+        if height_change != 0.0 {
+            self.height_change += height_change;
+            self.dirty.layout = true;
+        }
     }
 
     fn pos_to_select_point(&self, x: f32, y: f32) -> Option<DragPoint> {
@@ -155,6 +206,38 @@ impl Text {
         }
     }
 
+    // used while dragging so the selection keeps following a cursor that left the text
+    fn pos_to_nearest_select_point(&self, x: f32) -> Option<DragPoint> {
+        let char_bounds = self.char_bounds.as_ref()?;
+
+        let mut nearest: Option<(usize, f32)> = None;
+        for (i, points) in char_bounds.iter().enumerate() {
+            let distance = if x < points.p1x {
+                points.p1x-x
+            } else if x > points.p2x {
+                x-points.p2x
+            } else {
+                0.0
+            };
+            match nearest {
+                Some((_, nearest_distance)) if nearest_distance <= distance => {},
+                _ => nearest = Some((i, distance))
+            }
+        }
+
+        nearest.map(|(i, _)| {
+            let points = char_bounds[i];
+            DragPoint {
+                char_index: i,
+                char_side: if x < (points.p1x+points.p2x)/2.0 {
+                    CharSide::Left
+                } else {
+                    CharSide::Right
+                }
+            }
+        })
+    }
+
     fn selection_to_lines(&self, selection: &Selection) -> [LineRaw; 4] {        
         let chars_bounds = self.chars_bounds.unwrap();
         let ymax = chars_bounds.p1y;
@@ -189,6 +272,7 @@ impl Text {
     }
 
     fn compute_selection_lines(&mut self) {
+        self.dirty.construct = true;
         let selection_lines = match &self.select_state {
             SelectState::None => None,
             SelectState::Dragging(_, selection) => match selection {
@@ -200,15 +284,58 @@ impl Text {
         self.selection_lines = selection_lines;
     }
 
+    // the rectangle outlined by the selection lines
+    fn get_selection_points(&self) -> Option<Points> {
+        self.selection_lines.map(|selection_lines| Points {
+            p1x: selection_lines[0].p1[0].min(selection_lines[2].p1[0]),
+            p1y: selection_lines[1].p1[1],
+            p2x: selection_lines[0].p1[0].max(selection_lines[2].p1[0]),
+            p2y: selection_lines[0].p1[1],
+        })
+    }
+
     pub fn get_text(&self) -> &String {
         &self.text
     }
 
+    // bottom of the laid out glyphs when filling by width, the bottom of the target otherwise
+    fn get_bottom_y(&self) -> f32 {
+        match (&self.size_mode, self.chars_bounds) {
+            (TextSizeMode::FillWidth(_, _, _), Some(chars_bounds)) => chars_bounds.p2y,
+            (TextSizeMode::FillWidth(_, _, ty), None) => *ty,
+            (TextSizeMode::Fill(fill_target), _) => fill_target.cy-fill_target.sy,
+            (TextSizeMode::Unconstrained, _) => 0.0
+        }
+    }
+
+    pub fn get_chars_bounds(&self) -> Option<Points> {
+        self.chars_bounds
+    }
+
+    pub fn get_char_bounds(&self) -> Option<&Vec<Points>> {
+        self.char_bounds.as_ref()
+    }
+
+    // height over width of the laid out glyphs in normalized coordinates
+    pub fn compute_aspect(&self) -> f32 {
+        let mut chars_bounds = self.get_unfit_bounds();
+        let transform = compute_square_transform_by_width(chars_bounds, 1.0, 0.0, 0.0, self.resx, self.resy);
+        transform_points(&mut chars_bounds, transform);
+
+        let width = chars_bounds.p2x-chars_bounds.p1x;
+        if width > 0.0 {
+            (chars_bounds.p1y-chars_bounds.p2y)/width
+        } else {
+            0.0
+        }
+    }
+
     pub fn set_text(&mut self, new_text: String) {
         self.text = new_text;
-        
+        self.unfit_bounds.set(None);
+
         if let Some(_) = self.lines {
-            self.compute_chars();
+            self.recompute_chars();
         }
         if self.select_state != SelectState::None {
             self.select_state = SelectState::None;
@@ -218,9 +345,10 @@ impl Text {
 
     pub fn backspace(&mut self) {
         self.text.pop();
+        self.unfit_bounds.set(None);
 
         if let Some(_) = self.lines {
-            self.compute_chars();
+            self.recompute_chars();
         }
         if self.select_state != SelectState::None {
             self.select_state = SelectState::None;
@@ -229,6 +357,59 @@ impl Text {
     }
 }
 
+// the run of word, space or punctuation characters around char_index
+fn word_selection_at(text: &str, char_index: usize) -> Option<Selection> {
+    let chars: Vec<char> = text.chars().collect();
+    let char_class = |c: char| if c.is_alphanumeric() || c == '_' {
+        0
+    } else if c.is_whitespace() {
+        1
+    } else {
+        2
+    };
+
+    let class = char_class(*chars.get(char_index)?);
+    let mut start = char_index;
+    while start > 0 && char_class(chars[start-1]) == class {
+        start -= 1;
+    }
+    let mut end = char_index+1;
+    while end < chars.len() && char_class(chars[end]) == class {
+        end += 1;
+    }
+
+    Some(Selection {
+        start,
+        end,
+    })
+}
+
+// the line around char_index without its newline, None on an empty line
+fn line_selection_at(text: &str, char_index: usize) -> Option<Selection> {
+    let chars: Vec<char> = text.chars().collect();
+    if char_index >= chars.len() {
+        return None;
+    }
+
+    let mut start = char_index;
+    while start > 0 && chars[start-1] != '\n' {
+        start -= 1;
+    }
+    let mut end = char_index;
+    while end < chars.len() && chars[end] != '\n' {
+        end += 1;
+    }
+
+    if start == end {
+        None
+    } else {
+        Some(Selection {
+            start,
+            end,
+        })
+    }
+}
+
 impl Construct<LineTarget> for Text {
     fn construct(&self) -> LineTarget {
         LineTarget(
@@ -246,6 +427,72 @@ impl Construct<LineTarget> for Text {
     }
 }
 
+// the selection highlight as one capsule as tall as the glyphs
+impl Construct<Freeform2DCapsuleTarget> for Text {
+    fn construct(&self) -> Freeform2DCapsuleTarget {
+        Freeform2DCapsuleTarget(
+            match self.get_selection_points() {
+                Some(points) => fill_capsules(points, CapsuleFill::solid((points.p1y-points.p2y)/2.0)),
+                None => Vec::with_capacity(0)
+            }
+        )
+    }
+}
+
+impl Construct<ShapeTarget> for Text {
+    fn construct(&self) -> ShapeTarget {
+        ShapeTarget::new(self.construct(), self.construct())
+    }
+}
+
+impl Construct<DrawList> for Text {
+    fn construct(&self) -> DrawList {
+        DrawList::from(<Self as Construct<ShapeTarget>>::construct(self))
+    }
+}
+
+impl ConstructStyled<StyledLineTarget> for Text {
+    fn construct_styled(&self, theme: &Theme) -> StyledLineTarget {
+        let mut target = match &self.lines {
+            Some(lines) => StyledLineTarget::from_lines(lines, theme.text),
+            None => StyledLineTarget::EMPTY
+        };
+        if let Some(selection_lines) = self.selection_lines {
+            for line in selection_lines {
+                target.push_line(line, theme.selection);
+            }
+        }
+        target
+    }
+}
+
+impl ConstructStyled<DrawList> for Text {
+    fn construct_styled(&self, theme: &Theme) -> DrawList {
+        let mut target = DrawList::EMPTY;
+        if let Some(lines) = &self.lines {
+            target.set_stroke(theme.text);
+            target.push_lines(lines.clone());
+        }
+        if let Some(selection_lines) = self.selection_lines {
+            target.set_stroke(theme.selection);
+            target.push_lines(Vec::from(selection_lines));
+        }
+        target
+    }
+}
+
+impl RetainedConstruct<LineTarget> for Text {
+    fn construct_retained(&self, target: &mut RetainedTarget<LineTarget>) {
+        target.write_slot(&self.retained_id, self.dirty.construct, || self.construct());
+    }
+}
+
+impl RetainedConstruct<Freeform2DCapsuleTarget> for Text {
+    fn construct_retained(&self, target: &mut RetainedTarget<Freeform2DCapsuleTarget>) {
+        target.write_slot(&self.retained_id, self.dirty.construct, || self.construct());
+    }
+}
+
 impl Init for Text {
     fn init(&mut self) {}
 }
@@ -260,82 +507,123 @@ impl Fill for Text {
 
 impl FillWidth for Text {
     fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
-        todo!()
+        self.size_mode = TextSizeMode::FillWidth(sx, cx, ty);
+        self.compute_chars();
+        self.compute_selection_lines();
+        self.get_bottom_y()
+    }
+}
+
+// Text scales to whatever it is given, so it prefers the single line at its natural height
+// and shrinks that, keeping the aspect, until it fits.
+impl Measure for Text {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        SizeHint::flexible(fit_aspect(self.compute_aspect(), natural_height_norm(self.natural_height, self.resyp), constraints))
+    }
+}
+
+// a height in pixels as a share of the window height in normalized units
+fn natural_height_norm(natural_height: f32, resyp: u32) -> f32 {
+    natural_height*WINDOW_NORM/resyp.max(1) as f32
+}
+
+// the largest size up to the given height that keeps height over width and fits the constraints
+fn fit_aspect(aspect: f32, height: f32, constraints: Constraints) -> Size {
+    if aspect <= 0.0 {
+        return Size::new(0.0, height.min(constraints.max_height));
+    }
+    let height = height.min(constraints.max_height).min(constraints.max_width*aspect);
+    Size::new(height/aspect, height)
+}
+
+impl Dirty for Text {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.dirty
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let height_change = self.height_change;
+        self.height_change = 0.0;
+        self.dirty.layout = false;
+        height_change
+    }
+
+    fn clear_dirty(&mut self) {
+        self.dirty = DirtyFlags::CLEAN;
+        self.height_change = 0.0;
     }
 }
 
 impl TranslateX for Text {
     fn translate_x(&mut self, dx: f32) {
+        self.dirty.construct = true;
         match &mut self.size_mode {
-            TextSizeMode::Fill(size_and_center) => {
-                size_and_center.cx += dx;
+            TextSizeMode::Fill(size_and_center) => size_and_center.cx += dx,
+            TextSizeMode::FillWidth(_, cx, _) => *cx += dx,
+            TextSizeMode::Unconstrained => {}
+        }
 
-                if let Some(chars_bounds) = self.chars_bounds.as_mut() {
-                    chars_bounds.p1x += dx;
-                    chars_bounds.p2x += dx;
-                }
+        if let Some(chars_bounds) = self.chars_bounds.as_mut() {
+            chars_bounds.p1x += dx;
+            chars_bounds.p2x += dx;
+        }
 
-                if let Some(lines) = self.lines.as_mut() {
-                    for line in lines {
-                        line.p1[0] += dx;
-                        line.p2[0] += dx;
-                    }
-                }
-                
-                if let Some(chars_bounds) = self.char_bounds.as_mut() {
-                    for point in chars_bounds {
-                        point.p1x += dx;
-                        point.p2x += dx;
-                    }
-                }
-                
-                if let Some(selection_lines) = self.selection_lines.as_mut() {
-                    for line in selection_lines {
-                        line.p1[0] += dx;
-                        line.p2[0] += dx;
-                    }
-                }
-            },
-            TextSizeMode::FillWidth(_, _, _) => todo!(),
-            TextSizeMode::Unconstrained => todo!(),
+        if let Some(lines) = self.lines.as_mut() {
+            for line in lines {
+                line.p1[0] += dx;
+                line.p2[0] += dx;
+            }
+        }
+        
+        if let Some(chars_bounds) = self.char_bounds.as_mut() {
+            for point in chars_bounds {
+                point.p1x += dx;
+                point.p2x += dx;
+            }
+        }
+        
+        if let Some(selection_lines) = self.selection_lines.as_mut() {
+            for line in selection_lines {
+                line.p1[0] += dx;
+                line.p2[0] += dx;
+            }
         }
     }
 }
 
 impl TranslateY for Text {
     fn translate_y(&mut self, dy: f32) {
+        self.dirty.construct = true;
         match &mut self.size_mode {
-            TextSizeMode::Fill(size_and_center) => {
-                size_and_center.cy += dy;
+            TextSizeMode::Fill(size_and_center) => size_and_center.cy += dy,
+            TextSizeMode::FillWidth(_, _, ty) => *ty += dy,
+            TextSizeMode::Unconstrained => {}
+        }
 
-                if let Some(chars_bounds) = self.chars_bounds.as_mut() {
-                    chars_bounds.p1y += dy;
-                    chars_bounds.p2y += dy;
-                }
+        if let Some(chars_bounds) = self.chars_bounds.as_mut() {
+            chars_bounds.p1y += dy;
+            chars_bounds.p2y += dy;
+        }
 
-                if let Some(lines) = self.lines.as_mut() {
-                    for line in lines {
-                        line.p1[1] += dy;
-                        line.p2[1] += dy;
-                    }
-                }
-                
-                if let Some(chars_bounds) = self.char_bounds.as_mut() {
-                    for point in chars_bounds {
-                        point.p1y += dy;
-                        point.p2y += dy;
-                    }
-                }
-                
-                if let Some(selection_lines) = self.selection_lines.as_mut() {
-                    for line in selection_lines {
-                        line.p1[1] += dy;
-                        line.p2[1] += dy;
-                    }
-                }
-            },
-            TextSizeMode::FillWidth(_, _, _) => todo!(),
-            TextSizeMode::Unconstrained => todo!(),
+        if let Some(lines) = self.lines.as_mut() {
+            for line in lines {
+                line.p1[1] += dy;
+                line.p2[1] += dy;
+            }
+        }
+        
+        if let Some(chars_bounds) = self.char_bounds.as_mut() {
+            for point in chars_bounds {
+                point.p1y += dy;
+                point.p2y += dy;
+            }
+        }
+        
+        if let Some(selection_lines) = self.selection_lines.as_mut() {
+            for line in selection_lines {
+                line.p1[1] += dy;
+                line.p2[1] += dy;
+            }
         }
     }
 }
@@ -344,7 +632,8 @@ impl SignalReciever<ResizedSignal, ReconstructCallback<LineTarget>> for Text {
     fn take_signal(&mut self, signal: &mut ResizedSignal) -> ReconstructCallback<LineTarget> {
         self.resx = signal.resx;
         self.resy = signal.resy;
-        self.compute_chars();
+        self.resyp = signal.resyp;
+        self.recompute_chars();
         self.compute_selection_lines();
         ReconstructCallback::new(true)
     }
@@ -355,7 +644,12 @@ impl SignalReciever<CursorMovedSignal, (ReconstructCallback<LineTarget>, SelectS
         self.mousex = signal.norm_posx;
         self.mousey = signal.norm_posy;
 
-        match self.pos_to_select_point(self.mousex, self.mousey) {
+        let select_point = match self.select_state {
+            SelectState::Dragging(_, _) => self.pos_to_select_point(self.mousex, self.mousey).or_else(|| self.pos_to_nearest_select_point(self.mousex)),
+            _ => self.pos_to_select_point(self.mousex, self.mousey)
+        };
+
+        match select_point {
             Some(select_point) => {
                 match &mut self.select_state {
                     SelectState::Dragging(drag_state, selection) => {
@@ -443,12 +737,94 @@ impl<R: Default> SignalReciever<MouseLeftUpSignal, R> for Text {
     }
 }
 
-impl<R: Default> SignalReciever<CharacterInputSignal, R> for Text {
-    fn take_signal(&mut self, _signal: &mut CharacterInputSignal) -> R {
-        R::default()
+impl<R: Default> SignalReciever<MouseButtonSignal, R> for Text
+where
+    Text: SignalReciever<MouseLeftDownSignal, R>+SignalReciever<MouseLeftUpSignal, R>
+{
+    fn take_signal(&mut self, signal: &mut MouseButtonSignal) -> R {
+        match (signal.button, signal.state) {
+            (MouseButton::Left, ButtonState::Pressed) => <Text as SignalReciever<MouseLeftDownSignal, R>>::take_signal(self, &mut MouseLeftDownSignal()),
+            (MouseButton::Left, ButtonState::Released) => <Text as SignalReciever<MouseLeftUpSignal, R>>::take_signal(self, &mut MouseLeftUpSignal()),
+            _ => R::default()
+        }
+    }
+}
+
+// text reacts when the signal bubbles back up, the capture pass only passes through it
+impl<R: Default> SignalReciever<Propagating<CursorMovedSignal>, R> for Text
+where
+    Text: SignalReciever<CursorMovedSignal, R>
+{
+    fn take_signal(&mut self, signal: &mut Propagating<CursorMovedSignal>) -> R {
+        if signal.get_phase() == Phase::Capture {
+            return R::default();
+        }
+        <Text as SignalReciever<CursorMovedSignal, R>>::take_signal(self, &mut signal.signal)
+    }
+}
+
+impl<R: Default> SignalReciever<Propagating<MouseLeftDownSignal>, R> for Text
+where
+    Text: SignalReciever<MouseLeftDownSignal, R>
+{
+    fn take_signal(&mut self, signal: &mut Propagating<MouseLeftDownSignal>) -> R {
+        if signal.get_phase() == Phase::Capture {
+            return R::default();
+        }
+        let callback = <Text as SignalReciever<MouseLeftDownSignal, R>>::take_signal(self, &mut signal.signal);
+        if let SelectState::Dragging(_, _) = self.select_state {
+            signal.set_handled();
+        }
+        callback
+    }
+}
+
+impl<R: Default> SignalReciever<Propagating<MouseLeftUpSignal>, R> for Text
+where
+    Text: SignalReciever<MouseLeftUpSignal, R>
+{
+    fn take_signal(&mut self, signal: &mut Propagating<MouseLeftUpSignal>) -> R {
+        if signal.get_phase() == Phase::Capture {
+            return R::default();
+        }
+        <Text as SignalReciever<MouseLeftUpSignal, R>>::take_signal(self, &mut signal.signal)
     }
 }
 
+impl SignalReciever<ClickSignal, (ReconstructCallback<LineTarget>, SelectStateCallback)> for Text {
+    fn take_signal(&mut self, signal: &mut ClickSignal) -> (ReconstructCallback<LineTarget>, SelectStateCallback) {
+        if signal.button != MouseButton::Left {
+            return (ReconstructCallback::new(false), SelectStateCallback::NoChange)
+        }
+
+        let selection = match self.pos_to_select_point(signal.norm_posx, signal.norm_posy) {
+            Some(drag_point) => match signal.event {
+                ClickEvent::DoubleClick => word_selection_at(&self.text, drag_point.char_index),
+                ClickEvent::TripleClick => line_selection_at(&self.text, drag_point.char_index),
+            },
+            None => None
+        };
+
+        match selection {
+            Some(selection) => {
+                self.select_state = SelectState::Selected(selection);
+                self.compute_selection_lines();
+                (ReconstructCallback::new(true), SelectStateCallback::Update(self.select_state.clone()))
+            },
+            None => (ReconstructCallback::new(false), SelectStateCallback::NoChange)
+        }
+    }
+}
+
+impl SignalReciever<ClickSignal, ReconstructCallback<LineTarget>> for Text {
+    fn take_signal(&mut self, signal: &mut ClickSignal) -> ReconstructCallback<LineTarget> {
+        let (reconstruct_callback, _) = self.take_signal(signal);
+        reconstruct_callback
+    }
+}
+
+ignore_signals!(Text: ReleasedElsewhereSignal, ScrollSignal, FlingSignal, PinchSignal, CharacterInputSignal, ImeSignal, TickSignal, KeySignal, ScaleFactorSignal);
+
 #[derive(Debug, Clone)]
 pub enum SelectStateCallback {
     NoChange,
@@ -459,18 +835,121 @@ impl GetPointBounds for Text {
     fn get_point_bounds(&self) -> Points {
         match self.size_mode {
             TextSizeMode::Fill(fill_target) => fill_target.into(),
-            TextSizeMode::FillWidth(sx, cx, ty) => todo!(),
+            TextSizeMode::FillWidth(sx, cx, ty) => Points {
+                p1x: cx-sx,
+                p1y: ty,
+                p2x: cx+sx,
+                p2y: self.get_bottom_y(),
+            },
             TextSizeMode::Unconstrained => Points::ZERO,
         }
     }
 }
 
+impl HitTest for Text {
+    fn hit_test(&self, x: f32, y: f32, _path: &mut Vec<usize>) -> bool {
+        match self.size_mode {
+            TextSizeMode::Unconstrained => false,
+            _ => self.get_point_bounds().contains(x, y)
+        }
+    }
+}
+
+impl<S, R> RouteSignal<S, R> for Text
+where
+    Text: SignalReciever<S, R>
+{
+    fn route_signal(&mut self, _path: &[usize], signal: &mut S) -> R {
+        self.take_signal(signal)
+    }
+}
+
 impl GetCenterPosition for Text {
     fn get_center_position(&self) -> (f32, f32) {
         match self.size_mode {
             TextSizeMode::Fill(fill) => (fill.cx, fill.cy),
-            TextSizeMode::FillWidth(sx, cx, ty) => todo!(),
-            TextSizeMode::Unconstrained => todo!()
+            TextSizeMode::FillWidth(_, cx, ty) => (cx, (ty+self.get_bottom_y())/2.0),
+            TextSizeMode::Unconstrained => (0.0, 0.0)
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Size, b: Size) {
+        assert!((a.width-b.width).abs() < 1e-5 && (a.height-b.height).abs() < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn unbounded_text_keeps_its_natural_size() {
+        assert_close(fit_aspect(0.25, 0.1, Constraints::UNBOUNDED), Size::new(0.4, 0.1));
+    }
+
+    #[test]
+    fn narrow_constraints_shrink_keeping_the_aspect() {
+        assert_close(fit_aspect(0.25, 0.1, Constraints::width(0.2)), Size::new(0.2, 0.05));
+    }
+
+    #[test]
+    fn short_constraints_shrink_keeping_the_aspect() {
+        assert_close(fit_aspect(0.25, 0.1, Constraints::new(1.0, 0.05)), Size::new(0.2, 0.05));
+    }
+
+    #[test]
+    fn empty_text_has_no_width() {
+        assert_close(fit_aspect(0.0, 0.1, Constraints::new(1.0, 0.05)), Size::new(0.0, 0.05));
+    }
+
+    #[test]
+    fn natural_height_follows_the_window_height() {
+        assert!((natural_height_norm(Text::DEFAULT_NATURAL_HEIGHT, DEFAULT_RESYP) - 24.0*2.0/720.0).abs() < 1e-6);
+        assert!((natural_height_norm(36.0, 1080) - 36.0*2.0/1080.0).abs() < 1e-6);
+        assert!((natural_height_norm(24.0, 360) - 2.0*natural_height_norm(24.0, 720)).abs() < 1e-6);
+    }
+
+    fn selection(start: usize, end: usize) -> Option<Selection> {
+        Some(Selection {
+            start,
+            end,
+        })
+    }
+
+    #[test]
+    fn word_selection_takes_the_word_around_the_index() {
+        assert_eq!(word_selection_at("hello big_world", 1), selection(0, 5));
+        assert_eq!(word_selection_at("hello big_world", 9), selection(6, 15));
+    }
+
+    #[test]
+    fn word_selection_groups_spaces_and_punctuation() {
+        assert_eq!(word_selection_at("a  b", 1), selection(1, 3));
+        assert_eq!(word_selection_at("a?!b", 2), selection(1, 3));
+    }
+
+    #[test]
+    fn word_selection_past_the_end_is_none() {
+        assert_eq!(word_selection_at("word", 4), None);
+        assert_eq!(word_selection_at("", 0), None);
+    }
+
+    #[test]
+    fn line_selection_stops_at_newlines() {
+        let text = "first\nsecond line\nthird";
+        assert_eq!(line_selection_at(text, 2), selection(0, 5));
+        assert_eq!(line_selection_at(text, 8), selection(6, 17));
+        assert_eq!(line_selection_at(text, 20), selection(18, 23));
+    }
+
+    #[test]
+    fn line_selection_of_an_empty_line_is_none() {
+        assert_eq!(line_selection_at("a\n\nb", 2), None);
+        assert_eq!(line_selection_at("abc", 3), None);
+    }
+
+    #[test]
+    fn natural_height_survives_a_zero_height_window() {
+        assert!(natural_height_norm(24.0, 0).is_finite());
+    }
 }
\ No newline at end of file
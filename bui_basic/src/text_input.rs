@@ -2,32 +2,88 @@ use std::sync::{Arc, Mutex};
 
 use bui::{ttf::CachedFace, rect::Points, line::LineRaw};
 
-use crate::{text::Text, signal::{SignalReciever, CursorMovedSignal, ResizedSignal, ReconstructCallback, MouseLeftUpSignal, CharacterInputSignal, MouseLeftDownSignal}, construct::{LineTarget, Construct, StandardConstructTarget}, containers::{Fill, GetPointBounds, FillWidth, TranslateY, TranslateX, Init}};
+use crate::{text::Text, signal::{SignalReciever, ignore_signals, Propagating, Phase, CursorMovedSignal, ResizedSignal, ReconstructCallback, MouseLeftUpSignal, ReleasedElsewhereSignal, CharacterInputSignal, MouseLeftDownSignal, MouseButtonSignal, MouseButton, ButtonState, ImeSignal}, construct::{LineTarget, Freeform2DCapsuleTarget, ShapeTarget, Construct, StandardConstructTarget, capsule, outline_capsules}, containers::{Fill, GetPointBounds, FillWidth, TranslateY, TranslateX, Init}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, RetainedId}, style::{ConstructStyled, StyledLineTarget, Theme}, draw_list::DrawList, platform::{SharedPlatformInput, InputType, default_platform_input, report_platform_input_error}};
 
 #[derive(Debug, Clone)]
-pub struct TextInput {
-    text: Text,
-    bounds: Points,
+struct Preedit {
+    text: String,
+    // byte offsets into text
+    cursor: Option<(usize, usize)>,
+}
+
+// Whether a text field has the keyboard, showing the soft keyboard while it does.
+// A left release over the field takes focus, one anywhere else gives it up.
+#[derive(Debug, Clone)]
+pub struct KeyboardFocus {
     hovered: bool,
     focused: bool,
+    input_type: InputType,
+    platform_input: SharedPlatformInput,
 }
 
-impl TextInput {
-    pub fn new<T: Into<String>>(text: T, face: Arc<Mutex<CachedFace>>) -> Self {
-        Self::new_with_res(text, face, 1.0, 1.0)
+impl KeyboardFocus {
+    pub fn new() -> Self {
+        Self::new_with_platform_input(default_platform_input())
     }
 
-    pub fn new_with_res<T: Into<String>>(text: T, face: Arc<Mutex<CachedFace>>, resx: f32, resy: f32) -> Self {
+    pub fn new_with_platform_input(platform_input: SharedPlatformInput) -> Self {
         Self {
-            text: Text::new_with_res(text, face, resx, resy),
-            bounds: Points::ZERO,
             hovered: false,
             focused: false,
+            input_type: InputType::Text,
+            platform_input,
         }
     }
 
-    pub fn get_text(&self) -> &String {
-        self.text.get_text()
+    pub fn set_platform_input(&mut self, platform_input: SharedPlatformInput) {
+        self.platform_input = platform_input;
+    }
+
+    pub fn set_input_type(&mut self, input_type: InputType) {
+        self.input_type = input_type;
+        if self.focused {
+            report_platform_input_error(self.platform_input.lock().unwrap().set_input_type(input_type));
+        }
+    }
+
+    pub fn get_input_type(&self) -> InputType {
+        self.input_type
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+
+    pub fn focus(&mut self) {
+        self.keyboard_focus.focus();
+        self.report_caret_rect();
+    }
+
+    pub fn unfocus(&mut self) {
+        self.keyboard_focus.unfocus();
+        self.drop_preedit();
+    }
+
+    fn drop_preedit(&mut self) {
+        if self.preedit.take().is_some() {
+            self.refresh_text();
+        }
+    }
+
+    fn apply_focus_change(&mut self, focus_callback: &TextInputFocusCallback) {
+        match focus_callback {
+            TextInputFocusCallback::Focused => self.report_caret_rect(),
+            TextInputFocusCallback::Unfocused => self.drop_preedit(),
+            TextInputFocusCallback::None => {}
+        }
     }
 }
 
@@ -37,30 +93,77 @@ impl Init for TextInput {
 
 impl Construct<LineTarget> for TextInput {
     fn construct(&self) -> LineTarget {
-        let mut target = self.text.construct();
-        let border_lines = vec![
-            LineRaw {
-                p1: [self.bounds.p1x, self.bounds.p2y],
-                p2: [self.bounds.p1x, self.bounds.p1y],
-            },
-            LineRaw {
-                p1: [self.bounds.p1x, self.bounds.p1y],
-                p2: [self.bounds.p2x, self.bounds.p1y],
-            },
-            LineRaw {
-                p1: [self.bounds.p2x, self.bounds.p1y],
-                p2: [self.bounds.p2x, self.bounds.p2y],
-            },
-            LineRaw {
-                p1: [self.bounds.p2x, self.bounds.p2y],
-                p2: [self.bounds.p1x, self.bounds.p2y],
-            }
-        ];
-        target.append(LineTarget(border_lines));
+        let mut target: LineTarget = self.text.construct();
+        target.append(LineTarget(Vec::from(self.border_lines())));
+        if let Some(underline) = self.preedit_underline() {
+            target.append(LineTarget(vec![underline]));
+        }
+        target
+    }
+}
+
+impl Construct<Freeform2DCapsuleTarget> for TextInput {
+    fn construct(&self) -> Freeform2DCapsuleTarget {
+        let mut target: Freeform2DCapsuleTarget = self.text.construct();
+        target.append(Freeform2DCapsuleTarget(outline_capsules(self.bounds, self.border_radius)));
+        if let Some(underline) = self.preedit_underline() {
+            target.0.push(capsule(underline.p1, underline.p2, self.border_radius/2.0));
+        }
+        target
+    }
+}
+
+impl Construct<ShapeTarget> for TextInput {
+    fn construct(&self) -> ShapeTarget {
+        ShapeTarget::new(self.construct(), self.construct())
+    }
+}
+
+impl Construct<DrawList> for TextInput {
+    fn construct(&self) -> DrawList {
+        DrawList::from(<Self as Construct<ShapeTarget>>::construct(self))
+    }
+}
+
+impl ConstructStyled<StyledLineTarget> for TextInput {
+    fn construct_styled(&self, theme: &Theme) -> StyledLineTarget {
+        let mut target: StyledLineTarget = self.text.construct_styled(theme);
+        let border = theme.text_input_border.for_focus(self.is_focused());
+        for line in self.border_lines() {
+            target.push_line(line, border);
+        }
+        if let Some(underline) = self.preedit_underline() {
+            target.push_line(underline, theme.preedit_underline);
+        }
         target
     }
 }
 
+impl ConstructStyled<DrawList> for TextInput {
+    fn construct_styled(&self, theme: &Theme) -> DrawList {
+        let mut target: DrawList = self.text.construct_styled(theme);
+        target.set_stroke(theme.text_input_border.for_focus(self.is_focused()));
+        target.push_lines(Vec::from(self.border_lines()));
+        if let Some(underline) = self.preedit_underline() {
+            target.set_stroke(theme.preedit_underline);
+            target.push_line(underline);
+        }
+        target
+    }
+}
+
+impl RetainedConstruct<LineTarget> for TextInput {
+    fn construct_retained(&self, target: &mut RetainedTarget<LineTarget>) {
+        target.write_slot(&self.retained_id, self.text.get_dirty().construct, || self.construct());
+    }
+}
+
+impl RetainedConstruct<Freeform2DCapsuleTarget> for TextInput {
+    fn construct_retained(&self, target: &mut RetainedTarget<Freeform2DCapsuleTarget>) {
+        target.write_slot(&self.retained_id, self.text.get_dirty().construct, || self.construct());
+    }
+}
+
 impl Fill for TextInput {
     fn fill(&mut self, fill_target: bui::rect::SizeAndCenter) {
         self.text.fill(fill_target);
@@ -69,8 +172,34 @@ impl Fill for TextInput {
 }
 
 impl FillWidth for TextInput {
-    fn fill_width(&mut self, _sx: f32, _cx: f32, _ty: f32) -> f32 {
-        todo!()
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        let by = self.text.fill_width(sx, cx, ty);
+        self.bounds = self.text.get_point_bounds();
+        by
+    }
+}
+
+impl Measure for TextInput {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.text.measure(constraints)
+    }
+}
+
+impl Dirty for TextInput {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.text.get_dirty()
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        let height_change = self.text.update_layout();
+        if height_change != 0.0 {
+            self.bounds = self.text.get_point_bounds();
+        }
+        height_change
+    }
+
+    fn clear_dirty(&mut self) {
+        self.text.clear_dirty();
     }
 }
 
@@ -90,19 +219,43 @@ impl TranslateY for TextInput {
     }
 }
 
+impl GetPointBounds for TextInput {
+    fn get_point_bounds(&self) -> Points {
+        self.bounds
+    }
+}
+
+impl HitTest for TextInput {
+    fn hit_test(&self, x: f32, y: f32, _path: &mut Vec<usize>) -> bool {
+        self.bounds.contains(x, y)
+    }
+}
+
+impl<S, R> RouteSignal<S, R> for TextInput
+where
+    TextInput: SignalReciever<S, R>
+{
+    fn route_signal(&mut self, _path: &[usize], signal: &mut S) -> R {
+        self.take_signal(signal)
+    }
+}
+
 impl SignalReciever<ResizedSignal, ReconstructCallback<LineTarget>> for TextInput {
     fn take_signal(&mut self, signal: &mut ResizedSignal) -> ReconstructCallback<LineTarget> {
-        self.text.take_signal(signal)
+        let callback = self.text.take_signal(signal);
+        self.bounds = self.text.get_point_bounds();
+        callback
     }
 }
 
 impl<R: Default> SignalReciever<CursorMovedSignal, R> for TextInput {
     fn take_signal(&mut self, signal: &mut CursorMovedSignal) -> R {
-        self.hovered = self.bounds.contains(signal.norm_posx, signal.norm_posy);
+        self.keyboard_focus.set_hovered(self.bounds.contains(signal.norm_posx, signal.norm_posy));
         R::default()
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TextInputFocusCallback {
     None,
     Unfocused,
@@ -111,46 +264,45 @@ pub enum TextInputFocusCallback {
 
 impl SignalReciever<MouseLeftUpSignal, TextInputFocusCallback> for TextInput {
     fn take_signal(&mut self, _signal: &mut MouseLeftUpSignal) -> TextInputFocusCallback {
-        self.focused = self.hovered;
-        if self.hovered {
-            if self.focused {
-                TextInputFocusCallback::None
-            } else {
-                self.focused = true;
-                // show keyboard
-                #[cfg(target_os="android")]
-                {
-                    let ctx = ndk_context::android_context();
-                    let vm = unsafe { jni::JavaVM::from_raw(ctx.vm().cast()) }.unwrap();
-                    let env = vm.attach_current_thread().unwrap();
-                    
-                    let context_class = env.find_class("android/content/Context").unwrap();
-                    let input_method_service_name = env.get_static_field(context_class, "INPUT_METHOD_SERVICE", "Ljava/lang/String;").unwrap();
-
-                    let input_method_manager = env.call_method(ctx.context().cast(), "getSystemService", "(Ljava/lang/String;)Ljava/lang/Object;", &[input_method_service_name]).unwrap().l().unwrap();
-                    
-                    env.call_method(input_method_manager, "toggleSoftInput", "(II)V", &[2.into(), 0.into()]).unwrap();
-                }
-                TextInputFocusCallback::Focused
-            }
-        } else {
-            if self.focused {
-                self.focused = false;
-                TextInputFocusCallback::Unfocused
-            } else {
-                TextInputFocusCallback::None
-            }
-        }
+        let focus_callback = self.keyboard_focus.release();
+        self.apply_focus_change(&focus_callback);
+        focus_callback
+    }
+}
+
+// only sent by a PointerRouter, plain dispatch hands the release itself to every field
+impl SignalReciever<ReleasedElsewhereSignal, TextInputFocusCallback> for TextInput {
+    fn take_signal(&mut self, _signal: &mut ReleasedElsewhereSignal) -> TextInputFocusCallback {
+        let focus_callback = self.keyboard_focus.release_elsewhere();
+        self.apply_focus_change(&focus_callback);
+        focus_callback
+    }
+}
+
+impl<R: Default> SignalReciever<ReleasedElsewhereSignal, R> for TextInput {
+    default fn take_signal(&mut self, signal: &mut ReleasedElsewhereSignal) -> R {
+        let _: TextInputFocusCallback = self.take_signal(signal);
+        R::default()
+    }
+}
+
+impl<C> SignalReciever<ReleasedElsewhereSignal, ReconstructCallback<C>> for TextInput {
+    fn take_signal(&mut self, signal: &mut ReleasedElsewhereSignal) -> ReconstructCallback<C> {
+        let focus_callback: TextInputFocusCallback = self.take_signal(signal);
+        ReconstructCallback::new(focus_callback == TextInputFocusCallback::Unfocused)
     }
 }
 
 impl SignalReciever<CharacterInputSignal, ReconstructCallback<LineTarget>> for TextInput {
     fn take_signal(&mut self, signal: &mut CharacterInputSignal) -> ReconstructCallback<LineTarget> {
-        if self.focused {
+        if self.is_focused() {
             match signal.input {
-                '\u{8}' => self.text.backspace(),
-                _ => self.text.set_text(format!("{}{}", self.text.get_text(), signal.input))
+                '\u{8}' => {
+                    self.committed.pop();
+                },
+                _ => self.committed.push(signal.input)
             }
+            self.refresh_text();
             ReconstructCallback::new(true)
         } else {
             ReconstructCallback::new(false)
@@ -158,8 +310,244 @@ impl SignalReciever<CharacterInputSignal, ReconstructCallback<LineTarget>> for T
     }
 }
 
-impl<R: Default> SignalReciever<MouseLeftDownSignal, R> for TextInput {
-    fn take_signal(&mut self, _signal: &mut MouseLeftDownSignal) -> R {
+impl SignalReciever<ImeSignal, ReconstructCallback<LineTarget>> for TextInput {
+    fn take_signal(&mut self, signal: &mut ImeSignal) -> ReconstructCallback<LineTarget> {
+        if !self.is_focused() {
+            return ReconstructCallback::new(false);
+        }
+
+        match signal {
+            ImeSignal::PreeditStart => {
+                self.preedit = Some(Preedit {
+                    text: String::new(),
+                    cursor: None,
+                });
+            },
+            ImeSignal::PreeditUpdate { text, cursor } => {
+                self.preedit = if text.is_empty() {
+                    None
+                } else {
+                    Some(Preedit {
+                        text: text.clone(),
+                        cursor: *cursor,
+                    })
+                };
+                self.refresh_text();
+                self.report_caret_rect();
+            },
+            ImeSignal::Commit(text) => {
+                self.committed.push_str(text);
+                self.preedit = None;
+                self.refresh_text();
+                self.report_caret_rect();
+            },
+            ImeSignal::PreeditEnd => {
+                self.preedit = None;
+                self.refresh_text();
+            }
+        }
+        ReconstructCallback::new(true)
+    }
+}
+
+ignore_signals!(TextInput: MouseLeftDownSignal, ScrollSignal, FlingSignal, PinchSignal, ClickSignal, ScaleFactorSignal, TickSignal, KeySignal);
+
+impl<R: Default> SignalReciever<MouseButtonSignal, R> for TextInput {
+    fn take_signal(&mut self, signal: &mut MouseButtonSignal) -> R {
+        if let (MouseButton::Left, ButtonState::Released) = (signal.button, signal.state) {
+            let _: TextInputFocusCallback = self.take_signal(&mut MouseLeftUpSignal());
+        }
         R::default()
     }
+}
+
+impl<R: Default> SignalReciever<Propagating<CursorMovedSignal>, R> for TextInput {
+    fn take_signal(&mut self, signal: &mut Propagating<CursorMovedSignal>) -> R {
+        if signal.get_phase() == Phase::Capture {
+            return R::default();
+        }
+        self.take_signal(&mut signal.signal)
+    }
+}
+
+impl<R: Default> SignalReciever<Propagating<MouseLeftDownSignal>, R> for TextInput {
+    fn take_signal(&mut self, signal: &mut Propagating<MouseLeftDownSignal>) -> R {
+        if signal.get_phase() == Phase::Capture {
+            return R::default();
+        }
+        self.take_signal(&mut signal.signal)
+    }
+}
+
+impl<R: Default> SignalReciever<Propagating<MouseLeftUpSignal>, R> for TextInput {
+    fn take_signal(&mut self, signal: &mut Propagating<MouseLeftUpSignal>) -> R {
+        if signal.get_phase() == Phase::Capture {
+            return R::default();
+        }
+        let focus_callback: TextInputFocusCallback = self.take_signal(&mut signal.signal);
+        if let TextInputFocusCallback::Focused = focus_callback {
+            signal.set_handled();
+        }
+        R::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{column::VecColumn, hit_test::PointerRouter, platform::{RecordingPlatformInput, PlatformInputCall}, test_util::Block};
+
+    type Reconstruct = ReconstructCallback<LineTarget>;
+
+    // drives its KeyboardFocus the way TextInput does, which needs a font face to build
+    struct Field {
+        block: Block,
+        keyboard_focus: KeyboardFocus,
+    }
+
+    impl HitTest for Field {
+        fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+            self.block.hit_test(x, y, path)
+        }
+    }
+
+    impl<S, R> RouteSignal<S, R> for Field
+    where
+        Field: SignalReciever<S, R>
+    {
+        fn route_signal(&mut self, _path: &[usize], signal: &mut S) -> R {
+            self.take_signal(signal)
+        }
+    }
+
+    impl<S, R: Default> SignalReciever<S, R> for Field {
+        default fn take_signal(&mut self, _signal: &mut S) -> R {
+            R::default()
+        }
+    }
+
+    impl<R: Default> SignalReciever<CursorMovedSignal, R> for Field {
+        fn take_signal(&mut self, signal: &mut CursorMovedSignal) -> R {
+            self.keyboard_focus.set_hovered(self.block.points.contains(signal.norm_posx, signal.norm_posy));
+            R::default()
+        }
+    }
+
+    impl<R: Default> SignalReciever<MouseLeftUpSignal, R> for Field {
+        fn take_signal(&mut self, _signal: &mut MouseLeftUpSignal) -> R {
+            self.keyboard_focus.release();
+            R::default()
+        }
+    }
+
+    impl<R: Default> SignalReciever<ReleasedElsewhereSignal, R> for Field {
+        fn take_signal(&mut self, _signal: &mut ReleasedElsewhereSignal) -> R {
+            self.keyboard_focus.release_elsewhere();
+            R::default()
+        }
+    }
+
+    fn recorder() -> Arc<Mutex<RecordingPlatformInput>> {
+        Arc::new(Mutex::new(RecordingPlatformInput::new()))
+    }
+
+    // two fields sharing one keyboard, one above and one below the middle of the window
+    fn fields(recorder: &Arc<Mutex<RecordingPlatformInput>>) -> VecColumn<Field> {
+        let field = |p1y, p2y| {
+            let platform_input: SharedPlatformInput = recorder.clone();
+            Field {
+                block: Block::at(-0.5, p1y, 0.5, p2y),
+                keyboard_focus: KeyboardFocus::new_with_platform_input(platform_input),
+            }
+        };
+        VecColumn::from(vec![field(0.6, 0.2), field(-0.2, -0.6)])
+    }
+
+    fn moved(x: f32, y: f32) -> CursorMovedSignal {
+        CursorMovedSignal {
+            pixel_posx: 0.0,
+            pixel_posy: 0.0,
+            norm_posx: x,
+            norm_posy: y,
+        }
+    }
+
+    fn keyboard_calls(recorder: &Arc<Mutex<RecordingPlatformInput>>) -> Vec<&'static str> {
+        recorder.lock().unwrap().get_calls().iter().filter_map(|call| match call {
+            PlatformInputCall::ShowKeyboard => Some("show"),
+            PlatformInputCall::HideKeyboard => Some("hide"),
+            _ => None
+        }).collect()
+    }
+
+    fn click<T>(target: &mut T, x: f32, y: f32)
+    where
+        T: SignalReciever<CursorMovedSignal, Reconstruct>+SignalReciever<MouseLeftDownSignal, Reconstruct>+SignalReciever<MouseLeftUpSignal, Reconstruct>
+    {
+        let _: Reconstruct = target.take_signal(&mut moved(x, y));
+        let _: Reconstruct = target.take_signal(&mut MouseLeftDownSignal());
+        let _: Reconstruct = target.take_signal(&mut MouseLeftUpSignal());
+    }
+
+    #[test]
+    fn release_over_the_field_focuses_and_anywhere_else_unfocuses() {
+        let recorder = recorder();
+        let platform_input: SharedPlatformInput = recorder.clone();
+        let mut keyboard_focus = KeyboardFocus::new_with_platform_input(platform_input);
+
+        keyboard_focus.set_hovered(true);
+        assert_eq!(keyboard_focus.release(), TextInputFocusCallback::Focused);
+        assert_eq!(keyboard_focus.release(), TextInputFocusCallback::None);
+        keyboard_focus.set_hovered(false);
+        assert_eq!(keyboard_focus.release(), TextInputFocusCallback::Unfocused);
+        assert_eq!(keyboard_focus.release_elsewhere(), TextInputFocusCallback::None);
+
+        assert_eq!(keyboard_calls(&recorder), vec!["show", "hide"]);
+        assert!(!recorder.lock().unwrap().is_keyboard_visible());
+    }
+
+    #[test]
+    fn plain_click_outside_hides_the_keyboard() {
+        let recorder = recorder();
+        let mut fields = fields(&recorder);
+        click(&mut fields, 0.0, 0.4);
+        click(&mut fields, 0.0, 0.0);
+
+        assert_eq!(keyboard_calls(&recorder), vec!["show", "hide"]);
+        assert!(!recorder.lock().unwrap().is_keyboard_visible());
+    }
+
+    #[test]
+    fn routed_click_outside_hides_the_keyboard() {
+        let recorder = recorder();
+        let mut router = PointerRouter::new(fields(&recorder));
+        click(&mut router, 0.0, 0.4);
+        click(&mut router, 0.0, 0.0);
+
+        assert_eq!(keyboard_calls(&recorder), vec!["show", "hide"]);
+        assert!(!router.get_child_mut().get_children_mut()[0].keyboard_focus.is_focused());
+        assert!(!recorder.lock().unwrap().is_keyboard_visible());
+    }
+
+    #[test]
+    fn routed_click_on_another_field_moves_the_focus() {
+        let recorder = recorder();
+        let mut router = PointerRouter::new(fields(&recorder));
+        click(&mut router, 0.0, 0.4);
+        click(&mut router, 0.0, -0.4);
+
+        // the first field lets go before the second one asks for the keyboard
+        assert_eq!(keyboard_calls(&recorder), vec!["show", "hide", "show"]);
+        assert!(recorder.lock().unwrap().is_keyboard_visible());
+    }
+
+    #[test]
+    fn routed_click_on_the_focused_field_keeps_the_keyboard() {
+        let recorder = recorder();
+        let mut router = PointerRouter::new(fields(&recorder));
+        click(&mut router, 0.0, 0.4);
+        click(&mut router, 0.1, 0.3);
+
+        assert_eq!(keyboard_calls(&recorder), vec!["show"]);
+    }
 }
\ No newline at end of file
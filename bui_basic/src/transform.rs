@@ -0,0 +1,526 @@
+use bui::rect::{SizeAndCenter, Points};
+
+use crate::{construct::{Construct, LineTarget, Freeform2DCapsuleTarget, ShapeTarget}, containers::{Init, Fill, FillWidth, TranslateX, TranslateY, GetPointBounds}, signal::{SignalReciever, PointerPosition}, hit_test::{HitTest, RouteSignal}, measure::{Measure, Constraints, SizeHint}, dirty::{Dirty, DirtyFlags}, retained::{RetainedConstruct, RetainedTarget, RetainedId, IntoPrimitives}, style::{ConstructStyled, Theme, StyledLineTarget}, draw_list::{DrawList, DrawCommand}};
+
+// Maps (x, y) to (a*x+c*y+tx, b*x+d*y+ty) in normalized coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl Affine {
+    pub const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        tx: 0.0,
+        ty: 0.0,
+    };
+
+    pub const fn translate(tx: f32, ty: f32) -> Self {
+        Self {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            tx,
+            ty,
+        }
+    }
+
+    pub const fn scale(sx: f32, sy: f32) -> Self {
+        Self {
+            a: sx,
+            b: 0.0,
+            c: 0.0,
+            d: sy,
+            tx: 0.0,
+            ty: 0.0,
+        }
+    }
+
+    // counterclockwise. Normalized x and y are usually stretched differently, see rotate_with_aspect.
+    pub fn rotate(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            tx: 0.0,
+            ty: 0.0,
+        }
+    }
+
+    // rotates in pixel space so shapes are not sheared, aspect is width over height of the window
+    pub fn rotate_with_aspect(radians: f32, aspect: f32) -> Self {
+        Self::scale(1.0/aspect, 1.0)
+            .then(&Self::rotate(radians))
+            .then(&Self::scale(aspect, 1.0))
+    }
+
+    // applies self to the point first, then other
+    pub fn then(&self, other: &Affine) -> Self {
+        Self {
+            a: other.a*self.a+other.c*self.b,
+            b: other.b*self.a+other.d*self.b,
+            c: other.a*self.c+other.c*self.d,
+            d: other.b*self.c+other.d*self.d,
+            tx: other.a*self.tx+other.c*self.ty+other.tx,
+            ty: other.b*self.tx+other.d*self.ty+other.ty,
+        }
+    }
+
+    // the same transform with (cx, cy) as its origin
+    pub fn about(&self, cx: f32, cy: f32) -> Self {
+        Self::translate(-cx, -cy)
+            .then(self)
+            .then(&Self::translate(cx, cy))
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.a*self.d-self.b*self.c
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        Some(Self {
+            a: self.d/det,
+            b: -self.b/det,
+            c: -self.c/det,
+            d: self.a/det,
+            tx: (self.c*self.ty-self.d*self.tx)/det,
+            ty: (self.b*self.tx-self.a*self.ty)/det,
+        })
+    }
+
+    pub fn apply(&self, p: [f32; 2]) -> [f32; 2] {
+        [self.a*p[0]+self.c*p[1]+self.tx, self.b*p[0]+self.d*p[1]+self.ty]
+    }
+
+    // how much lengths grow on average, used for radii
+    pub fn length_scale(&self) -> f32 {
+        self.determinant().abs().sqrt()
+    }
+
+    // the axis aligned bounds of the transformed rectangle
+    pub fn apply_points(&self, points: Points) -> Points {
+        let corners = [
+            self.apply([points.p1x, points.p1y]),
+            self.apply([points.p2x, points.p1y]),
+            self.apply([points.p1x, points.p2y]),
+            self.apply([points.p2x, points.p2y]),
+        ];
+        let mut bounds = Points {
+            p1x: corners[0][0],
+            p1y: corners[0][1],
+            p2x: corners[0][0],
+            p2y: corners[0][1],
+        };
+        for corner in &corners[1..] {
+            bounds.p1x = bounds.p1x.min(corner[0]);
+            bounds.p2x = bounds.p2x.max(corner[0]);
+            bounds.p1y = bounds.p1y.max(corner[1]);
+            bounds.p2y = bounds.p2y.min(corner[1]);
+        }
+        bounds
+    }
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+// Construct targets whose geometry can be transformed after construction.
+pub trait TransformTarget {
+    fn transform(&mut self, affine: &Affine);
+}
+
+impl TransformTarget for LineTarget {
+    fn transform(&mut self, affine: &Affine) {
+        for line in &mut self.0 {
+            line.p1 = affine.apply(line.p1);
+            line.p2 = affine.apply(line.p2);
+        }
+    }
+}
+
+impl TransformTarget for Freeform2DCapsuleTarget {
+    fn transform(&mut self, affine: &Affine) {
+        let length_scale = affine.length_scale();
+        for capsule in &mut self.0 {
+            capsule.p1 = affine.apply(capsule.p1);
+            capsule.p2 = affine.apply(capsule.p2);
+            capsule.r1 *= length_scale;
+            capsule.r2 *= length_scale;
+        }
+    }
+}
+
+impl TransformTarget for ShapeTarget {
+    fn transform(&mut self, affine: &Affine) {
+        self.lines.transform(affine);
+        self.capsules.transform(affine);
+    }
+}
+
+// stroke widths are in pixels and stay as they are
+impl TransformTarget for StyledLineTarget {
+    fn transform(&mut self, affine: &Affine) {
+        for styled_line in &mut self.0 {
+            styled_line.line.p1 = affine.apply(styled_line.line.p1);
+            styled_line.line.p2 = affine.apply(styled_line.line.p2);
+        }
+    }
+}
+
+// clips become the bounds of the transformed clip
+impl TransformTarget for DrawList {
+    fn transform(&mut self, affine: &Affine) {
+        let length_scale = affine.length_scale();
+        for command in &mut self.0 {
+            match command {
+                DrawCommand::Line(line) => {
+                    line.p1 = affine.apply(line.p1);
+                    line.p2 = affine.apply(line.p2);
+                },
+                DrawCommand::Capsule(capsule) => {
+                    capsule.p1 = affine.apply(capsule.p1);
+                    capsule.p2 = affine.apply(capsule.p2);
+                    capsule.r1 *= length_scale;
+                    capsule.r2 *= length_scale;
+                },
+                DrawCommand::PushClip(clip) => *clip = affine.apply_points(*clip),
+                DrawCommand::PopClip | DrawCommand::SetStroke(_) => {}
+            }
+        }
+    }
+}
+
+// Draws the child through an affine transform and maps pointer positions back into the child's space.
+// The child is laid out and measured untransformed. Translating the container moves the transform, not the child.
+// Only normalized positions are mapped, pixel positions stay in window pixels. Children must not compare
+// those with their own bounds, they are only good for distances on the screen like click slop.
+#[derive(Debug, Clone)]
+pub struct Transform<T> {
+    child: T,
+    affine: Affine,
+    // None when the transform collapses the child, which then cannot be hit
+    inverse: Option<Affine>,
+    transform_dirty: bool,
+    retained_id: RetainedId,
+}
+
+impl<T> Transform<T> {
+    pub fn new(child: T, affine: Affine) -> Self {
+        Self {
+            child,
+            affine,
+            inverse: affine.inverse(),
+            transform_dirty: true,
+            retained_id: RetainedId::new(),
+        }
+    }
+
+    pub fn get_child(&self) -> &T {
+        &self.child
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut T {
+        &mut self.child
+    }
+
+    pub fn get_affine(&self) -> &Affine {
+        &self.affine
+    }
+
+    pub fn set_affine(&mut self, affine: Affine) {
+        self.affine = affine;
+        self.inverse = affine.inverse();
+        self.transform_dirty = true;
+    }
+
+    // child space position of a normalized window position
+    pub fn to_child(&self, p: [f32; 2]) -> Option<[f32; 2]> {
+        self.inverse.map(|inverse| inverse.apply(p))
+    }
+
+    // gives the child the signal with its pointer position in child space and restores it afterwards
+    fn take_mapped_signal<S, R>(&mut self, signal: &mut S, take: impl FnOnce(&mut T, &mut S) -> R) -> R {
+        let position = signal.get_norm_position();
+        if let Some(position) = position {
+            signal.set_norm_position(self.to_child(position).unwrap_or([f32::NAN, f32::NAN]));
+        }
+        let callback = take(&mut self.child, signal);
+        if let Some(position) = position {
+            signal.set_norm_position(position);
+        }
+        callback
+    }
+}
+
+impl<T: Init> Init for Transform<T> {
+    fn init(&mut self) {
+        self.child.init();
+    }
+}
+
+impl<T: Fill> Fill for Transform<T> {
+    fn fill(&mut self, fill_target: SizeAndCenter) {
+        self.transform_dirty = true;
+        self.child.fill(fill_target);
+    }
+}
+
+impl<T: FillWidth> FillWidth for Transform<T> {
+    fn fill_width(&mut self, sx: f32, cx: f32, ty: f32) -> f32 {
+        self.transform_dirty = true;
+        self.child.fill_width(sx, cx, ty)
+    }
+}
+
+impl<T: Measure> Measure for Transform<T> {
+    fn measure(&self, constraints: Constraints) -> SizeHint {
+        self.child.measure(constraints)
+    }
+}
+
+impl<T: Dirty> Dirty for Transform<T> {
+    fn get_dirty(&self) -> DirtyFlags {
+        self.child.get_dirty().or(DirtyFlags::new(false, self.transform_dirty))
+    }
+
+    fn update_layout(&mut self) -> f32 {
+        self.child.update_layout()
+    }
+
+    fn clear_dirty(&mut self) {
+        self.transform_dirty = false;
+        self.child.clear_dirty();
+    }
+}
+
+impl<T> TranslateX for Transform<T> {
+    fn translate_x(&mut self, dx: f32) {
+        let mut affine = self.affine;
+        affine.tx += dx;
+        self.set_affine(affine);
+    }
+}
+
+impl<T> TranslateY for Transform<T> {
+    fn translate_y(&mut self, dy: f32) {
+        let mut affine = self.affine;
+        affine.ty += dy;
+        self.set_affine(affine);
+    }
+}
+
+impl<T: GetPointBounds> GetPointBounds for Transform<T> {
+    fn get_point_bounds(&self) -> Points {
+        self.affine.apply_points(self.child.get_point_bounds())
+    }
+}
+
+impl<C: TransformTarget, T: Construct<C>> Construct<C> for Transform<T> {
+    fn construct(&self) -> C {
+        let mut target = self.child.construct();
+        target.transform(&self.affine);
+        target
+    }
+}
+
+impl<C: TransformTarget, T: ConstructStyled<C>> ConstructStyled<C> for Transform<T> {
+    fn construct_styled(&self, theme: &Theme) -> C {
+        let mut target = self.child.construct_styled(theme);
+        target.transform(&self.affine);
+        target
+    }
+}
+
+// the child's slots cannot be transformed in place, so the whole subtree shares one slot
+impl<C: IntoPrimitives+TransformTarget, T: Construct<C>+Dirty> RetainedConstruct<C> for Transform<T> {
+    fn construct_retained(&self, target: &mut RetainedTarget<C>) {
+        target.write_slot(&self.retained_id, self.get_dirty().construct, || self.construct());
+    }
+}
+
+impl<S, R, T: SignalReciever<S, R>> SignalReciever<S, R> for Transform<T> {
+    fn take_signal(&mut self, signal: &mut S) -> R {
+        self.take_mapped_signal(signal, |child, signal| child.take_signal(signal))
+    }
+}
+
+impl<T: HitTest> HitTest for Transform<T> {
+    fn hit_test(&self, x: f32, y: f32, path: &mut Vec<usize>) -> bool {
+        match self.to_child([x, y]) {
+            Some([x, y]) => self.child.hit_test(x, y, path),
+            None => false
+        }
+    }
+}
+
+impl<S, R, T: RouteSignal<S, R>> RouteSignal<S, R> for Transform<T> {
+    fn route_signal(&mut self, path: &[usize], signal: &mut S) -> R {
+        self.take_mapped_signal(signal, |child, signal| child.route_signal(path, signal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::{signal::{CursorMovedSignal, MouseLeftDownSignal}, test_util::Block};
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a-b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    fn assert_point(actual: [f32; 2], expected: [f32; 2]) {
+        assert_close(actual[0], expected[0]);
+        assert_close(actual[1], expected[1]);
+    }
+
+    // records the normalized pointer position of every signal it gets
+    #[derive(Default)]
+    struct Probe {
+        positions: Vec<Option<[f32; 2]>>,
+    }
+
+    impl<S, R: Default> SignalReciever<S, R> for Probe {
+        fn take_signal(&mut self, signal: &mut S) -> R {
+            self.positions.push(signal.get_norm_position());
+            R::default()
+        }
+    }
+
+    impl<S, R: Default> RouteSignal<S, R> for Probe {
+        fn route_signal(&mut self, _path: &[usize], signal: &mut S) -> R {
+            self.take_signal(signal)
+        }
+    }
+
+    fn cursor_moved(norm_posx: f32, norm_posy: f32) -> CursorMovedSignal {
+        CursorMovedSignal {
+            pixel_posx: 100.0,
+            pixel_posy: 200.0,
+            norm_posx,
+            norm_posy,
+        }
+    }
+
+    #[test]
+    fn then_applies_self_first() {
+        let translate = Affine::translate(1.0, 0.0);
+        let scale = Affine::scale(2.0, 3.0);
+        assert_point(translate.then(&scale).apply([0.0, 1.0]), [2.0, 3.0]);
+        assert_point(scale.then(&translate).apply([0.0, 1.0]), [1.0, 3.0]);
+    }
+
+    #[test]
+    fn about_keeps_the_origin_in_place() {
+        let rotate = Affine::rotate(FRAC_PI_2).about(1.0, 1.0);
+        assert_point(rotate.apply([1.0, 1.0]), [1.0, 1.0]);
+        assert_point(rotate.apply([2.0, 1.0]), [1.0, 2.0]);
+    }
+
+    #[test]
+    fn inverse_undoes_the_transform() {
+        let affine = Affine::rotate(0.3)
+            .then(&Affine::scale(2.0, 0.5))
+            .then(&Affine::translate(0.1, -0.2));
+        let inverse = affine.inverse().unwrap();
+        for p in [[0.0, 0.0], [1.0, -0.5], [-0.3, 0.8]] {
+            assert_point(inverse.apply(affine.apply(p)), p);
+            assert_point(affine.apply(inverse.apply(p)), p);
+        }
+        let identity = affine.then(&inverse);
+        for (actual, expected) in [identity.a, identity.b, identity.c, identity.d, identity.tx, identity.ty].into_iter().zip([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]) {
+            assert_close(actual, expected);
+        }
+    }
+
+    #[test]
+    fn singular_transforms_have_no_inverse() {
+        assert_eq!(Affine::scale(0.0, 1.0).inverse(), None);
+        assert_eq!(Affine::scale(2.0, 3.0).then(&Affine { a: 1.0, b: 2.0, c: 2.0, d: 4.0, tx: 0.5, ty: 0.0 }).inverse(), None);
+        assert_eq!(Affine::scale(f32::INFINITY, 1.0).inverse(), None);
+        assert!(Affine::IDENTITY.inverse().is_some());
+    }
+
+    #[test]
+    fn apply_points_bounds_the_transformed_corners() {
+        let points = Points {
+            p1x: -1.0,
+            p1y: 0.5,
+            p2x: 1.0,
+            p2y: -0.5,
+        };
+        let rotated = Affine::rotate(FRAC_PI_2).apply_points(points);
+        assert_close(rotated.p1x, -0.5);
+        assert_close(rotated.p1y, 1.0);
+        assert_close(rotated.p2x, 0.5);
+        assert_close(rotated.p2y, -1.0);
+
+        // flipping keeps p1 at the top left
+        let flipped = Affine::scale(-1.0, -2.0).then(&Affine::translate(0.5, 0.0)).apply_points(points);
+        assert_close(flipped.p1x, -0.5);
+        assert_close(flipped.p1y, 1.0);
+        assert_close(flipped.p2x, 1.5);
+        assert_close(flipped.p2y, -1.0);
+    }
+
+    #[test]
+    fn signals_reach_the_child_in_child_space() {
+        let mut transform = Transform::new(Probe::default(), Affine::scale(2.0, 2.0).then(&Affine::translate(0.5, 0.25)));
+        let mut signal = cursor_moved(1.5, 0.25);
+        let () = transform.take_signal(&mut signal);
+        let () = transform.route_signal(&[], &mut signal);
+        assert_eq!(transform.get_child().positions, vec![Some([0.5, 0.0]); 2]);
+        // the signal is handed back as it came, pixel positions are never touched
+        assert_eq!((signal.norm_posx, signal.norm_posy), (1.5, 0.25));
+        assert_eq!((signal.pixel_posx, signal.pixel_posy), (100.0, 200.0));
+    }
+
+    #[test]
+    fn signals_without_a_position_are_passed_unchanged() {
+        let mut transform = Transform::new(Probe::default(), Affine::translate(0.5, 0.0));
+        let () = transform.take_signal(&mut MouseLeftDownSignal());
+        assert_eq!(transform.get_child().positions, vec![None]);
+    }
+
+    #[test]
+    fn collapsed_child_gets_no_position() {
+        let mut transform = Transform::new(Probe::default(), Affine::scale(0.0, 1.0));
+        let () = transform.take_signal(&mut cursor_moved(0.0, 0.0));
+        let position = transform.get_child().positions[0].unwrap();
+        assert!(position[0].is_nan() && position[1].is_nan());
+    }
+
+    #[test]
+    fn hit_test_maps_into_child_space() {
+        let mut transform = Transform::new(Block::at(-0.5, 0.5, 0.5, -0.5), Affine::translate(0.5, 0.0));
+        let mut path = Vec::new();
+        assert!(transform.hit_test(0.9, 0.0, &mut path));
+        assert!(!transform.hit_test(-0.6, 0.0, &mut path));
+
+        transform.set_affine(Affine::scale(2.0, 2.0));
+        assert!(transform.hit_test(0.9, 0.9, &mut path));
+        assert!(!transform.hit_test(1.1, 0.0, &mut path));
+
+        transform.set_affine(Affine::scale(0.0, 1.0));
+        assert!(!transform.hit_test(0.0, 0.0, &mut path));
+    }
+}
\ No newline at end of file
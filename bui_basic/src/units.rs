@@ -0,0 +1,143 @@
+use crate::signal::{ResizedSignal, ScaleFactorSignal};
+
+pub const DEFAULT_RESXP: u32 = 1280;
+pub const DEFAULT_RESYP: u32 = 720;
+
+// the full window along either axis in normalized coordinates
+pub const WINDOW_NORM: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Px(f32),
+    // density independent pixels, multiplied by the scale factor to get pixels
+    Dp(f32),
+    Norm(f32),
+    // share of the length available along the same axis
+    Fraction(f32),
+}
+
+impl Length {
+    pub const ZERO: Self = Length::Norm(0.0);
+
+    pub fn to_norm_x(&self, units: &UnitContext, available: f32) -> f32 {
+        self.to_norm(units.resxp, units.scale_factor, available)
+    }
+
+    pub fn to_norm_y(&self, units: &UnitContext, available: f32) -> f32 {
+        self.to_norm(units.resyp, units.scale_factor, available)
+    }
+
+    fn to_norm(&self, res_p: u32, scale_factor: f32, available: f32) -> f32 {
+        match self {
+            Length::Px(size) => size*WINDOW_NORM/res_p.max(1) as f32,
+            Length::Dp(size) => size*scale_factor*WINDOW_NORM/res_p.max(1) as f32,
+            Length::Norm(size) => *size,
+            Length::Fraction(fraction) => fraction*available
+        }
+    }
+}
+
+// What lengths are converted against, kept up to date from ResizedSignal and ScaleFactorSignal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitContext {
+    pub resxp: u32,
+    pub resyp: u32,
+    pub scale_factor: f32,
+}
+
+impl UnitContext {
+    pub const DEFAULT: Self = Self::new(DEFAULT_RESXP, DEFAULT_RESYP, 1.0);
+
+    pub const fn new(resxp: u32, resyp: u32, scale_factor: f32) -> Self {
+        Self {
+            resxp,
+            resyp,
+            scale_factor,
+        }
+    }
+
+    pub fn resize(&mut self, signal: &ResizedSignal) {
+        self.resxp = signal.resxp;
+        self.resyp = signal.resyp;
+    }
+
+    pub fn rescale(&mut self, signal: &ScaleFactorSignal) {
+        self.scale_factor = signal.scale_factor;
+    }
+}
+
+impl Default for UnitContext {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a-b).abs() < 1e-6, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn pixels_are_a_share_of_the_window_along_their_axis() {
+        let units = UnitContext::DEFAULT;
+        assert_close(Length::Px(640.0).to_norm_x(&units, 0.0), 1.0);
+        assert_close(Length::Px(360.0).to_norm_y(&units, 0.0), 1.0);
+
+        let units = UnitContext::new(1920, 1080, 1.0);
+        assert_close(Length::Px(96.0).to_norm_x(&units, 0.0), 0.1);
+        assert_close(Length::Px(54.0).to_norm_y(&units, 0.0), 0.1);
+    }
+
+    #[test]
+    fn pixels_ignore_the_scale_factor() {
+        let units = UnitContext::new(1000, 1000, 2.0);
+        assert_close(Length::Px(50.0).to_norm_x(&units, 0.0), 0.1);
+    }
+
+    #[test]
+    fn dp_scale_with_the_scale_factor() {
+        assert_close(Length::Dp(50.0).to_norm_x(&UnitContext::new(1000, 1000, 1.0), 0.0), 0.1);
+        assert_close(Length::Dp(50.0).to_norm_x(&UnitContext::new(1000, 1000, 2.0), 0.0), 0.2);
+        assert_close(Length::Dp(50.0).to_norm_y(&UnitContext::new(1000, 500, 1.5), 0.0), 0.3);
+    }
+
+    #[test]
+    fn fractions_and_norm_ignore_the_window() {
+        let units = UnitContext::new(1920, 1080, 3.0);
+        assert_close(Length::Fraction(0.25).to_norm_x(&units, 1.6), 0.4);
+        assert_close(Length::Fraction(0.25).to_norm_y(&units, 0.8), 0.2);
+        assert_close(Length::Norm(0.3).to_norm_y(&units, 0.8), 0.3);
+        assert_close(Length::ZERO.to_norm_x(&units, 2.0), 0.0);
+    }
+
+    #[test]
+    fn zero_sized_window_gives_finite_lengths() {
+        let units = UnitContext::new(0, 0, 1.0);
+        assert!(Length::Px(10.0).to_norm_x(&units, 0.0).is_finite());
+        assert!(Length::Dp(10.0).to_norm_y(&units, 0.0).is_finite());
+    }
+
+    #[test]
+    fn resize_and_rescale_follow_the_signals() {
+        let mut units = UnitContext::default();
+        assert_eq!(units, UnitContext::DEFAULT);
+
+        units.resize(&ResizedSignal {
+            resxp: 500,
+            resyp: 250,
+            resx: 500.0,
+            resy: 250.0,
+        });
+        assert_eq!(units, UnitContext::new(500, 250, 1.0));
+        assert_close(Length::Px(25.0).to_norm_y(&units, 0.0), 0.2);
+
+        units.rescale(&ScaleFactorSignal {
+            scale_factor: 2.0,
+        });
+        assert_eq!(units, UnitContext::new(500, 250, 2.0));
+        assert_close(Length::Dp(25.0).to_norm_y(&units, 0.0), 0.4);
+    }
+}
\ No newline at end of file